@@ -42,16 +42,16 @@ func _on_back_pressed() -> void:
 	game_container.visible = false
 	menu_container.visible = true
 
-func _on_score_changed(new_score: int) -> void:
+func _on_score_changed(new_score: int, _tick: int) -> void:
 	if game_board:
 		var score_label = game_board.get_node("HUD/ScoreLabel")
 		if score_label:
 			score_label.text = "Score: %d" % new_score
 
-func _on_match_found(count: int) -> void:
+func _on_match_found(count: int, _tick: int) -> void:
 	print("Match found! Count: %d" % count)
 
-func _on_rotation_triggered() -> void:
+func _on_rotation_triggered(_tick: int) -> void:
 	print("Rotation triggered!")
 
 func _process(delta: float) -> void:
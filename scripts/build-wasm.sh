@@ -38,6 +38,6 @@ echo "Building for wasm32-unknown-emscripten..."
 echo "BINDGEN_EXTRA_CLANG_ARGS: $BINDGEN_EXTRA_CLANG_ARGS"
 
 # Build with nightly and build-std
-cargo +nightly build -Zbuild-std --release --target wasm32-unknown-emscripten
+cargo +nightly build -Zbuild-std --release --target wasm32-unknown-emscripten --features wasm
 
 echo "Build complete!"
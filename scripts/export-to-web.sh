@@ -100,7 +100,7 @@ build_wasm() {
     # Build for wasm32-unknown-emscripten using nightly with build-std
     # Using nothreads variant to avoid pthread size mismatch issues
     # The -Zbuild-std flag rebuilds std for the target, required for WASM
-    cargo +nightly build -Zbuild-std --release --target wasm32-unknown-emscripten
+    cargo +nightly build -Zbuild-std --release --target wasm32-unknown-emscripten --features wasm
 
     info "WASM library built at: target/wasm32-unknown-emscripten/release/"
 }
@@ -0,0 +1,113 @@
+//! Animation ownership - at most one animation drives a symbol node at a time
+//!
+//! Every symbol node animates on a tween of its own, claimed by the animation
+//! moving it. Claiming a node that is still animating stops the old animation
+//! first and puts the node back at rest: in its cell, upright and at full
+//! size. So a rotation that starts while a fall is still landing can't leave a
+//! symbol half-turned between two cells, and killing animations never leaves a
+//! node stretched or shrunk. Animations call back on a timer of their own, so
+//! cutting one node's tween short never stalls the command waiting on it.
+
+use godot::classes::{ColorRect, Tween};
+use godot::prelude::*;
+use std::collections::HashMap;
+
+/// What a symbol node is animating
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationKind {
+    Clear,
+    Exit,
+    Fall,
+    Spawn,
+    Intro,
+    Outro,
+    Rotation,
+    Spin,
+    Frames,
+}
+
+impl AnimationKind {
+    /// Get the lowercase name of this kind
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnimationKind::Clear => "clear",
+            AnimationKind::Exit => "exit",
+            AnimationKind::Fall => "fall",
+            AnimationKind::Spawn => "spawn",
+            AnimationKind::Intro => "intro",
+            AnimationKind::Outro => "outro",
+            AnimationKind::Rotation => "rotation",
+            AnimationKind::Spin => "spin",
+            AnimationKind::Frames => "frames",
+        }
+    }
+}
+
+/// The animation driving a node
+struct Claim {
+    node: Gd<ColorRect>,
+    kind: AnimationKind,
+    tween: Gd<Tween>,
+    /// Where the node rests once the animation is over; None for nodes leaving the board
+    rest: Option<Vector2>,
+}
+
+impl Claim {
+    /// Check if the animation is still running or waiting to start
+    fn is_running(&self) -> bool {
+        self.tween.is_instance_valid() && self.tween.is_valid()
+    }
+
+    /// Stop the animation if it is still running, putting the node back at rest
+    fn stop(mut self) {
+        if !self.is_running() {
+            return;
+        }
+        self.tween.kill();
+        if !self.node.is_instance_valid() {
+            return;
+        }
+        if let Some(rest) = self.rest {
+            self.node.set_position(rest);
+        }
+        self.node.set_scale(Vector2::ONE);
+        self.node.set_rotation(0.0);
+    }
+}
+
+/// The animation owning each symbol node
+#[derive(Default)]
+pub struct AnimationOwners {
+    claims: HashMap<InstanceId, Claim>,
+}
+
+impl AnimationOwners {
+    /// Hand a node to an animation running on `tween`, stopping the one that still owned it
+    pub fn claim(&mut self, node: &Gd<ColorRect>, kind: AnimationKind, tween: Gd<Tween>, rest: Option<Vector2>) {
+        self.claims.retain(|_, claim| claim.is_running());
+        let claim = Claim {
+            node: node.clone(),
+            kind,
+            tween,
+            rest,
+        };
+        if let Some(previous) = self.claims.insert(node.instance_id(), claim) {
+            if previous.is_running() {
+                godot_print!("{} animation cut short by {}", previous.kind.name(), kind.name());
+            }
+            previous.stop();
+        }
+    }
+
+    /// Stop every running animation, putting each node back at rest
+    pub fn stop_all(&mut self) {
+        for (_, claim) in self.claims.drain() {
+            claim.stop();
+        }
+    }
+
+    /// Count the nodes with an animation still running
+    pub fn running(&self) -> usize {
+        self.claims.values().filter(|claim| claim.is_running()).count()
+    }
+}
@@ -0,0 +1,145 @@
+//! Attract mode - the board plays itself while nobody is at it
+//!
+//! Moves come from the ruleset's move finder, or from a recorded replay played
+//! through a `GameEngine` on the board it was dealt. Each move is highlighted
+//! one period before it is made so the demo reads clearly at a glance.
+
+use crate::clock::Interval;
+use crate::engine::GameEngine;
+use crate::model::BoardModel;
+use crate::replay::{Replay, ReplayMove};
+use godot::prelude::*;
+
+/// What attract mode does next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttractStep {
+    /// Point out the move about to be made
+    Highlight(Vector2i, Vector2i),
+    /// Make the previously highlighted move
+    Swap(Vector2i, Vector2i),
+    /// A replay move was played; the board should show the replay's model
+    Replayed,
+}
+
+/// A recorded game played back a move at a time, from its dealt board again once it ends
+#[derive(Debug, Clone)]
+struct Playback {
+    replay: Replay,
+    max_cascade_depth: i32,
+    engine: GameEngine,
+    next: usize,
+    highlighted: bool,
+}
+
+impl Playback {
+    fn new(replay: Replay, max_cascade_depth: i32) -> Self {
+        let engine = replay.engine(max_cascade_depth);
+        Self {
+            replay,
+            max_cascade_depth,
+            engine,
+            next: 0,
+            highlighted: false,
+        }
+    }
+
+    /// Highlight the next move if it has cells to point at, otherwise play it
+    fn advance(&mut self) -> Option<AttractStep> {
+        let Some(mv) = self.replay.moves().get(self.next) else {
+            // Start over from the dealt board
+            self.engine = self.replay.engine(self.max_cascade_depth);
+            self.next = 0;
+            return Some(AttractStep::Replayed);
+        };
+
+        if let Some((from, to)) = focus(mv).filter(|_| !self.highlighted) {
+            self.highlighted = true;
+            return Some(AttractStep::Highlight(from, to));
+        }
+
+        self.highlighted = false;
+        self.next += 1;
+        if let Err(err) = self.engine.play(mv, |_| {}) {
+            godot_warn!("Attract replay move {} failed: {}", self.next - 1, err);
+            self.next = self.replay.move_count();
+        }
+        Some(AttractStep::Replayed)
+    }
+}
+
+/// The cells a move starts and ends on, for moves the player points at
+fn focus(mv: &ReplayMove) -> Option<(Vector2i, Vector2i)> {
+    match mv {
+        ReplayMove::Swap { from, to, .. } => Some((*from, *to)),
+        ReplayMove::Tap { pos, .. } | ReplayMove::Hold(pos) => Some((*pos, *pos)),
+        ReplayMove::Link(cells) => Some((*cells.first()?, *cells.last()?)),
+        ReplayMove::Rotate(_) | ReplayMove::Spin { .. } | ReplayMove::Edit(_) => None,
+    }
+}
+
+/// Autoplay state for attract mode
+#[derive(Debug, Clone, Default)]
+pub struct AttractMode {
+    active: bool,
+    timer: Interval,
+    pending: Option<(Vector2i, Vector2i)>,
+    playback: Option<Playback>,
+}
+
+impl AttractMode {
+    /// Start autoplay from a clean slate
+    pub fn start(&mut self) {
+        *self = Self {
+            active: true,
+            ..Self::default()
+        };
+    }
+
+    /// Start autoplay that plays back a recorded game instead of finding moves
+    pub fn start_replay(&mut self, replay: &Replay, max_cascade_depth: i32) {
+        self.start();
+        self.playback = Some(Playback::new(replay.clone(), max_cascade_depth));
+    }
+
+    /// Get the model a replay is being played on, if autoplay is playing one back
+    pub fn replay_model(&self) -> Option<&BoardModel> {
+        self.playback.as_ref().map(|playback| playback.engine.model())
+    }
+
+    /// Stop autoplay, returning true if it was running
+    pub fn stop(&mut self) -> bool {
+        let was_active = self.active;
+        *self = Self::default();
+        was_active
+    }
+
+    /// Check if autoplay is running
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Advance by `delta`; each `period` either highlights a new move from
+    /// `next_move` (or the replay) or makes the one highlighted before
+    pub fn advance(
+        &mut self,
+        delta: f64,
+        period: f64,
+        next_move: impl FnOnce() -> Option<(Vector2i, Vector2i)>,
+    ) -> Option<AttractStep> {
+        if !self.active || !self.timer.advance(delta, period) {
+            return None;
+        }
+        if let Some(playback) = &mut self.playback {
+            return playback.advance();
+        }
+
+        match self.pending.take() {
+            Some((from, to)) => Some(AttractStep::Swap(from, to)),
+            None => {
+                let (from, to) = next_move()?;
+                self.pending = Some((from, to));
+                Some(AttractStep::Highlight(from, to))
+            }
+        }
+    }
+}
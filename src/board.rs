@@ -1,118 +1,970 @@
 //! Game board implementation - the main Godot class
 
-use crate::matching::MatchFinder;
-use crate::symbols::{Grid, Symbol, SymbolType};
-use godot::classes::{ColorRect, InputEvent, InputEventMouseButton, Node2D, Tween};
+use crate::attract::{AttractMode, AttractStep};
+use crate::chain::{Chain, ChainSpecial};
+use crate::clock::{GameClock, Interval};
+use crate::combo::{ComboEffect, SpecialKind};
+use crate::commands::{BoardCommand, CommandQueue};
+use crate::diagnostics::{self, InputLog};
+use crate::edits::{BoardEdit, Special};
+use crate::emphasis::EmphasisSource;
+use crate::error::{BoardError, SwapRejection};
+use crate::events::EventThrottle;
+use crate::expiry;
+use crate::garden::{self, GardenPlot};
+use crate::hud;
+use crate::input_mask::{self, InputMask};
+use crate::jelly;
+use crate::layout::{self, LayoutCell};
+use crate::level::LevelResource;
+use crate::link::{self, InputMode, LinkChange, LinkPath};
+use crate::locale::{self, Catalog};
+use crate::model::{BoardModel, Fall, ModelEvent};
+use crate::modifiers::{self, ModifierContext, ModifierEvent, ModifierStack, ModifierTuning};
+use crate::multipliers;
+use crate::objectives::{ObjectiveKind, ObjectiveSet};
+use crate::opening::{self, FairnessPolicy};
+use crate::pacing::RotationPacing;
+use crate::performance::{self, PerformancePreset};
+use crate::placement::{self, Anchor, Placement};
+use crate::profiles;
+use crate::refill::RefillPolicy;
+use crate::rules;
+use crate::render;
+use crate::replay::{GameReplay, Replay, ReplayMove};
+use crate::results::{self, ResultsSequence};
+use crate::roulette::{RouletteEffect, RouletteKind};
+use crate::rotation_stats::RotationStats;
+use crate::rewind::{RewindHistory, Snapshot};
+use crate::sandbox::{self, SandboxHistory};
+use crate::snapshot;
+use crate::soak::{NodeSample, SoakRun, SoakStep};
+use crate::solver;
+use crate::state::{GameState, StateMachine};
+use crate::symbols::{CellOverlay, Grid, Symbol, SymbolKind, SymbolTags, SymbolType};
+use crate::teaching::{self, TeachingLog};
+use crate::theme::SymbolTheme;
+use crate::ticker::ScoreTicker;
+use crate::tuning;
+use crate::turn::{TurnCounters, TurnSummary};
+use crate::tutorial::Tutorial;
+use crate::unlocks;
+use crate::view::BoardView;
+use godot::classes::notify::Node2DNotification;
+use godot::classes::performance::Monitor;
+use godot::classes::{
+    ConfigFile, DirAccess, Engine, Image, InputEvent, InputEventKey, InputEventMouseButton, InputEventMouseMotion, Json,
+    Node2D, Performance, ThemeDb,
+};
+use godot::global::{Error, Key};
 use godot::prelude::*;
+use std::collections::VecDeque;
 
-/// Game states
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum GameState {
-    #[default]
-    Ready,
-    Selected,
-    Swapping,
-    Matching,
-    Falling,
-    Rotating,
-}
+/// Throttle key for board snapshots
+const SNAPSHOT: &str = "snapshot";
+
+/// Default most snapshots per second
+const SNAPSHOT_RATE: f64 = 10.0;
+
+/// Throttle key for HUD summaries, which share the snapshot rate
+const HUD: &str = "hud";
+
+/// Boards dealt before settling for one that has no move or fails the opening fairness thresholds
+const MAX_DEAL_ATTEMPTS: usize = 20;
+
+/// Default moves the rewind booster can go back
+const REWIND_DEPTH: usize = 5;
 
 /// The main game board - a Godot Node2D that manages the match-3 grid
 #[derive(GodotClass)]
-#[class(base=Node2D)]
+#[class(tool, base=Node2D)]
 pub struct GameBoard {
     base: Base<Node2D>,
 
     /// The logical grid
-    #[var]
+    #[export_group(name = "Layout")]
+    #[export(range = (1.0, 32.0, 1.0))]
+    #[var(get, set = set_grid_width)]
     grid_width: i32,
-    #[var]
+    #[export(range = (1.0, 32.0, 1.0))]
+    #[var(get, set = set_grid_height)]
     grid_height: i32,
 
-    grid: Grid,
-
-    /// Visual representations of symbols (ColorRect nodes)
-    symbol_nodes: Vec<Option<Gd<ColorRect>>>,
-
     /// Cell size in pixels
-    #[var]
+    #[export(range = (8.0, 256.0, 1.0, or_greater))]
+    #[var(get, set = set_cell_size)]
     cell_size: f32,
 
     /// Padding between cells
-    #[var]
+    #[export(range = (0.0, 32.0, 0.5))]
+    #[var(get, set = set_cell_padding)]
     cell_padding: f32,
 
+    /// Level to load; overrides the grid size and preplaces symbols
+    #[export]
+    #[var(get, set = set_level)]
+    level: Option<Gd<LevelResource>>,
+
+    /// Rect the board is placed in, in the node's local space
+    #[export_group(name = "Placement")]
+    #[export]
+    #[var(get, set = set_board_rect)]
+    board_rect: Rect2,
+
+    /// Where the grid is aligned within the board rect
+    #[export(enum = (TopLeft, Top, TopRight, Left, Center, Right, BottomLeft, Bottom, BottomRight))]
+    #[var(get, set = set_board_anchor)]
+    board_anchor: i32,
+
+    /// Inset from each edge of the board rect
+    #[export(range = (0.0, 256.0, 1.0, or_greater))]
+    #[var(get, set = set_board_margin)]
+    board_margin: f32,
+
+    /// Whether to scale cells so the grid fills the board rect, ignoring cell_size
+    #[export]
+    #[var(get, set = set_fit_to_rect)]
+    fit_to_rect: bool,
+
+    /// Whether placement changed while the board was busy and still has to be applied
+    placement_dirty: bool,
+
+    /// Game variant: how moves are made and whether the board rotates
+    #[export_group(name = "Rules")]
+    #[export(enum = (ClassicRevolving, TapRotate, ComboRush))]
+    #[var(get, set = set_ruleset)]
+    ruleset: i32,
+
+    /// Modifiers stacked on every level, before the level's own: frenzy (timed by the frenzy
+    /// settings below), expiry, roulette, mirror (every swap repeated across the vertical center line),
+    /// fog (only cells near recent moves and matches show)
+    #[export]
+    modifiers: PackedStringArray,
+
+    /// Whether the player may stash a symbol in the hold slot (right click) and swap it back later
+    #[export]
+    hold_enabled: bool,
+
+    /// Turns without a match of 4 or more before a refill sets one up (0 for never)
+    #[export(range = (0.0, 50.0, 1.0, or_greater))]
+    #[var(get, set = set_pity_threshold)]
+    pity_threshold: i32,
+
+    /// Competitive play, where every player must face the same luck: pity nudges are off
+    #[export]
+    #[var(get, set = set_competitive)]
+    competitive: bool,
+
+    /// Moves the rewind booster can go back (0 for none); a snapshot is kept for each
+    #[export(range = (0.0, 20.0, 1.0, or_greater))]
+    #[var(get, set = set_rewind_depth)]
+    rewind_depth: i32,
+
+    /// Snapshots from before the player's recent moves
+    rewind: RewindHistory,
+
+    /// Mechanics unlocked without a profile and for a new profile; a campaign locks some here and
+    /// hands them out with set_unlocked
+    #[export(flags = (
+        Spin = 1,
+        SpinCounterclockwise = 2,
+        Hold = 4,
+        Rewind = 8,
+        ManualRotation = 16,
+        RotateCounterclockwise = 32
+    ))]
+    starting_unlocks: i32,
+
+    /// Mechanics unlocked for the current player, as UNLOCK_* flags
+    unlocks: i32,
+
+    /// The player's move being resolved, tallied for turn_resolved
+    turn: Option<TurnSummary>,
+
+    /// Whether the hold slot was used since the player's last move
+    hold_used: bool,
+
+    /// Fewest legal swaps an opening must offer; boards are dealt again until one does
+    #[export_group(name = "Opening")]
+    #[export(range = (0.0, 50.0, 1.0, or_greater))]
+    opening_min_moves: i32,
+
+    /// Fewest points the opening's best swap must score before any refill
+    #[export(range = (0.0, 500.0, 1.0, or_greater))]
+    opening_min_best_score: i32,
+
+    /// Least count of the rarest symbol type over the most common one (0 for any spread, 1 for exactly even)
+    #[export(range = (0.0, 1.0, 0.01))]
+    opening_min_balance: f64,
+
+    /// Rotation interval in seconds
+    #[export_group(name = "Rotation")]
+    #[export(range = (0.5, 60.0, 0.1, or_greater))]
+    rotation_interval: f64,
+
+    /// Whether the rotation timer runs faster while the board goes without moves or matches
+    #[export]
+    adaptive_rotation: bool,
+
+    /// Fastest rate of the rotation timer on a static board, relative to normal
+    #[export(range = (1.0, 4.0, 0.1))]
+    adaptive_max_rate: f64,
+
+    /// Seconds without moves or matches until the rotation timer reaches its fastest rate
+    #[export(range = (0.5, 60.0, 0.5, or_greater))]
+    adaptive_ramp_seconds: f64,
+
+    /// How refilled symbols pick their type; Bag prevents long droughts of one type
+    #[export_group(name = "Cascades")]
+    #[export(enum = (Random, Bag))]
+    #[var(get, set = set_refill_policy)]
+    refill_policy: i32,
+
+    /// Most match resolutions in one cascade before it is cut short
+    #[export(range = (1.0, 100.0, 1.0, or_greater))]
+    max_cascade_depth: i32,
+
+    /// Seconds of normal play between frenzies, when the frenzy modifier is stacked; 0 disables frenzies
+    #[export_group(name = "Frenzy")]
+    #[export(range = (0.0, 600.0, 1.0, or_greater))]
+    frenzy_interval: f64,
+
+    /// Seconds a frenzy lasts
+    #[export(range = (1.0, 120.0, 0.5, or_greater))]
+    frenzy_duration: f64,
+
+    /// Seconds before a frenzy at which it is announced
+    #[export(range = (0.0, 30.0, 0.5))]
+    frenzy_warning: f64,
+
+    /// Score multiplier during a frenzy
+    #[export(range = (1.0, 10.0, 1.0, or_greater))]
+    frenzy_multiplier: i32,
+
+    /// Symbol type the player most needs for their objectives (-1 for none)
+    /// Refills favor it during a frenzy and seekers aim for it
+    #[export(range = (-1.0, 5.0, 1.0))]
+    #[var(get, set = set_objective_color)]
+    objective_color: i32,
+
+    /// Score, mirrored from the model; change it with add_score and reset_score
+    #[var(get)]
+    score: i32,
+
+    /// Moves made since the board was reset
+    #[var(get)]
+    moves_made: i32,
+
+    /// Cells the fog clears from around each move and match, when the fog modifier is stacked
+    #[export_group(name = "Fog")]
+    #[export(range = (0.0, 5.0, 1.0, or_greater))]
+    fog_radius: i32,
+
+    /// Moves a cell stays clear before the fog covers it again
+    #[export(range = (1.0, 20.0, 1.0, or_greater))]
+    fog_moves: i32,
+
+    /// Score thresholds for each star, in ascending order
+    #[export_group(name = "Scoring")]
+    #[export]
+    star_thresholds: PackedInt32Array,
+
+    /// How fast the display score counts toward the score, in gap closed per second (0 jumps straight to it)
+    #[export(range = (0.0, 30.0, 0.5, or_greater))]
+    score_tick_rate: f64,
+
+    /// Points per symbol in the first row the level-end sweep clears, rising by as much each row after;
+    /// 0 skips the sweep
+    #[export(range = (0.0, 100.0, 1.0, or_greater))]
+    sweep_points: i32,
+
+    /// Objectives completed, kept while the level-end sweep runs before the results
+    sweep_objectives: i32,
+
+    /// Seconds for matched symbols to shrink away
+    #[export_group(name = "Animation")]
+    #[export(range = (0.0, 2.0, 0.01))]
+    clear_duration: f64,
+
+    /// Seconds per cell a symbol takes to fall
+    #[export(range = (0.0, 1.0, 0.01))]
+    fall_duration_per_cell: f64,
+
+    /// Falls of more than this many cells are fast falls, announced with fast_fall; 0 disables them
+    #[export(range = (0.0, 32.0, 1.0))]
+    fast_fall_cells: i32,
+
+    /// Whether fast-falling symbols stretch along their fall
+    #[export]
+    fast_fall_trail: bool,
+
+    /// Seconds for refilled symbols to drop in
+    #[export(range = (0.0, 2.0, 0.01))]
+    spawn_duration: f64,
+
+    /// Seconds for the rotation spin
+    #[export(range = (0.0, 2.0, 0.01))]
+    rotation_duration: f64,
+
+    /// Seconds each skipped state shows for as a rewind plays back
+    #[export(range = (0.0, 1.0, 0.01))]
+    rewind_frame_duration: f64,
+
+    /// Seconds between results fanfare events
+    #[export(range = (0.0, 5.0, 0.05))]
+    results_event_spacing: f64,
+
+    /// Seconds each row of the level-end sweep takes to clear
+    #[export(range = (0.0, 1.0, 0.01))]
+    sweep_row_duration: f64,
+
+    /// Seconds each symbol takes to drop in for the intro or fly off for the outro
+    #[export(range = (0.0, 2.0, 0.01))]
+    transition_duration: f64,
+
+    /// Seconds between one column starting its intro or outro and the next
+    #[export(range = (0.0, 0.5, 0.01))]
+    transition_column_delay: f64,
+
+    /// Whether the outro has sent the symbols off and the board waits for an intro
+    symbols_away: bool,
+
+    /// Whether resync_visuals should run once animations finish, e.g. after the app resumed
+    resync_pending: bool,
+
+    /// Emphasis on the two cells of a hint, until the player next acts (0 for none)
+    #[export_group(name = "Emphasis")]
+    #[export(range = (0.0, 1.0, 0.05))]
+    hint_emphasis: f64,
+
+    /// Emphasis on the symbols of the objective color (0 for none)
+    #[export(range = (0.0, 1.0, 0.05))]
+    objective_emphasis: f64,
+
+    /// Emphasis on seekers, wilds, striped symbols and color bombs (0 for none)
+    #[export(range = (0.0, 1.0, 0.05))]
+    special_emphasis: f64,
+
+    /// Idle seconds before the board starts playing itself; 0 disables attract mode
+    #[export_group(name = "Attract")]
+    #[export(range = (0.0, 300.0, 1.0, or_greater))]
+    attract_idle_seconds: f64,
+
+    /// Seconds between attract mode highlights and moves
+    #[export(range = (0.1, 10.0, 0.1))]
+    attract_move_interval: f64,
+
+    /// Animation speed while in attract mode, relative to normal play
+    #[export(range = (0.1, 1.0, 0.05))]
+    attract_speed: f64,
+
+    /// Recorded game attract mode plays back instead of finding its own moves (none to find them)
+    #[var]
+    attract_replay: Option<Gd<GameReplay>>,
+
+    /// Grid, rules, scoring and randomness
+    model: BoardModel,
+
+    /// Moves made since the board was dealt
+    replay: Replay,
+
+    /// Recent player inputs, for crash reports
+    input_log: InputLog,
+
+    /// Rotations and the matches made around them this game
+    rotation_stats: RotationStats,
+
+    /// How long the board has been static, for adaptive rotation
+    pacing: RotationPacing,
+
+    /// Symbol nodes and animations
+    view: BoardView,
+
+    /// How much presentation work the board does
+    performance: PerformancePreset,
+
     /// Currently selected position
     selected_pos: Option<Vector2i>,
 
-    /// Current game state
-    state: GameState,
+    /// Cells made non-interactive by lock_cells
+    locked_cells: Vec<Vector2i>,
 
-    /// Score
+    /// How the player makes moves; a level's input mode overrides this
+    #[export(enum = (Swap, Link))]
+    input_mode: i32,
+
+    /// Whether an idle board with moves left gets a free rotation or a row shuffle to keep play flowing
+    #[export_group(name = "Idle Shuffle")]
     #[export]
-    score: i32,
+    idle_shuffle_enabled: bool,
 
-    /// Combo multiplier
-    combo: i32,
+    /// Idle seconds before each free rotation or row shuffle
+    #[export(range = (1.0, 120.0, 1.0, or_greater))]
+    idle_shuffle_seconds: f64,
 
-    /// Rotation timer
-    rotation_timer: f64,
+    /// Shuffle a random row instead of giving a free rotation; rulesets without rotation always shuffle
+    #[export]
+    idle_shuffle_row: bool,
 
-    /// Rotation interval in seconds
+    /// Time towards the next idle shuffle
+    idle_shuffle_timer: Interval,
+
+    /// Path being dragged in link mode
+    link: LinkPath,
+
+    /// Released path waiting for its ClearLink command
+    released_link: Vec<Vector2i>,
+
+    /// Scripted edit batches waiting for the board to settle, each applied by an Edit command
+    pending_edits: VecDeque<Vec<BoardEdit>>,
+
+    /// Current game state
+    state: StateMachine,
+
+    /// Pending board mutations
+    commands: CommandQueue,
+
+    /// Running tutorial, constraining what the player may do
+    tutorial: Tutorial,
+
+    /// Cells and actions the player may currently use
+    input_mask: InputMask,
+
+    /// Modifiers in play on the current level
+    modifier_stack: ModifierStack,
+
+    /// Autoplay state while in attract mode
+    attract: AttractMode,
+
+    /// The board's own model, put back when attract mode stops playing a replay over it
+    attract_saved_model: Option<BoardModel>,
+
+    /// Bot play and node counts of a running soak test
+    soak: SoakRun,
+
+    /// Score shown counting toward the real one
+    ticker: ScoreTicker,
+
+    /// Whether the board is a sandbox: free placement and undo, no scoring
+    #[var(get, set = set_sandbox_mode)]
+    sandbox_mode: bool,
+
+    /// Undo history for sandbox changes
+    sandbox_history: SandboxHistory,
+
+    /// Teach moments already shown to this profile
+    teaching: TeachingLog,
+
+    /// Player-facing text per locale
+    catalog: Catalog,
+
+    /// File the seen teach moments are saved in; set per profile
+    #[var(get, set = set_teach_moments_path)]
+    teach_moments_path: GString,
+
+    /// Whether clears plant garden tiles that carry over between levels and sessions
+    #[var(get, set = set_garden_mode)]
+    garden_mode: bool,
+
+    /// Garden tiles planted so far
+    garden: GardenPlot,
+
+    /// Whether the garden changed since it was last saved
+    garden_dirty: bool,
+
+    /// File the garden is saved in; set per profile
+    #[var(get, set = set_garden_path)]
+    garden_path: GString,
+
+    /// Directory holding one subdirectory per profile (see the `profiles` module)
     #[var]
-    rotation_interval: f64,
+    profiles_dir: GString,
+
+    /// Profile in use, or None for the plain save paths
+    profile: Option<String>,
+
+    /// Incremented whenever the board is rebuilt; callbacks from older generations are ignored
+    generation: i64,
+
+    /// Time towards the next rotation
+    rotation_timer: Interval,
+
+    /// Per-symbol-type clear sounds and particle colors
+    theme: SymbolTheme,
+
+    /// Best score reached this session
+    #[var]
+    best_score: i32,
+
+    /// Pending results fanfare events
+    results: ResultsSequence,
+
+    /// Seconds without input before the board counts as idle
+    #[var]
+    idle_threshold: f64,
+
+    /// Seconds between idle_tick events while idle
+    #[var]
+    idle_tick_interval: f64,
+
+    /// Whether to emit a hint once the board becomes idle
+    #[var]
+    idle_hint_enabled: bool,
+
+    /// Seconds since the last input
+    idle_time: f64,
+
+    /// Time towards the next idle tick
+    idle_tick_timer: Interval,
+
+    /// Whether a hint was already given during this idle period
+    idle_hint_given: bool,
+
+    /// Whether each move is graded against the best available move
+    #[var]
+    trainer_mode: bool,
+
+    /// Most moves evaluated when grading a move in trainer mode
+    #[var]
+    trainer_move_budget: i32,
+
+    /// Rate limits for emitted signals
+    throttle: EventThrottle,
+
+    /// Tick of the last emitted signal; every signal carries the next one as its last argument
+    event_tick: i64,
+
+    /// Most snapshots per second built by get_board_snapshot_json and get_hud_summary;
+    /// faster calls get the last one
+    #[var(get, set = set_snapshot_rate)]
+    snapshot_rate: f64,
+
+    /// Rate limit for building snapshots
+    snapshot_throttle: EventThrottle,
+
+    /// Last snapshot built
+    snapshot_cache: Option<Dictionary>,
+
+    /// Last HUD summary built
+    hud_cache: Option<Dictionary>,
+
+    /// Symbols of the objective color cleared this game
+    objective_cleared: i32,
+
+    /// Why the level was won during the current move (the last jelly cleared or ingredient collected), if it was
+    level_won: Option<&'static str>,
 
-    /// Board offset for centering
-    board_offset: Vector2,
+    /// The level's weighted objectives and their progress
+    objectives: ObjectiveSet,
+
+    /// Game time for this board, used for timers and throttling
+    clock: GameClock,
+
+    /// Whether the debug overlay is shown (toggle with F3)
+    #[var(get, set = set_debug_overlay)]
+    debug_overlay: bool,
+
+    /// Canvas layer the debug overlay draws on, above the symbols
+    debug_layer: Option<Gd<Node2D>>,
+
+    /// Positions cleared by the most recent match
+    last_match_positions: Vec<Vector2i>,
 }
 
 #[godot_api]
 impl INode2D for GameBoard {
     fn init(base: Base<Node2D>) -> Self {
+        let mut snapshot_throttle = EventThrottle::default();
+        snapshot_throttle.set_rate_limit(SNAPSHOT, SNAPSHOT_RATE);
+        snapshot_throttle.set_rate_limit(HUD, SNAPSHOT_RATE);
+        let mut rewind = RewindHistory::default();
+        rewind.set_capacity(REWIND_DEPTH);
+
         Self {
             base,
             grid_width: 8,
             grid_height: 8,
-            grid: Grid::new(8, 8),
-            symbol_nodes: Vec::new(),
             cell_size: 64.0,
             cell_padding: 4.0,
-            selected_pos: None,
-            state: GameState::Ready,
-            score: 0,
-            combo: 1,
-            rotation_timer: 0.0,
+            level: None,
+            board_rect: Rect2::new(Vector2::ZERO, Vector2::new(1280.0, 720.0)),
+            board_anchor: Anchor::Center as i32,
+            board_margin: 0.0,
+            fit_to_rect: false,
+            placement_dirty: false,
+            ruleset: 0,
+            modifiers: PackedStringArray::new(),
+            hold_enabled: false,
+            pity_threshold: 0,
+            competitive: false,
+            rewind_depth: REWIND_DEPTH as i32,
+            rewind,
+            starting_unlocks: unlocks::UNLOCK_ALL,
+            unlocks: unlocks::UNLOCK_ALL,
+            turn: None,
+            hold_used: false,
+            opening_min_moves: 0,
+            opening_min_best_score: 0,
+            opening_min_balance: 0.0,
             rotation_interval: 5.0,
-            board_offset: Vector2::ZERO,
+            adaptive_rotation: false,
+            adaptive_max_rate: 2.0,
+            adaptive_ramp_seconds: 10.0,
+            pacing: RotationPacing::default(),
+            refill_policy: RefillPolicy::Random as i32,
+            max_cascade_depth: 20,
+            frenzy_interval: 0.0,
+            frenzy_duration: 10.0,
+            frenzy_warning: 3.0,
+            frenzy_multiplier: 2,
+            fog_radius: 1,
+            fog_moves: 3,
+            objective_color: -1,
+            score: 0,
+            moves_made: 0,
+            star_thresholds: [1000, 2500, 5000].into_iter().collect(),
+            score_tick_rate: 8.0,
+            sweep_points: 10,
+            sweep_objectives: 0,
+            clear_duration: 0.2,
+            fall_duration_per_cell: 0.08,
+            fast_fall_cells: 3,
+            fast_fall_trail: true,
+            spawn_duration: 0.15,
+            rotation_duration: 0.3,
+            rewind_frame_duration: 0.08,
+            results_event_spacing: 0.6,
+            sweep_row_duration: 0.12,
+            transition_duration: 0.5,
+            transition_column_delay: 0.06,
+            symbols_away: false,
+            resync_pending: false,
+            hint_emphasis: 0.5,
+            objective_emphasis: 0.0,
+            special_emphasis: 0.0,
+            attract_idle_seconds: 0.0,
+            attract_move_interval: 1.5,
+            attract_speed: 0.5,
+            attract_replay: None,
+            model: BoardModel::new(8, 8),
+            replay: Replay::new(BoardModel::new(8, 8)),
+            input_log: InputLog::default(),
+            rotation_stats: RotationStats::default(),
+            view: BoardView::new(),
+            performance: PerformancePreset::default(),
+            selected_pos: None,
+            locked_cells: Vec::new(),
+            input_mode: InputMode::Swap as i32,
+            link: LinkPath::default(),
+            released_link: Vec::new(),
+            pending_edits: VecDeque::new(),
+            state: StateMachine::default(),
+            commands: CommandQueue::default(),
+            tutorial: Tutorial::default(),
+            input_mask: InputMask::default(),
+            modifier_stack: ModifierStack::default(),
+            attract: AttractMode::default(),
+            attract_saved_model: None,
+            soak: SoakRun::default(),
+            ticker: ScoreTicker::default(),
+            sandbox_mode: false,
+            sandbox_history: SandboxHistory::default(),
+            teaching: TeachingLog::default(),
+            catalog: Catalog::default(),
+            teach_moments_path: "user://teach_moments.cfg".into(),
+            garden_mode: false,
+            garden: GardenPlot::default(),
+            garden_dirty: false,
+            garden_path: "user://garden.cfg".into(),
+            profiles_dir: "user://profiles".into(),
+            profile: None,
+            generation: 0,
+            rotation_timer: Interval::default(),
+            theme: SymbolTheme::default(),
+            best_score: 0,
+            results: ResultsSequence::default(),
+            idle_threshold: 10.0,
+            idle_tick_interval: 5.0,
+            idle_hint_enabled: true,
+            idle_time: 0.0,
+            idle_tick_timer: Interval::default(),
+            idle_hint_given: false,
+            idle_shuffle_enabled: false,
+            idle_shuffle_seconds: 15.0,
+            idle_shuffle_row: false,
+            idle_shuffle_timer: Interval::default(),
+            trainer_mode: false,
+            trainer_move_budget: 200,
+            throttle: EventThrottle::default(),
+            event_tick: 0,
+            snapshot_rate: SNAPSHOT_RATE,
+            snapshot_throttle,
+            snapshot_cache: None,
+            hud_cache: None,
+            objective_cleared: 0,
+            level_won: None,
+            objectives: ObjectiveSet::default(),
+            clock: GameClock::default(),
+            debug_overlay: false,
+            debug_layer: None,
+            last_match_positions: Vec::new(),
         }
     }
 
     fn ready(&mut self) {
         godot_print!("GameBoard ready - initializing {} x {} grid", self.grid_width, self.grid_height);
+        self.unlocks = self.starting_unlocks;
+        self.load_teach_moments();
+        self.load_garden();
         self.initialize_board();
     }
 
     fn process(&mut self, delta: f64) {
+        self.guarded("process", |board| board.advance_frame(delta));
+    }
+
+    fn on_notification(&mut self, what: Node2DNotification) {
+        // Tweens can miss their callbacks while the app is in the background
+        if what == Node2DNotification::APPLICATION_FOCUS_IN || what == Node2DNotification::APPLICATION_RESUMED {
+            self.resync_pending = true;
+        }
+    }
+
+    fn get_configuration_warnings(&self) -> PackedStringArray {
+        let mut warnings = PackedStringArray::new();
+
+        if self.grid_width < 1 || self.grid_height < 1 {
+            warnings.push("Grid width and height must both be at least 1.");
+        } else if self.grid_width < 3 && self.grid_height < 3 {
+            warnings.push("Grid is smaller than 3x3 in both directions, so no match can ever form.");
+        }
+        if self.cell_size <= self.cell_padding * 2.0 {
+            warnings.push("Cell padding leaves no room for symbols; increase cell_size or reduce cell_padding.");
+        }
+        if self.rotation_interval <= 0.0 {
+            warnings.push("Rotation interval must be positive.");
+        }
+        match &self.level {
+            Some(level) => {
+                let level = level.bind();
+                let (width, height) = level.size();
+                if level.cell_count() != width * height {
+                    warnings.push("Level layers do not match its size; repaint or resize the level.");
+                }
+                if level.hole_cells().len() >= width * height {
+                    warnings.push("Level masks out every cell, leaving no symbols to play with.");
+                }
+                if width != height && unlocks::allows(self.starting_unlocks, unlocks::UNLOCK_SPIN) {
+                    warnings.push("Only square boards spin; lock Spin in starting_unlocks or make the level square.");
+                }
+            }
+            None => {
+                warnings.push("No level resource is assigned; a random board of the grid size is dealt.");
+            }
+        }
+
+        warnings
+    }
+
+    fn input(&mut self, event: Gd<InputEvent>) {
+        self.guarded("input", |board| board.handle_input(event));
+    }
+}
+
+impl GameBoard {
+    /// Get the logical grid, for views that draw the board themselves
+    pub(crate) fn grid(&self) -> &Grid {
+        self.model.grid()
+    }
+
+    /// Get how long the rotation spin actually takes, for views that animate it themselves
+    pub(crate) fn rotation_time(&self) -> f64 {
+        self.animation_time(self.rotation_duration)
+    }
+
+    /// Run an entry point's logic; if it panics, reset the board and emit fatal_error
+    fn guarded<R>(&mut self, entry: &str, f: impl FnOnce(&mut Self) -> R) -> Option<R> {
+        let message = match diagnostics::guard(|| f(self)) {
+            Ok(result) => return Some(result),
+            Err(message) => message,
+        };
+        godot_error!("Panic in {}: {}", entry, message);
+
+        // Capture the board as the panic left it, then start over from a fresh deal
+        let rows = |rows: Vec<String>| -> PackedStringArray { rows.iter().map(GString::from).collect() };
+        let mut report = Dictionary::new();
+        report.set("entry", entry);
+        report.set("message", message.as_str());
+        report.set("seed", self.model.seed());
+        report.set("state", self.state.current().name());
+        report.set("board", rows(snapshot::type_rows(self.model.grid())));
+        report.set("faces", rows(snapshot::face_rows(self.model.grid())));
+        report.set("inputs", self.input_log.entries().map(GString::from).collect::<PackedStringArray>());
+
+        let recovered = diagnostics::guard(|| self.reset_board()).is_ok();
+        if !recovered {
+            godot_error!("Board could not be reset after a panic in {}", entry);
+        }
+        report.set("recovered", recovered);
+        self.emit_event("fatal_error", &[report.to_variant()]);
+        None
+    }
+
+    /// Advance timers, attract mode, rotation and frenzies by one frame
+    fn advance_frame(&mut self, delta: f64) {
+        if Engine::singleton().is_editor_hint() {
+            return;
+        }
+
+        if self.debug_overlay {
+            if let Some(layer) = self.debug_layer.as_mut() {
+                layer.queue_redraw();
+            }
+        }
+
+        if self.placement_dirty && self.state.current().accepts_input() {
+            self.apply_placement();
+        }
+
+        if self.resync_pending && !self.clock.is_paused() && !self.view.is_animating() {
+            self.resync_visuals();
+        }
+
+        if self.clock.is_paused() {
+            return;
+        }
+        let delta = self.clock.advance(delta);
+
+        if let Some(shown) = self.ticker.advance(delta, self.score_tick_rate) {
+            self.emit_event("display_score_changed", &[shown.to_variant()]);
+        }
+        if let Some(fraction) = self.model.tick_combo_window(delta) {
+            self.emit_event("combo_window_changed", &[fraction.to_variant()]);
+        }
+
+        if self.state.current() == GameState::Results {
+            self.advance_results(delta);
+            return;
+        }
+
+        // Track idle time while waiting for the player
+        if self.soak.is_active() {
+            self.advance_soak(delta);
+        } else if self.attract.is_active() {
+            self.advance_attract(delta);
+        } else if self.state.current().accepts_input() {
+            self.update_idle(delta);
+            if self.attract_idle_seconds > 0.0
+                && self.idle_time >= self.attract_idle_seconds
+                && self.state.current() == GameState::Ready
+            {
+                self.begin_attract();
+            }
+        }
+
         // Handle rotation timer
-        if self.state == GameState::Ready {
-            self.rotation_timer += delta;
-            if self.rotation_timer >= self.rotation_interval {
-                self.rotation_timer = 0.0;
+        if self.state.current() == GameState::Ready
+            && self.model.rules().rotates()
+            && !self.tutorial.freezes_rotation()
+        {
+            let rate = if self.adaptive_rotation {
+                self.pacing.advance(delta);
+                self.pacing.rate(self.adaptive_max_rate, self.adaptive_ramp_seconds)
+            } else {
+                1.0
+            };
+            if self.rotation_timer.advance(delta * rate, self.rotation_interval) {
                 self.trigger_rotation();
+            } else if self.rotation_interval - self.rotation_timer.elapsed() <= teaching::ROTATION_LEAD {
+                self.teach(teaching::TOPIC_ROTATION);
             }
         }
+
+        self.run_modifiers(|stack, ctx| stack.on_tick(ctx, delta));
     }
 
-    fn input(&mut self, event: Gd<InputEvent>) {
-        if self.state != GameState::Ready && self.state != GameState::Selected {
+    /// Handle a player input event
+    fn handle_input(&mut self, event: Gd<InputEvent>) {
+        if Engine::singleton().is_editor_hint() {
             return;
         }
 
-        if let Ok(mouse_event) = event.try_cast::<InputEventMouseButton>() {
-            if mouse_event.is_pressed() && mouse_event.get_button_index() == godot::global::MouseButton::LEFT {
-                let click_pos = mouse_event.get_position();
-                self.handle_click(click_pos);
+        // Any input ends attract mode and is not passed on to the board
+        if self.attract.is_active() && event.is_pressed() {
+            self.end_attract();
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
             }
+            return;
+        }
+
+        let Ok(mouse_event) = event.clone().try_cast::<InputEventMouseButton>() else {
+            if event.is_pressed() {
+                self.reset_idle();
+            }
+
+            if let Ok(motion) = event.clone().try_cast::<InputEventMouseMotion>() {
+                if self.link.is_active() {
+                    if let Some(grid_pos) = self.cell_at_screen(motion.get_position()) {
+                        self.extend_link(grid_pos);
+                    }
+                }
+                return;
+            }
+
+            if let Ok(key_event) = event.try_cast::<InputEventKey>() {
+                if key_event.is_pressed() && !key_event.is_echo() && key_event.get_keycode() == Key::F3 {
+                    let enabled = !self.debug_overlay;
+                    self.set_debug_overlay(enabled);
+                }
+            }
+            return;
+        };
+
+        if mouse_event.get_button_index() == godot::global::MouseButton::RIGHT && mouse_event.is_pressed() {
+            if let Some(grid_pos) = self.cell_at_screen(mouse_event.get_position()) {
+                self.reset_idle();
+                if self.state.current() == GameState::Selected {
+                    self.deselect_symbol();
+                }
+                // In tap rulesets without a hold slot, a right click turns the symbol back a face
+                let handled = if self.hold_enabled {
+                    self.exchange_hold(grid_pos)
+                } else {
+                    self.state.current() == GameState::Ready
+                        && self.model.rules().taps()
+                        && self.tap_symbol(grid_pos, false)
+                };
+                if handled {
+                    if let Some(mut viewport) = self.base().get_viewport() {
+                        viewport.set_input_as_handled();
+                    }
+                }
+            }
+            return;
+        }
+        if mouse_event.get_button_index() != godot::global::MouseButton::LEFT {
+            return;
+        }
+        if !mouse_event.is_pressed() {
+            if self.link.is_active() {
+                self.finish_link();
+            }
+            return;
+        }
+
+        // Clicks outside this board are left for other boards and UI
+        let Some(grid_pos) = self.cell_at_screen(mouse_event.get_position()) else {
+            return;
+        };
+
+        self.reset_idle();
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
+        }
+
+        if self.state.current().accepts_input() && !self.clock.is_paused() {
+            self.handle_click(grid_pos);
         }
     }
 }
@@ -121,667 +973,3929 @@ impl INode2D for GameBoard {
 impl GameBoard {
     /// Signal emitted when score changes
     #[signal]
-    fn score_changed(new_score: i32);
+    fn score_changed(new_score: i32, tick: i64);
 
-    /// Signal emitted when a match is found
+    /// Add points from a named source (e.g. "bonus"), emitting score_changed
+    #[func]
+    fn add_score(&mut self, amount: i32, source: GString) {
+        self.model.add_score(amount, &source.to_string());
+        self.flush_model_events();
+    }
+
+    /// Reset the score and its breakdown to zero, emitting score_changed
+    #[func]
+    fn reset_score(&mut self) {
+        self.model.reset_scoring();
+        self.flush_model_events();
+        if self.model.combo_window().is_some() {
+            self.emit_event("combo_window_changed", &[0.0.to_variant()]);
+        }
+    }
+
+    /// Signal emitted as the window to keep a timed combo going drains or refills, with the share left
+    /// (0 to 1); only rulesets that time combos emit it
     #[signal]
-    fn match_found(count: i32);
+    fn combo_window_changed(fraction: f64, tick: i64);
 
-    /// Signal emitted when rotation occurs
+    /// Get the share of the timed combo window left (0 to 1), or -1 if the ruleset does not time combos
+    #[func]
+    fn get_combo_window(&self) -> f64 {
+        self.model.combo_window().unwrap_or(-1.0)
+    }
+
+    /// Signal emitted when the display score counts up, or drops with the score
     #[signal]
-    fn rotation_triggered();
+    fn display_score_changed(display_score: i32, tick: i64);
 
-    /// Initialize the game board
+    /// Get the score as a HUD should show it, counting toward the real score at score_tick_rate
     #[func]
-    fn initialize_board(&mut self) {
-        // Calculate board offset to center it
-        let board_width = self.grid_width as f32 * self.cell_size;
-        let board_height = self.grid_height as f32 * self.cell_size;
-        self.board_offset = Vector2::new(
-            (1280.0 - board_width) / 2.0,
-            (720.0 - board_height) / 2.0,
-        );
+    fn get_display_score(&self) -> i32 {
+        self.ticker.value()
+    }
+
+    /// Get the points scored per source, e.g. { "match": 1200, "bonus": 300 }
+    #[func]
+    fn get_score_breakdown(&self) -> Dictionary {
+        let mut breakdown = Dictionary::new();
+        for (source, total) in self.model.breakdown().totals() {
+            breakdown.set(source, total);
+        }
+        breakdown
+    }
 
-        // Create the grid
-        self.grid = Grid::new(self.grid_width as usize, self.grid_height as usize);
-        self.grid.fill_random();
+    /// Signal emitted when a match is found
+    #[signal]
+    fn match_found(count: i32, tick: i64);
 
-        // Create visual nodes
-        self.create_symbol_nodes();
+    /// Signal emitted when a cascade ends, with what started it ("swap", "rotation", "link", "tap", "hold"
+    /// or "spin") and one dictionary per resolution: { matches: [{ positions, symbol_type, moved }], cleared,
+    /// created, fired } where `moved` lists the match's cells that fell or spawned since the previous step,
+    /// and `created` and `fired` the specials the step left behind and set off, as [{ pos, special }]
+    #[signal]
+    fn chain_resolved(cause: GString, steps: Array<Dictionary>, tick: i64);
 
-        godot_print!("Board initialized with {} symbols", self.grid_width * self.grid_height);
+    /// Signal emitted `frenzy_warning` seconds before a frenzy starts
+    #[signal]
+    fn frenzy_upcoming(seconds: f64, tick: i64);
+
+    /// Signal emitted when a frenzy starts
+    #[signal]
+    fn frenzy_started(multiplier: i32, duration: f64, tick: i64);
+
+    /// Signal emitted when a frenzy ends
+    #[signal]
+    fn frenzy_ended(tick: i64);
+
+    /// Check if a frenzy is running
+    #[func]
+    fn is_frenzy_active(&self) -> bool {
+        self.modifier_stack.boosting()
+    }
+
+    /// Signal emitted with the kind of effect the next rotation will have, when rotation roulette is on
+    /// (rotate_faces, shuffle_row, freeze_column or spawn_special); its text is `get_text("rotation.<kind>", {})`
+    #[signal]
+    fn next_rotation_effect(kind: GString, tick: i64);
+
+    /// Get the names of the modifiers in play on the current level, in the order their hooks run
+    #[func]
+    fn get_active_modifiers(&self) -> PackedStringArray {
+        self.modifier_stack.names().into_iter().map(GString::from).collect()
+    }
+
+    /// Stack a modifier (frenzy, expiry, roulette, mirror or fog) on the current level until the next reset
+    /// Returns false if the name is unknown or the modifier is already in play
+    #[func]
+    fn add_modifier(&mut self, name: GString) -> bool {
+        let name = name.to_string();
+        let Some(modifier) = modifiers::create(&name, self.model.seed(), self.expiry_rotations()) else {
+            godot_warn!("Unknown modifier: {} (available: {})", name, modifiers::NAMES.join(", "));
+            return false;
+        };
+        let mut added = false;
+        self.run_modifiers(|stack, ctx| added = stack.add(modifier, ctx));
+        added
+    }
+
+    /// Take a modifier out of play on the current level until the next reset
+    /// Returns false if it was not in play
+    #[func]
+    fn remove_modifier(&mut self, name: GString) -> bool {
+        let name = name.to_string();
+        let mut removed = false;
+        self.run_modifiers(|stack, ctx| removed = stack.remove(&name, ctx));
+        removed
+    }
+
+    /// Replace the modifiers in play with the board's own followed by the level's
+    fn stack_modifiers(&mut self) {
+        let mut names: Vec<String> = self.modifiers.as_slice().iter().map(GString::to_string).collect();
+        if let Some(level) = &self.level {
+            names.extend(level.bind().modifiers());
+        }
+        let (seed, expiry_rotations) = (self.model.seed(), self.expiry_rotations());
+
+        self.run_modifiers(|stack, ctx| {
+            stack.clear(ctx);
+            for name in &names {
+                match modifiers::create(name, seed, expiry_rotations) {
+                    Some(modifier) => {
+                        if !stack.add(modifier, ctx) {
+                            godot_warn!("Modifier {} is stacked twice", name);
+                        }
+                    }
+                    None => godot_warn!("Unknown modifier: {} (available: {})", name, modifiers::NAMES.join(", ")),
+                }
+            }
+        });
+    }
+
+    /// Get the level's expiry age, for the expiry modifier (0 without a level)
+    fn expiry_rotations(&self) -> u32 {
+        self.level.as_ref().map_or(0, |level| level.bind().expiry_rotations())
+    }
+
+    /// Run modifier hooks against the model, then show what they report
+    fn run_modifiers(&mut self, hook: impl FnOnce(&mut ModifierStack, &mut ModifierContext)) -> Vec<ModifierEvent> {
+        let tuning = ModifierTuning {
+            frenzy_interval: self.frenzy_interval,
+            frenzy_duration: self.frenzy_duration,
+            frenzy_warning: self.frenzy_warning,
+            frenzy_multiplier: self.frenzy_multiplier,
+            favored: (self.objective_color >= 0)
+                .then(|| Self::symbol_type_from_i32(self.objective_color))
+                .flatten(),
+            fog_radius: self.fog_radius,
+            fog_moves: self.fog_moves,
+        };
+        let mut ctx = ModifierContext::new(&mut self.model, tuning);
+        hook(&mut self.modifier_stack, &mut ctx);
+        let events = ctx.into_events();
+
+        for event in &events {
+            match *event {
+                ModifierEvent::FrenzyUpcoming { seconds } => {
+                    self.emit_event("frenzy_upcoming", &[seconds.to_variant()]);
+                }
+                ModifierEvent::FrenzyStarted { multiplier, duration } => {
+                    self.emit_event("frenzy_started", &[multiplier.to_variant(), duration.to_variant()]);
+                }
+                ModifierEvent::FrenzyEnded => self.emit_event("frenzy_ended", &[]),
+                ModifierEvent::SwapMirrored(mirror1, mirror2) => self.view.swap(mirror1, mirror2),
+                ModifierEvent::MirrorSkipped(reason) => godot_print!("Mirrored swap skipped: {}", reason),
+                ModifierEvent::RotationAnnounced(kind) => {
+                    self.emit_event("next_rotation_effect", &[GString::from(kind.name()).to_variant()]);
+                }
+                ModifierEvent::CellRevealed(pos) => {
+                    self.view.set_fogged(pos, false);
+                    self.emit_event("cell_revealed", &[pos.to_variant()]);
+                }
+                ModifierEvent::CellFogged(pos) => {
+                    self.view.set_fogged(pos, true);
+                    self.emit_event("cell_fogged", &[pos.to_variant()]);
+                }
+            }
+        }
+        events
+    }
+
+    /// Check if a cell is locked by lock_cells or by a modifier, like the column frozen by the roulette
+    fn is_locked(&self, pos: Vector2i) -> bool {
+        self.locked_cells.contains(&pos) || self.modifier_stack.locks(pos)
+    }
+
+    /// Signal emitted when a cascade is cut short at max_cascade_depth
+    #[signal]
+    fn cascade_cap_reached(depth: i32, tick: i64);
+
+    /// Signal emitted when rotation occurs
+    #[signal]
+    fn rotation_triggered(tick: i64);
+
+    /// Get how much rotations shaped this game: { rotations, rotation_matches, swap_matches,
+    /// other_matches, average_time_to_match }, counting only the matches a move or rotation made
+    /// directly; average_time_to_match is the game time from a rotation to the player's next match
+    /// (-1 before any)
+    #[func]
+    fn get_rotation_stats(&self) -> Dictionary {
+        let stats = &self.rotation_stats;
+        let mut result = Dictionary::new();
+        result.set("rotations", stats.rotations());
+        result.set("rotation_matches", stats.rotation_matches());
+        result.set("swap_matches", stats.swap_matches());
+        result.set("other_matches", stats.other_matches());
+        result.set("average_time_to_match", stats.average_time_to_match().unwrap_or(-1.0));
+        result
+    }
+
+    /// Signal emitted for each symbol falling more than fast_fall_cells cells, as it starts to fall
+    #[signal]
+    fn fast_fall(pos_from: Vector2i, pos_to: Vector2i, symbol_type: i32, tick: i64);
+
+    /// Emit fast_fall for the falls long enough to count
+    fn announce_fast_falls(&mut self, falls: &[Fall]) {
+        if self.fast_fall_cells <= 0 {
+            return;
+        }
+        for fall in falls {
+            let Some(symbol_type) = self.model.symbol_type_at(fall.to) else {
+                continue;
+            };
+            if fall.to.y - fall.from.y > self.fast_fall_cells {
+                self.emit_event(
+                    "fast_fall",
+                    &[fall.from.to_variant(), fall.to.to_variant(), (symbol_type.index() as i32).to_variant()],
+                );
+            }
+        }
+    }
+
+    /// Signal emitted for each symbol cleared, with its theme cues and the tags it carried
+    #[signal]
+    fn symbol_cleared(
+        pos: Vector2i,
+        symbol_type: i32,
+        clear_sound: GString,
+        particle_color: Color,
+        tags: Dictionary,
+        tick: i64,
+    );
+
+    /// Set the grid width, refreshing the editor preview
+    #[func]
+    fn set_grid_width(&mut self, value: i32) {
+        self.grid_width = value.max(1);
+        self.refresh_preview();
+    }
+
+    /// Set the grid height, refreshing the editor preview
+    #[func]
+    fn set_grid_height(&mut self, value: i32) {
+        self.grid_height = value.max(1);
+        self.refresh_preview();
+    }
+
+    /// Set the cell size, refreshing the editor preview
+    #[func]
+    fn set_cell_size(&mut self, value: f32) {
+        self.cell_size = value.max(1.0);
+        self.refresh_preview();
+    }
+
+    /// Set the cell padding, refreshing the editor preview
+    #[func]
+    fn set_cell_padding(&mut self, value: f32) {
+        self.cell_padding = value.max(0.0);
+        self.refresh_preview();
+    }
+
+    /// Pause or resume this board's timers, animations and input
+    #[func]
+    fn set_paused(&mut self, paused: bool) {
+        self.clock.set_paused(paused);
+        self.view.set_paused(paused);
+    }
+
+    /// Check if this board is paused
+    #[func]
+    fn is_paused(&self) -> bool {
+        self.clock.is_paused()
+    }
+
+    /// Set the rect the board is placed in, e.g. to embed it in a UI container
+    #[func]
+    fn set_board_rect(&mut self, rect: Rect2) {
+        self.board_rect = rect;
+        self.placement_changed();
+    }
+
+    /// Set the anchor the grid is aligned to
+    #[func]
+    fn set_board_anchor(&mut self, anchor: i32) {
+        self.board_anchor = anchor.clamp(0, Anchor::ALL.len() as i32 - 1);
+        self.placement_changed();
+    }
+
+    /// Set the inset from the board rect's edges
+    #[func]
+    fn set_board_margin(&mut self, margin: f32) {
+        self.board_margin = margin.max(0.0);
+        self.placement_changed();
+    }
+
+    /// Set whether cells scale to fill the board rect
+    #[func]
+    fn set_fit_to_rect(&mut self, fit: bool) {
+        self.fit_to_rect = fit;
+        self.placement_changed();
+    }
+
+    /// Refresh the editor preview, or move the running board once it is idle
+    fn placement_changed(&mut self) {
+        if Engine::singleton().is_editor_hint() {
+            self.refresh_preview();
+        } else {
+            self.placement_dirty = true;
+        }
+    }
+
+    /// Compute the grid's cell size and offset from the placement options
+    fn placement(&self) -> Placement {
+        placement::place(
+            self.board_rect,
+            self.board_margin,
+            Anchor::from_index(self.board_anchor).unwrap_or_default(),
+            self.grid_width as usize,
+            self.grid_height as usize,
+            self.cell_size,
+            self.fit_to_rect,
+        )
+    }
+
+    /// Move the existing symbol nodes to the current placement
+    fn apply_placement(&mut self) {
+        self.placement_dirty = false;
+        let placement = self.placement();
+        self.view.relayout(placement.cell_size, placement.offset);
+    }
+
+    /// Set the level to load, refreshing the editor preview
+    #[func]
+    fn set_level(&mut self, level: Option<Gd<LevelResource>>) {
+        self.level = level;
+        self.refresh_preview();
+    }
+
+    /// Rebuild the board so the editor viewport shows the current configuration
+    fn refresh_preview(&mut self) {
+        if Engine::singleton().is_editor_hint() && self.base().is_inside_tree() {
+            self.initialize_board();
+            self.base_mut().update_configuration_warnings();
+        }
+    }
+
+    /// Show or hide the debug overlay
+    #[func]
+    fn set_debug_overlay(&mut self, enabled: bool) {
+        self.debug_overlay = enabled;
+
+        if enabled && self.debug_layer.is_none() {
+            let mut layer = Node2D::new_alloc();
+            layer.set_z_index(100);
+            let callable = self.base().callable("draw_debug_overlay");
+            layer.connect("draw", &callable);
+            self.base_mut().add_child(&layer);
+            self.debug_layer = Some(layer);
+        }
+
+        if let Some(layer) = self.debug_layer.as_mut() {
+            layer.set_visible(enabled);
+            layer.queue_redraw();
+        }
+    }
+
+    /// Draw coordinates, faces, match positions and state onto the debug layer
+    #[func]
+    fn draw_debug_overlay(&mut self) {
+        let Some(mut layer) = self.debug_layer.clone() else {
+            return;
+        };
+        let Some(font) = ThemeDb::singleton().get_fallback_font() else {
+            return;
+        };
+
+        let size = self.view.symbol_size();
+        let grid = self.model.grid();
+
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                let pos = self.view.cell_position(Vector2i::new(x as i32, y as i32));
+                let coords = format!("{},{}", x, y);
+                layer
+                    .draw_string_ex(&font, pos + Vector2::new(2.0, 11.0), coords.as_str())
+                    .font_size(10)
+                    .done();
+
+                // Faces in rotation order, current face in uppercase
+                if let Some(symbol) = grid.get(x, y) {
+                    let faces: String = symbol
+                        .faces
+                        .iter()
+                        .enumerate()
+                        .map(|(i, face)| {
+                            if i == symbol.rotation_state as usize {
+                                face.letter()
+                            } else {
+                                face.letter().to_ascii_lowercase()
+                            }
+                        })
+                        .collect();
+                    layer
+                        .draw_string_ex(&font, pos + Vector2::new(2.0, size - 4.0), faces.as_str())
+                        .font_size(12)
+                        .done();
+                }
+            }
+        }
+
+        for pos in &self.last_match_positions {
+            let rect = Rect2::new(self.view.cell_position(*pos), Vector2::new(size, size));
+            layer.draw_rect_ex(rect, Color::WHITE).filled(false).width(3.0).done();
+        }
+
+        let status = format!(
+            "State: {:?}  Rotation: {:.1}/{:.1}s  Combo: {}x",
+            self.state.current(), self.rotation_timer.elapsed(), self.rotation_interval, self.model.combo()
+        );
+        layer.draw_string(&font, self.view.offset() - Vector2::new(0.0, 8.0), status.as_str());
+    }
+
+    /// Render the current logical grid into an image, e.g. for level-select thumbnails
+    #[func]
+    fn render_board_thumbnail(&self, size: Vector2i) -> Option<Gd<Image>> {
+        let image = render::rasterize_grid(self.model.grid(), size);
+        if image.is_none() {
+            godot_warn!("Cannot render board thumbnail of size {:?}", size);
+        }
+        image
+    }
+
+    /// Get the moves made since the board was dealt, to render later
+    #[func]
+    fn get_replay(&self) -> Gd<GameReplay> {
+        GameReplay::from_replay(self.replay.clone())
+    }
+
+    /// Render a frame for each step of the replay's moves `from_move..to_move`: the board
+    /// before the first move, then after each move and each clear, fall and refill it set off
+    #[func]
+    fn render_replay_frames(&self, replay: Gd<GameReplay>, from_move: i32, to_move: i32) -> Array<Gd<Image>> {
+        let (from_move, to_move) = (from_move.max(0) as usize, to_move.max(0) as usize);
+        render::replay_frames(replay.bind().replay(), from_move, to_move, self.max_cascade_depth)
+    }
+
+    /// Build a board from an image, each cell taking the symbol type nearest its pixels' color
+    /// The image is downsampled to at most the current grid size; smaller images shrink the board
+    /// Returns false if the image is empty or compressed, or the board is busy
+    #[func]
+    fn load_board_from_image(&mut self, img: Gd<Image>) -> bool {
+        self.guarded("load_board_from_image", |board| board.build_board_from_image(img)).unwrap_or(false)
+    }
+
+    /// Build a board from an image, each cell taking the symbol type nearest its pixels' color
+    fn build_board_from_image(&mut self, img: Gd<Image>) -> bool {
+        if !self.state.current().accepts_input() {
+            godot_warn!("Cannot load board from image while board is busy ({:?})", self.state.current());
+            return false;
+        }
+        if img.is_compressed() {
+            godot_warn!("Cannot read pixels from a compressed image; decompress it first");
+            return false;
+        }
+
+        let max = (self.grid_width.max(1) as usize, self.grid_height.max(1) as usize);
+        let Some((width, height, cells)) = render::sample_image(&img, max.0, max.1) else {
+            godot_warn!("Cannot load board from an empty image");
+            return false;
+        };
+
+        self.deselect_symbol();
+        self.grid_width = width as i32;
+        self.grid_height = height as i32;
+        let preplaced: Vec<_> =
+            cells.into_iter().enumerate().map(|(idx, symbol_type)| (idx % width, idx / width, symbol_type)).collect();
+        self.build_board(&preplaced);
+        true
+    }
+
+    /// Reseed the random source used for fills and refills
+    #[func]
+    fn set_seed(&mut self, seed: i64) {
+        self.guarded("set_seed", |board| board.reseed(seed));
+    }
+
+    /// Reseed the model and start the replay and rewind history over
+    fn reseed(&mut self, seed: i64) {
+        self.model.set_seed(seed);
+        // Later refills follow the new seed, so replays start from here
+        self.replay = Replay::new(self.model.clone());
+        self.rewind.clear();
+    }
+
+    /// Report the opening a board seeded with `seed` deals for the current level and thresholds:
+    /// { moves, best: { from, to, score } (empty with no move), color_counts, balance, deals, fair }
+    /// `deals` counts the boards dealt, rerolls included; the board itself is left untouched
+    #[func]
+    fn analyze_opening(&self, seed: i64) -> Dictionary {
+        let mut sim = self.model.clone();
+        sim.set_seed(seed);
+        let preplaced = self.level.as_ref().map(|level| level.bind().preplaced_symbols()).unwrap_or_default();
+        let size = (self.grid_width as usize, self.grid_height as usize);
+        let dealt = opening::deal(&mut sim, size, &preplaced, &self.fairness_policy(), MAX_DEAL_ATTEMPTS);
+        let report = opening::analyze(&sim);
+
+        let mut best = Dictionary::new();
+        if let Some(found) = report.best {
+            best.set("from", found.from);
+            best.set("to", found.to);
+            best.set("score", found.score);
+        }
+        let mut result = Dictionary::new();
+        result.set("moves", report.moves as i64);
+        result.set("best", best);
+        result.set("color_counts", report.counts.iter().copied().collect::<PackedInt32Array>());
+        result.set("balance", report.balance);
+        result.set("deals", dealt.attempts as i64);
+        result.set("fair", dealt.fair);
+        result
+    }
+
+    /// Get the opening thresholds set in the Opening group
+    fn fairness_policy(&self) -> FairnessPolicy {
+        FairnessPolicy {
+            min_moves: self.opening_min_moves.max(0) as usize,
+            min_best_score: self.opening_min_best_score,
+            min_balance: self.opening_min_balance,
+        }
+    }
+
+    /// Set the turns without a match of 4 or more before a refill sets one up (0 for never)
+    #[func]
+    fn set_pity_threshold(&mut self, threshold: i32) {
+        self.pity_threshold = threshold.max(0);
+        self.apply_pity();
+    }
+
+    /// Turn competitive play on or off; it switches pity nudges off
+    #[func]
+    fn set_competitive(&mut self, competitive: bool) {
+        self.competitive = competitive;
+        self.apply_pity();
+    }
+
+    /// Get the turns since the last match of 4 or more (or pity nudge), for tuning pity_threshold
+    #[func]
+    fn get_pity_turns(&self) -> i32 {
+        self.model.pity_turns() as i32
+    }
+
+    /// Signal emitted when a refill places a symbol that sets up a match of 4 or more
+    #[signal]
+    fn pity_nudged(pos: Vector2i, tick: i64);
+
+    /// Pass the pity threshold to the model, or none in competitive play
+    fn apply_pity(&mut self) {
+        let threshold = if self.competitive { 0 } else { self.pity_threshold as u32 };
+        self.model.set_pity_threshold(threshold);
+    }
+
+    /// Set the refill policy (0 = Random, 1 = Bag)
+    #[func]
+    fn set_refill_policy(&mut self, policy: i32) {
+        let policy = RefillPolicy::from_index(policy).unwrap_or_default();
+        self.refill_policy = policy as i32;
+        self.model.set_refill_policy(policy);
+    }
+
+    /// Get the next `count` symbol types (as type indices) a column will spawn, in spawn order
+    /// Refills always take these, so the preview holds until the refill policy, ruleset or board changes
+    #[func]
+    fn get_spawn_preview(&mut self, column: i32, count: i32) -> PackedInt32Array {
+        if column < 0 || column >= self.grid_width || count <= 0 {
+            return PackedInt32Array::new();
+        }
+        let preview = self.model.spawn_preview(column as usize, count as usize);
+        preview.iter().map(|symbol_type| symbol_type.index() as i32).collect()
+    }
+
+    /// Switch the game variant (0 = ClassicRevolving, 1 = TapRotate, 2 = ComboRush); ignored while the board is busy
+    #[func]
+    fn set_ruleset(&mut self, index: i32) {
+        self.guarded("set_ruleset", |board| board.apply_ruleset(index));
+    }
+
+    /// Switch the model to a built-in ruleset
+    fn apply_ruleset(&mut self, index: i32) {
+        // A cascade in progress must finish under the rules it started with
+        if !self.state.current().accepts_input() {
+            godot_warn!("Cannot switch ruleset while board is busy ({:?})", self.state.current());
+            return;
+        }
+        let Some(rules) = rules::builtin(index) else {
+            godot_warn!("Unknown ruleset {}", index);
+            return;
+        };
+        self.ruleset = index;
+        self.model.set_rules(rules);
+        self.refresh_preview();
+    }
+
+    /// Set what two specials ("seeker", "striped" or "color_bomb") do when swapped into each other:
+    /// "cross", "clear_board", "blast", "convert_and_fire", or "" for nothing; switching the ruleset
+    /// restores its own combos
+    #[func]
+    fn set_special_combo(&mut self, first: GString, second: GString, effect: GString) -> bool {
+        let kinds = (SpecialKind::from_name(&first.to_string()), SpecialKind::from_name(&second.to_string()));
+        let (Some(first), Some(second)) = kinds else {
+            godot_warn!("Unknown special kind in combo: {} + {}", first, second);
+            return false;
+        };
+        let effect = effect.to_string();
+        if effect.is_empty() {
+            self.model.set_special_combo(first, second, None);
+            return true;
+        }
+        let Some(effect) = ComboEffect::from_name(&effect) else {
+            godot_warn!("Unknown combo effect: {}", effect);
+            return false;
+        };
+        self.model.set_special_combo(first, second, Some(effect));
+        true
+    }
+
+    /// Check if the player has any move that creates a match
+    #[func]
+    fn has_moves(&self) -> bool {
+        self.model.find_hint().is_some()
+    }
+
+    /// Get the seed last passed to `set_seed` (-1 when seeded from entropy)
+    #[func]
+    fn get_seed(&self) -> i64 {
+        self.model.seed()
+    }
+
+    /// Run a debug console command and return its result
+    /// Commands: help, set_cell x y type, force_rotate, give_special kind x y,
+    /// load_layout rows (separated by '/'), seed n
+    #[func]
+    fn debug_command(&mut self, cmd: GString) -> GString {
+        let cmd = cmd.to_string();
+        self.input_log.record(format!("debug {}", cmd));
+        let result = match self.guarded("debug_command", |board| board.run_debug_command(&cmd)) {
+            Some(Ok(message)) => format!("ok: {}", message),
+            Some(Err(message)) => format!("error: {}", message),
+            None => "error: command panicked; the board was reset".to_string(),
+        };
+        godot_print!("> {} -> {}", cmd, result);
+        result.into()
+    }
+
+    /// Parse and execute a debug command
+    fn run_debug_command(&mut self, cmd: &str) -> Result<String, String> {
+        let mut parts = cmd.split_whitespace();
+        let name = parts.next().ok_or("empty command")?;
+        let args: Vec<&str> = parts.collect();
+
+        let parse_i32 = |arg: Option<&&str>| -> Result<i32, String> {
+            let arg = arg.ok_or("missing argument")?;
+            arg.parse().map_err(|_| format!("not a number: {}", arg))
+        };
+
+        let board_idle = self.state.current().accepts_input();
+
+        match name {
+            "help" => Ok("set_cell x y type | force_rotate | give_special kind x y | load_layout rows | seed n".to_string()),
+            "set_cell" => {
+                let pos = Vector2i::new(parse_i32(args.first())?, parse_i32(args.get(1))?);
+                let type_arg = args.get(2).ok_or("missing symbol type")?;
+                let symbol_type = SymbolType::parse(type_arg).ok_or(format!("unknown symbol type: {}", type_arg))?;
+                if !board_idle {
+                    return Err("board is busy".to_string());
+                }
+                self.deselect_symbol();
+                self.set_cell_type(pos, symbol_type).map_err(|err| err.to_string())?;
+                Ok(format!("{:?} is now {}", pos, symbol_type.name()))
+            }
+            "force_rotate" => {
+                if !board_idle {
+                    return Err("board is busy".to_string());
+                }
+                self.deselect_symbol();
+                self.rotation_timer.reset();
+                self.trigger_rotation();
+                Ok("rotation triggered".to_string())
+            }
+            "give_special" => {
+                let kind = args.first().ok_or("missing special kind")?;
+                let special = Special::from_name(kind)
+                    .ok_or(format!("unknown special: {} (available: {})", kind, Special::NAMES.join(", ")))?;
+                let pos = Vector2i::new(parse_i32(args.get(1))?, parse_i32(args.get(2))?);
+                if !board_idle {
+                    return Err("board is busy".to_string());
+                }
+                match special {
+                    Special::Kind(special) => self.model.set_kind(pos, special),
+                    Special::Wild => self.model.set_wild(pos),
+                    Special::Seeker => self.model.set_seeker(pos),
+                }
+                .map_err(|err| err.to_string())?;
+                self.repaint_cell(pos);
+                self.emphasize_symbols();
+                Ok(format!("{:?} is now a {}", pos, kind))
+            }
+            "load_layout" => {
+                if !board_idle {
+                    return Err("board is busy".to_string());
+                }
+                let rows = layout::parse(&args.concat())?;
+                self.deselect_symbol();
+                self.apply_layout(&rows);
+                Ok(format!("loaded {}x{} layout", self.grid_width, self.grid_height))
+            }
+            "seed" => {
+                let seed = args.first().ok_or("missing seed")?;
+                let seed: i64 = seed.parse().map_err(|_| format!("not a number: {}", seed))?;
+                self.reseed(seed);
+                self.reset_board();
+                Ok(format!("board reset with seed {}", seed))
+            }
+            _ => Err(format!("unknown command: {}", name)),
+        }
+    }
+
+    /// Replace the symbol at a position, updating its visual
+    fn set_cell_type(&mut self, pos: Vector2i, symbol_type: SymbolType) -> Result<(), BoardError> {
+        self.model.set_cell(pos, symbol_type)?;
+        self.view.set_cell_color(pos, symbol_type.color());
+        Ok(())
+    }
+
+    /// Rebuild the board from a parsed layout, resizing the grid to fit; blockers become stones
+    fn apply_layout(&mut self, rows: &layout::Layout) {
+        self.grid_height = rows.len() as i32;
+        self.grid_width = rows.first().map_or(0, Vec::len) as i32;
+
+        let mut preplaced = Vec::new();
+        let mut overlays = Vec::new();
+        let mut holes = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                match cell {
+                    LayoutCell::Symbol(symbol_type) => preplaced.push((x, y, *symbol_type)),
+                    LayoutCell::Random => {}
+                    LayoutCell::Blocker => overlays.push((Vector2i::new(x as i32, y as i32), CellOverlay::Stone(1))),
+                    LayoutCell::Hole => holes.push(Vector2i::new(x as i32, y as i32)),
+                }
+            }
+        }
+
+        self.model.set_overlays(overlays);
+        self.model.set_holes(holes);
+        self.build_board(&preplaced);
+    }
+
+    /// Load the board from an ASCII layout (see the `layout` module for the format)
+    /// Returns false if the layout is invalid or the board is busy
+    #[func]
+    fn import_layout(&mut self, text: GString) -> bool {
+        self.guarded("import_layout", |board| board.load_layout_text(text)).unwrap_or(false)
+    }
+
+    /// Parse a text layout and put it on the board
+    fn load_layout_text(&mut self, text: GString) -> bool {
+        if !self.state.current().accepts_input() {
+            godot_warn!("Cannot import layout while board is busy ({:?})", self.state.current());
+            return false;
+        }
+
+        match layout::parse(&text.to_string()) {
+            Ok(rows) => {
+                self.deselect_symbol();
+                self.apply_layout(&rows);
+                true
+            }
+            Err(message) => {
+                godot_warn!("Invalid layout: {}", message);
+                false
+            }
+        }
+    }
+
+    /// Replace the board with the first-play onboarding board
+    /// Returns { swap, first_match, cascade } cell arrays (empty if the board is too small or busy);
+    /// `swap` is in tutorial step form, so the result can seed a tutorial step
+    #[func]
+    fn load_onboarding_board(&mut self) -> Dictionary {
+        self.guarded("load_onboarding_board", |board| board.deal_onboarding_board()).unwrap_or_default()
+    }
+
+    /// Deal the onboarding board and describe its scripted first moves
+    fn deal_onboarding_board(&mut self) -> Dictionary {
+        if !self.state.current().accepts_input() {
+            godot_warn!("Cannot load onboarding board while board is busy ({:?})", self.state.current());
+            return Dictionary::new();
+        }
+
+        self.deselect_symbol();
+        self.build_board(&[]);
+
+        let Some(onboarding) = self.model.generate_onboarding() else {
+            godot_warn!("Cannot generate an onboarding board of {}x{}", self.grid_width, self.grid_height);
+            return Dictionary::new();
+        };
+        self.view.rebuild(self.model.grid());
+        self.emphasize_symbols();
+
+        let cells = |cells: &[Vector2i]| -> Array<Vector2i> { cells.iter().copied().collect() };
+        let mut result = Dictionary::new();
+        result.set("swap", cells(&[onboarding.swap.0, onboarding.swap.1]));
+        result.set("first_match", cells(&onboarding.first_match));
+        result.set("cascade", cells(&onboarding.cascade));
+        result
+    }
+
+    /// Export the board's current symbols as an ASCII layout
+    #[func]
+    fn export_layout(&self) -> GString {
+        let grid = self.model.grid();
+        let rows: layout::Layout = (0..grid.height)
+            .map(|y| {
+                (0..grid.width)
+                    .map(|x| match grid.get(x, y) {
+                        Some(symbol) => LayoutCell::Symbol(symbol.current_type()),
+                        None if grid.is_stone(x, y) => LayoutCell::Blocker,
+                        None if !grid.is_enabled(x, y) => LayoutCell::Hole,
+                        None => LayoutCell::Random,
+                    })
+                    .collect()
+            })
+            .collect();
+        layout::format(&rows).into()
+    }
+
+    /// List every live-tunable parameter as dictionaries of name, min, max, step and value
+    #[func]
+    fn get_tunables(&self) -> Array<Dictionary> {
+        tuning::TUNABLES
+            .iter()
+            .map(|tunable| {
+                let mut entry = Dictionary::new();
+                entry.set("name", tunable.name);
+                entry.set("min", tunable.min);
+                entry.set("max", tunable.max);
+                entry.set("step", tunable.step);
+                entry.set("value", self.tunable_value(tunable.name).unwrap_or_default());
+                entry
+            })
+            .collect()
+    }
+
+    /// Set a tunable parameter by name, clamped to its range
+    /// Returns false for unknown names or non-numeric values
+    #[func]
+    fn set_tunable(&mut self, name: GString, value: Variant) -> bool {
+        let name = name.to_string();
+        let Some(tunable) = tuning::find(&name) else {
+            godot_warn!("Unknown tunable: {}", name);
+            return false;
+        };
+        let Some(value) = variant_to_f64(&value) else {
+            godot_warn!("Non-numeric value for tunable {}", name);
+            return false;
+        };
+
+        let value = tunable.clamp(value);
+        let field = match tunable.name {
+            "rotation_interval" => &mut self.rotation_interval,
+            "clear_duration" => &mut self.clear_duration,
+            "fall_duration_per_cell" => &mut self.fall_duration_per_cell,
+            "spawn_duration" => &mut self.spawn_duration,
+            "rotation_duration" => &mut self.rotation_duration,
+            "results_event_spacing" => &mut self.results_event_spacing,
+            "idle_threshold" => &mut self.idle_threshold,
+            "idle_tick_interval" => &mut self.idle_tick_interval,
+            "idle_shuffle_seconds" => &mut self.idle_shuffle_seconds,
+            _ => return false,
+        };
+        *field = value;
+        true
+    }
+
+    /// Read a tunable parameter by name
+    fn tunable_value(&self, name: &str) -> Option<f64> {
+        match name {
+            "rotation_interval" => Some(self.rotation_interval),
+            "clear_duration" => Some(self.clear_duration),
+            "fall_duration_per_cell" => Some(self.fall_duration_per_cell),
+            "spawn_duration" => Some(self.spawn_duration),
+            "rotation_duration" => Some(self.rotation_duration),
+            "results_event_spacing" => Some(self.results_event_spacing),
+            "idle_threshold" => Some(self.idle_threshold),
+            "idle_tick_interval" => Some(self.idle_tick_interval),
+            "idle_shuffle_seconds" => Some(self.idle_shuffle_seconds),
+            _ => None,
+        }
+    }
+
+    /// Limit how often signals are emitted
+    /// Keys are signal names, values are the maximum emissions per second
+    #[func]
+    fn set_event_rate_limits(&mut self, limits: Dictionary) {
+        self.throttle.clear();
+        for (key, value) in limits.iter_shared() {
+            let Ok(event) = key.try_to::<GString>() else {
+                godot_warn!("Ignoring rate limit with non-string key: {}", key);
+                continue;
+            };
+            let Some(max_per_second) = variant_to_f64(&value) else {
+                godot_warn!("Ignoring non-numeric rate limit for {}", event);
+                continue;
+            };
+            self.throttle.set_rate_limit(&event.to_string(), max_per_second);
+        }
+    }
+
+    /// Get the configured signal rate limits
+    #[func]
+    fn get_event_rate_limits(&self) -> Dictionary {
+        let mut limits = Dictionary::new();
+        for (event, max_per_second) in self.throttle.rate_limits() {
+            limits.set(GString::from(event), max_per_second);
+        }
+        limits
+    }
+
+    /// Set the most snapshots and HUD summaries per second (zero or less removes the cap)
+    #[func]
+    fn set_snapshot_rate(&mut self, max_per_second: f64) {
+        self.snapshot_rate = max_per_second;
+        self.snapshot_throttle.set_rate_limit(SNAPSHOT, max_per_second);
+        self.snapshot_throttle.set_rate_limit(HUD, max_per_second);
+    }
+
+    /// Preset for desktops and recent phones: everything on
+    #[constant]
+    const PERFORMANCE_HIGH: i32 = 0;
+
+    /// Preset for mid-range phones: pooled symbol nodes and capped juice signals
+    #[constant]
+    const PERFORMANCE_MEDIUM: i32 = 1;
+
+    /// Preset for low-end phones: also fewer snapshots and quicker animations without flourishes
+    #[constant]
+    const PERFORMANCE_LOW: i32 = 2;
+
+    /// Apply a PERFORMANCE_* preset. It sets node pooling, the rate limits of the
+    /// juice signals (symbol_cleared, fast_fall, idle_tick), the snapshot rate and
+    /// animation fidelity, replacing earlier rate limits on those signals
+    #[func]
+    fn set_performance_preset(&mut self, level: i32) {
+        let Some(preset) = PerformancePreset::from_index(level) else {
+            godot_warn!("Unknown performance preset: {}", level);
+            return;
+        };
+        self.performance = preset;
+        self.view.set_pooling(preset.pool_nodes());
+        for event in performance::JUICE_EVENTS {
+            self.throttle.set_rate_limit(event, preset.juice_rate());
+        }
+        self.set_snapshot_rate(preset.snapshot_rate());
+        self.save_profile();
+    }
+
+    /// Get the current PERFORMANCE_* preset
+    #[func]
+    fn get_performance_preset(&self) -> i32 {
+        self.performance.index()
+    }
+
+    /// Get a compact snapshot of the board for streaming overlays and dashboards:
+    /// { width, height, types, faces, specials, ice, jelly, multipliers, score, combo, state, time,
+    /// rotation_in, idle }; the grids are row strings (see the `snapshot` module). Calls faster
+    /// than `snapshot_rate` return a copy of the previous snapshot
+    #[func]
+    fn get_board_snapshot(&mut self) -> Dictionary {
+        if let Some(cached) = &self.snapshot_cache {
+            if !self.snapshot_throttle.allow(SNAPSHOT, self.clock.now()) {
+                return cached.duplicate_shallow();
+            }
+        }
+
+        let grid = self.model.grid();
+        let rows = |rows: Vec<String>| -> PackedStringArray { rows.iter().map(GString::from).collect() };
+        let mut snapshot = Dictionary::new();
+        snapshot.set("width", grid.width as i64);
+        snapshot.set("height", grid.height as i64);
+        snapshot.set("types", rows(snapshot::type_rows(grid)));
+        snapshot.set("faces", rows(snapshot::face_rows(grid)));
+        snapshot.set("specials", rows(snapshot::special_rows(grid)));
+        snapshot.set("ice", rows(snapshot::ice_rows(grid)));
+        snapshot.set("jelly", rows(snapshot::jelly_rows(grid, self.model.jelly())));
+        snapshot.set("multipliers", rows(snapshot::multiplier_rows(grid, self.model.cell_multipliers())));
+        snapshot.set("score", self.score);
+        snapshot.set("combo", self.model.combo());
+        snapshot.set("state", self.state.current().name());
+        snapshot.set("time", self.clock.now());
+        snapshot.set("rotation_in", (self.rotation_interval - self.rotation_timer.elapsed()).max(0.0));
+        snapshot.set("idle", self.idle_time);
+
+        self.snapshot_cache = Some(snapshot.duplicate_shallow());
+        snapshot
+    }
+
+    /// Get the snapshot from `get_board_snapshot` as JSON text
+    #[func]
+    fn get_board_snapshot_json(&mut self) -> GString {
+        Json::stringify(&self.get_board_snapshot().to_variant())
+    }
+
+    /// Get a compact summary of the board for HUD widgets and minimaps:
+    /// { counts, blockers, seekers, expiring, objective: { symbol_type, on_board, cleared }, score, moves }
+    /// `counts` holds the symbols of each type by type index, `expiring` the cells that turn into
+    /// blockers on the next rotation; calls faster than `snapshot_rate` return a copy of the previous summary
+    #[func]
+    fn get_hud_summary(&mut self) -> Dictionary {
+        if let Some(cached) = &self.hud_cache {
+            if !self.snapshot_throttle.allow(HUD, self.clock.now()) {
+                return cached.duplicate_shallow();
+            }
+        }
+
+        let summary = hud::summarize(self.model.grid(), self.model.expiry());
+        let mut objective = Dictionary::new();
+        let objective_type = usize::try_from(self.objective_color).ok().filter(|idx| *idx < SymbolType::ALL.len());
+        objective.set("symbol_type", objective_type.map_or(-1, |idx| idx as i32));
+        objective.set("on_board", objective_type.map_or(0, |idx| summary.counts[idx]));
+        objective.set("cleared", self.objective_cleared);
+
+        let mut result = Dictionary::new();
+        result.set("counts", summary.counts.iter().copied().collect::<PackedInt32Array>());
+        result.set("blockers", summary.blockers);
+        result.set("seekers", summary.seekers);
+        result.set("expiring", summary.expiring.into_iter().collect::<Array<Vector2i>>());
+        result.set("objective", objective);
+        result.set("score", self.score);
+        result.set("moves", self.moves_made);
+
+        self.hud_cache = Some(result.duplicate_shallow());
+        result
+    }
+
+    /// Get how much of the level's weighted objectives is done, from 0 to 100 (0 without objectives)
+    #[func]
+    fn get_completion_percent(&self) -> f64 {
+        self.objectives.completion_percent()
+    }
+
+    /// Get the level's objectives as { kind, symbol_type, target, weight, progress, complete }
+    /// `symbol_type` is -1 for anything but collect objectives
+    #[func]
+    fn get_objectives(&self) -> Array<Dictionary> {
+        self.objectives
+            .objectives()
+            .iter()
+            .map(|objective| {
+                let symbol_type = match objective.kind {
+                    ObjectiveKind::Collect(symbol_type) => symbol_type.index() as i32,
+                    _ => -1,
+                };
+                let mut entry = Dictionary::new();
+                entry.set("kind", objective.kind.name());
+                entry.set("symbol_type", symbol_type);
+                entry.set("target", objective.target);
+                entry.set("weight", objective.weight);
+                entry.set("progress", objective.progress);
+                entry.set("complete", objective.is_complete());
+                entry
+            })
+            .collect()
+    }
+
+    /// Signal emitted when the level's completion first reaches 25, 50 or 75 percent
+    #[signal]
+    fn completion_threshold(percent: i32, tick: i64);
+
+    /// Signal emitted when objective progress moves, either way (a rewind takes it back), with the
+    /// weighted completion from 0 to 100 and the symbols of the objective color cleared
+    #[signal]
+    fn completion_changed(percent: f64, objective_cleared: i32, tick: i64);
+
+    /// Get an estimate of the board's footprint, for watching it on constrained devices:
+    /// { grid_width, grid_height, grid_bytes, symbol_nodes, tile_nodes, clearing_nodes, pooled_nodes, animating_nodes,
+    /// replay_moves, replay_bytes, input_log_entries, input_log_capacity, snapshot_cached, hud_cached }
+    /// Byte counts cover the Rust-side data only, not engine allocations
+    #[func]
+    fn get_memory_report(&self) -> Dictionary {
+        let grid = self.model.grid();
+        let nodes = self.view.node_counts();
+        let mut report = Dictionary::new();
+        report.set("grid_width", grid.width as i64);
+        report.set("grid_height", grid.height as i64);
+        report.set("grid_bytes", (grid.cells.capacity() * size_of::<Option<Symbol>>()) as i64);
+        report.set("symbol_nodes", nodes.symbols as i64);
+        report.set("tile_nodes", nodes.tiles as i64);
+        report.set("clearing_nodes", nodes.clearing as i64);
+        report.set("pooled_nodes", nodes.pooled as i64);
+        report.set("animating_nodes", nodes.animating as i64);
+        report.set("replay_moves", self.replay.move_count() as i64);
+        report.set("replay_bytes", self.replay.approximate_bytes() as i64);
+        report.set("input_log_entries", self.input_log.entry_count() as i64);
+        report.set("input_log_capacity", diagnostics::MAX_ENTRIES as i64);
+        report.set("snapshot_cached", self.snapshot_cache.is_some());
+        report.set("hud_cached", self.hud_cache.is_some());
+        report
+    }
+
+    /// Emit a signal unless its rate limit suppresses it, appending the next tick to its arguments
+    fn emit_event(&mut self, name: &str, args: &[Variant]) {
+        if !self.throttle.allow(name, self.clock.now()) {
+            return;
+        }
+        self.event_tick += 1;
+        let mut args = args.to_vec();
+        args.push(self.event_tick.to_variant());
+        self.base_mut().emit_signal(name, &args);
+    }
+
+    /// Get the tick of the last emitted signal (0 before the first)
+    /// Every signal's last argument is its tick: it goes up by one per emitted signal and is never
+    /// reset, so listeners can order and correlate events across levels and restarts
+    #[func]
+    fn get_current_tick(&self) -> i64 {
+        self.event_tick
+    }
+
+    /// Signal emitted whenever the game state changes
+    #[signal]
+    fn state_changed(from: GString, to: GString, tick: i64);
+
+    /// Get the name of the current game state
+    #[func]
+    fn get_state_name(&self) -> GString {
+        self.state.current().name().into()
+    }
+
+    /// Move the state machine to `next`, logging and rejecting illegal transitions
+    fn set_state(&mut self, next: GameState) -> bool {
+        match self.state.transition(next) {
+            Ok(previous) => {
+                self.emit_state_changed(previous, next);
+                true
+            }
+            Err(err) => {
+                godot_warn!("{}", err);
+                false
+            }
+        }
+    }
+
+    /// Emit state_changed if the state actually changed
+    fn emit_state_changed(&mut self, from: GameState, to: GameState) {
+        if from != to {
+            self.emit_event(
+                "state_changed",
+                &[GString::from(from.name()).to_variant(), GString::from(to.name()).to_variant()],
+            );
+        }
+    }
+
+    /// Signal emitted periodically while the board is idle
+    #[signal]
+    fn idle_tick(seconds_idle: f64, tick: i64);
+
+    /// Signal emitted with a suggested swap; both cells are the same for a tap
+    #[signal]
+    fn hint_available(from: Vector2i, to: Vector2i, tick: i64);
+
+    /// Advance the idle timer, emitting ticks and the optional hint
+    fn update_idle(&mut self, delta: f64) {
+        self.idle_time += delta;
+        if self.idle_shuffle_enabled && self.idle_shuffle_timer.advance(delta, self.idle_shuffle_seconds) {
+            self.idle_shuffle();
+        }
+        if self.idle_time < self.idle_threshold {
+            return;
+        }
+
+        if self.idle_hint_enabled && !self.idle_hint_given {
+            self.idle_hint_given = true;
+            self.show_hint();
+        }
+
+        if self.idle_tick_timer.advance(delta, self.idle_tick_interval) {
+            let seconds_idle = self.idle_time;
+            self.emit_event("idle_tick", &[seconds_idle.to_variant()]);
+        }
+    }
+
+    /// Reset idle tracking after player input
+    fn reset_idle(&mut self) {
+        self.idle_time = 0.0;
+        self.idle_tick_timer.reset();
+        self.idle_hint_given = false;
+        self.idle_shuffle_timer.reset();
+        self.view.clear_emphasis(EmphasisSource::Hint);
+    }
+
+    /// Signal emitted when an idle board shuffled a row, or -1 when it gave a free rotation instead
+    #[signal]
+    fn idle_shuffled(row: i32, tick: i64);
+
+    /// Give a free rotation or shuffle a random row, if the board is waiting and has a move left
+    fn idle_shuffle(&mut self) {
+        let waiting = self.state.current() == GameState::Ready && !self.sandbox_mode;
+        if !waiting || self.tutorial.current().is_some() || self.model.find_hint().is_none() {
+            return;
+        }
+
+        if !self.idle_shuffle_row && self.model.rules().rotates() {
+            godot_print!("Idle for {:.1}s - free rotation", self.idle_time);
+            self.rotation_timer.reset();
+            self.emit_event("idle_shuffled", &[(-1).to_variant()]);
+            self.trigger_rotation();
+            return;
+        }
+
+        let row = self.model.random_row();
+        godot_print!("Idle for {:.1}s - shuffling row {}", self.idle_time, row);
+        self.model.shuffle_row(row);
+        self.replay.record(ReplayMove::Rotate(RouletteEffect::ShuffleRow(row)));
+        self.view.finish_rotation(self.model.grid());
+        self.show_symbol_ages();
+        self.emit_event("idle_shuffled", &[(row as i32).to_variant()]);
+        self.queue_command(BoardCommand::Clear);
+    }
+
+    /// Emit a hint for the first available move, if any, emphasizing its cells until the player acts
+    #[func]
+    fn show_hint(&mut self) -> bool {
+        let Some((from, to)) = self.model.find_hint() else {
+            godot_print!("No moves available for hint");
+            return false;
+        };
+        self.view.set_emphasis(EmphasisSource::Hint, &[from, to], self.hint_emphasis as f32);
+        self.emit_event("hint_available", &[from.to_variant(), to.to_variant()]);
+        true
+    }
+
+    /// Predict a swap's outcome without touching the board, playing the cascade out without refills
+    /// Returns legal, reason (the swap_rejected code when not legal), score, steps (as in
+    /// chain_resolved), seekers (cells left holding a new seeker), and created and fired: every special
+    /// the cascade left behind and set off, as [{ pos, special }] (see `ChainSpecial::name`)
+    #[func]
+    fn simulate_move(&mut self, from: Vector2i, to: Vector2i) -> Dictionary {
+        self.guarded("simulate_move", |board| board.simulate_swap(from, to)).unwrap_or_default()
+    }
+
+    /// Play a swap out on a copy of the model and describe what it would do
+    fn simulate_swap(&self, from: Vector2i, to: Vector2i) -> Dictionary {
+        let mut result = Dictionary::new();
+        match solver::simulate_move(&self.model, from, to) {
+            Ok(outcome) => {
+                let steps = outcome.chain.as_ref().map(chain_steps).unwrap_or_default();
+                result.set("legal", true);
+                result.set("reason", "");
+                result.set("score", outcome.score);
+                result.set("steps", steps);
+                result.set("seekers", outcome.seekers.into_iter().collect::<Array<Vector2i>>());
+                result.set("created", specials_array(&outcome.specials.created));
+                result.set("fired", specials_array(&outcome.specials.fired));
+            }
+            Err(reason) => {
+                result.set("legal", false);
+                result.set("reason", reason.code());
+                result.set("score", 0);
+                result.set("steps", Array::<Dictionary>::new());
+                result.set("seekers", Array::<Vector2i>::new());
+                result.set("created", Array::<Dictionary>::new());
+                result.set("fired", Array::<Dictionary>::new());
+            }
+        }
+        result
+    }
+
+    /// Predict the matches the next rotation will create, as dictionaries of
+    /// positions (Array of Vector2i), symbol_type, horizontal and shape ("line", "l", "t" or "cross")
+    #[func]
+    fn get_post_rotation_matches(&self) -> Array<Dictionary> {
+        // Other roulette effects don't turn faces, so there is nothing to predict
+        if self.modifier_stack.upcoming_rotation().is_some_and(|kind| kind != RouletteKind::RotateFaces) {
+            return Array::new();
+        }
+        self.model
+            .post_rotation_matches()
+            .into_iter()
+            .map(|(m, symbol_type)| {
+                let mut entry = Dictionary::new();
+                entry.set("positions", m.positions.iter().copied().collect::<Array<Vector2i>>());
+                entry.set("symbol_type", symbol_type.index() as i32);
+                entry.set("horizontal", m.horizontal);
+                entry.set("shape", m.shape.name());
+                entry
+            })
+            .collect()
+    }
+
+    /// Signal emitted for each step of the level-end results fanfare
+    #[signal]
+    fn result_event(kind: GString, value: i32, tick: i64);
+
+    /// Signal emitted once every results event has been released
+    #[signal]
+    fn results_finished(stars: i32, tick: i64);
+
+    /// Signal emitted as the level-end sweep clears a row, with the rows swept before it and the points scored
+    #[signal]
+    fn sweep_row_cleared(row: i32, step: i32, points: i32, tick: i64);
+
+    /// End the level and start the results fanfare sequence
+    /// With objectives completed, a sweep clears the rest of the board row by row first, bottom up
+    /// Returns false if the board is still resolving a move
+    #[func]
+    fn end_level(&mut self, objectives_completed: i32) -> bool {
+        self.guarded("end_level", |board| board.finish_level(objectives_completed)).unwrap_or(false)
+    }
+
+    /// End the level and show its results
+    fn finish_level(&mut self, objectives_completed: i32) -> bool {
+        if !self.state.current().accepts_input() {
+            godot_print!("Cannot end level while board is busy ({:?})", self.state.current());
+            return false;
+        }
+
+        self.deselect_symbol();
+
+        if objectives_completed > 0 && self.sweep_points > 0 && self.set_state(GameState::Sweeping) {
+            // The sweep runs through the command queue, so no move can slip in between its rows
+            self.sweep_objectives = objectives_completed;
+            for step in 0..self.grid_height {
+                self.commands.push(BoardCommand::Sweep {
+                    row: self.grid_height - 1 - step,
+                    step,
+                });
+            }
+            self.run_commands();
+            return true;
+        }
+        self.start_results(objectives_completed);
+        true
+    }
+
+    /// Rate the score and start the results fanfare sequence
+    fn start_results(&mut self, objectives_completed: i32) {
+        let stars = results::stars_for_score(self.score, self.star_thresholds.as_slice());
+        let record = (self.score > self.best_score).then_some(self.score);
+        self.best_score = self.best_score.max(self.score);
+        if record.is_some() {
+            self.save_profile();
+        }
+
+        self.results = ResultsSequence::new(objectives_completed.max(0), stars, record);
+        self.set_state(GameState::Results);
+
+        godot_print!("Level ended with score {} ({} stars)", self.score, stars);
+    }
+
+    /// Release due results events and finish the sequence when empty
+    fn advance_results(&mut self, delta: f64) {
+        let spacing = self.results_event_spacing;
+        if let Some(event) = self.results.advance(delta, spacing) {
+            self.emit_event(
+                "result_event",
+                &[GString::from(event.kind()).to_variant(), event.value().to_variant()],
+            );
+        }
+
+        if self.results.is_finished() {
+            let stars = self.results.stars();
+            self.set_state(GameState::Finished);
+            self.emit_event("results_finished", &[stars.to_variant()]);
+        }
+    }
+
+    /// Set the sound cue played when symbols of a type clear
+    #[func]
+    fn set_symbol_clear_sound(&mut self, symbol_type: i32, sound: GString) {
+        let Some(symbol_type) = Self::symbol_type_from_i32(symbol_type) else {
+            return;
+        };
+        self.theme.set_clear_sound(symbol_type, sound);
+    }
+
+    /// Get the sound cue played when symbols of a type clear
+    #[func]
+    fn get_symbol_clear_sound(&self, symbol_type: i32) -> GString {
+        Self::symbol_type_from_i32(symbol_type)
+            .map(|t| self.theme.cue(t).clear_sound.clone())
+            .unwrap_or_default()
+    }
+
+    /// Set the particle color used when symbols of a type clear
+    #[func]
+    fn set_symbol_particle_color(&mut self, symbol_type: i32, color: Color) {
+        let Some(symbol_type) = Self::symbol_type_from_i32(symbol_type) else {
+            return;
+        };
+        self.theme.set_particle_color(symbol_type, color);
+    }
+
+    /// Get the particle color used when symbols of a type clear
+    #[func]
+    fn get_symbol_particle_color(&self, symbol_type: i32) -> Color {
+        Self::symbol_type_from_i32(symbol_type)
+            .map(|t| self.theme.cue(t).particle_color)
+            .unwrap_or(Color::WHITE)
+    }
+
+    /// Convert a script-facing symbol type index, warning on bad input
+    fn symbol_type_from_i32(index: i32) -> Option<SymbolType> {
+        if index < 0 || index as usize >= SymbolType::ALL.len() {
+            godot_warn!("Invalid symbol type index: {}", index);
+            return None;
+        }
+        Some(SymbolType::from_index(index as usize))
+    }
+
+    /// Initialize the game board
+    #[func]
+    fn initialize_board(&mut self) {
+        self.guarded("initialize_board", |board| board.deal_board());
+    }
+
+    /// Deal the board from the level or the current settings
+    fn deal_board(&mut self) {
+        // Take the board size and tutorial from the level, if any
+        let (preplaced, tutorial_steps, has_blockers, wells, overlays, cell_tags, ingredients) = match &self.level {
+            Some(level) => {
+                let level = level.bind();
+                let (width, height) = level.size();
+                self.grid_width = width as i32;
+                self.grid_height = height as i32;
+                (
+                    level.preplaced_symbols(),
+                    level.tutorial_steps(),
+                    level.has_any_blocker(),
+                    level.gravity_wells(),
+                    level.cell_overlays(),
+                    level.cell_tags(),
+                    level.ingredient_cells(),
+                )
+            }
+            None => (Vec::new(), Array::new(), false, Vec::new(), Vec::new(), Vec::new(), Vec::new()),
+        };
+
+        self.model.set_gravity_wells(wells);
+        self.model.set_overlays(overlays);
+        let holes = self.level.as_ref().map(|level| level.bind().hole_cells());
+        self.model.set_holes(holes.unwrap_or_default());
+        let cell_multipliers = self.level.as_ref().map(|level| level.bind().cell_multipliers());
+        self.model.set_cell_multipliers(cell_multipliers.unwrap_or_default());
+        let jelly = self.level.as_ref().map(|level| level.bind().jelly());
+        self.model.set_jelly(jelly.unwrap_or_default());
+        self.level_won = None;
+        let collect = self.level.as_ref().map(|level| {
+            let level = level.bind();
+            (level.collect_type(), level.spawn_guarantee())
+        });
+        let (collect_type, guarantee) = collect.unwrap_or_default();
+        let objectives = self.level.as_ref().map(|level| level.bind().objectives());
+        self.objectives = ObjectiveSet::new(objectives.unwrap_or_default());
+        if let Some(collect_type) = collect_type {
+            self.set_objective_color(collect_type.index() as i32);
+        }
+        self.model.set_spawn_guarantee(guarantee);
+        self.stack_modifiers();
+
+        self.build_board(&preplaced);
+        for (pos, key, value) in cell_tags {
+            if let Err(err) = self.model.set_tag(pos, &key, Some(value)) {
+                godot_warn!("Cannot tag {:?} from level data: {}", pos, err);
+            }
+        }
+        for pos in ingredients {
+            match self.model.set_kind(pos, SymbolKind::Ingredient) {
+                Ok(()) => self.repaint_cell(pos),
+                Err(err) => godot_warn!("Cannot place an ingredient at {:?} from level data: {}", pos, err),
+            }
+        }
+
+        if has_blockers {
+            self.teach(teaching::TOPIC_BLOCKERS);
+        }
+
+        if tutorial_steps.is_empty() {
+            self.stop_tutorial();
+        } else {
+            self.start_tutorial(tutorial_steps);
+        }
+
+        godot_print!("Board initialized with {} symbols", self.grid_width * self.grid_height);
+    }
+
+    /// Build a random grid of the current size with some preplaced symbols
+    fn build_board(&mut self, preplaced: &[(usize, usize, SymbolType)]) {
+        let placement = self.placement();
+        self.placement_dirty = false;
+
+        // Invalidate animations still running against the old board
+        self.generation += 1;
+        self.view.kill_tweens();
+
+        // Deal until the player has a move and a fair start, so levels are always solvable from the start
+        let (width, height) = (self.grid_width as usize, self.grid_height as usize);
+        let policy = self.fairness_policy();
+        let dealt = opening::deal(&mut self.model, (width, height), preplaced, &policy, MAX_DEAL_ATTEMPTS);
+        if !dealt.fair {
+            godot_warn!("No fair opening after {} deals of a {}x{} board", dealt.attempts, width, height);
+        }
+        self.sandbox_history.clear();
+        self.replay = Replay::new(self.model.clone());
+        self.rewind.clear();
+        self.symbols_away = false;
+
+        // Create visual nodes
+        self.attach_view();
+        self.view.configure(width, height, placement.cell_size, self.cell_padding, placement.offset);
+        self.view.rebuild(self.model.grid());
+        self.show_tiles();
+        self.show_fog();
+        self.emphasize_symbols();
+    }
+
+    /// Add the view's container node as a child, once
+    fn attach_view(&mut self) {
+        let root = self.view.root().clone();
+        if root.get_parent().is_none() {
+            self.base_mut().add_child(&root);
+        }
+    }
+
+    /// Send queued model events out as signals
+    fn flush_model_events(&mut self) {
+        let completion = self.completion();
+        for event in self.model.drain_events() {
+            if let Some(turn) = &mut self.turn {
+                turn.record(&event);
+            }
+            self.objectives.record(&event);
+            match event {
+                ModelEvent::MatchFound { count } => {
+                    let (score, combo) = (self.model.score(), self.model.combo());
+                    godot_print!("Found {} matches, score: {} (combo: {}x)", count, score, combo);
+                    self.emit_event("match_found", &[count.to_variant()]);
+                }
+                ModelEvent::ScoreChanged(score) => {
+                    self.score = score;
+                    self.emit_event("score_changed", &[score.to_variant()]);
+                    if let Some(shown) = self.ticker.set_target(score) {
+                        self.emit_event("display_score_changed", &[shown.to_variant()]);
+                    }
+                }
+                ModelEvent::SymbolCleared { pos, symbol_type, tags } => {
+                    if symbol_type.index() as i32 == self.objective_color {
+                        self.objective_cleared += 1;
+                    }
+                    // Announce each cleared symbol with its theme cues
+                    let cue = self.theme.cue(symbol_type).clone();
+                    self.emit_event(
+                        "symbol_cleared",
+                        &[
+                            pos.to_variant(),
+                            (symbol_type.index() as i32).to_variant(),
+                            cue.clear_sound.to_variant(),
+                            cue.particle_color.to_variant(),
+                            tags_dictionary(&tags).to_variant(),
+                        ],
+                    );
+                    if self.garden_mode && self.garden.plant(pos) {
+                        self.garden_dirty = true;
+                        self.view.set_tile(pos, self.cell_tile(pos));
+                        self.emit_event("garden_planted", &[pos.to_variant()]);
+                    }
+                }
+                ModelEvent::SeekerCreated(pos) => {
+                    self.repaint_cell(pos);
+                    self.emit_event("seeker_created", &[pos.to_variant()]);
+                    self.teach(teaching::TOPIC_SPECIALS);
+                }
+                ModelEvent::SeekerLaunched { from, to } => {
+                    self.view.animate_seeker(from, to, self.animation_time(self.clear_duration));
+                    self.emit_event("seeker_launched", &[from.to_variant(), to.to_variant()]);
+                }
+                ModelEvent::StripedCreated { pos, kind } => {
+                    self.repaint_cell(pos);
+                    self.emit_event("striped_created", &[pos.to_variant(), GString::from(kind.name()).to_variant()]);
+                    self.teach(teaching::TOPIC_SPECIALS);
+                }
+                ModelEvent::StripeFired { pos, kind } => {
+                    self.emit_event("stripe_fired", &[pos.to_variant(), GString::from(kind.name()).to_variant()]);
+                }
+                ModelEvent::ColorBombCreated(pos) => {
+                    self.repaint_cell(pos);
+                    self.emit_event("color_bomb_created", &[pos.to_variant()]);
+                    self.teach(teaching::TOPIC_SPECIALS);
+                }
+                ModelEvent::ColorBombFired { pos, symbol_type } => {
+                    self.emit_event("color_bomb_fired", &[pos.to_variant(), (symbol_type.index() as i32).to_variant()]);
+                }
+                ModelEvent::ComboTriggered { at, effect, cells } => {
+                    self.view.flash_cells(&cells, effect.flash_color(), self.animation_time(self.clear_duration));
+                    self.emit_event("special_combo", &[at.to_variant(), GString::from(effect.name()).to_variant()]);
+                }
+                ModelEvent::PityNudge(pos) => {
+                    self.emit_event("pity_nudged", &[pos.to_variant()]);
+                }
+                ModelEvent::StoneHit { pos, hp } => {
+                    self.view.set_overlay(pos, Some(CellOverlay::Stone(hp)));
+                    self.emit_event("stone_hit", &[pos.to_variant(), (hp as i32).to_variant()]);
+                }
+                ModelEvent::StoneBroken(pos) => {
+                    self.view.set_overlay(pos, None);
+                    self.emit_event("stone_broken", &[pos.to_variant()]);
+                }
+                ModelEvent::ChocolateEaten(pos) => {
+                    self.view.set_overlay(pos, None);
+                    self.emit_event("chocolate_eaten", &[pos.to_variant()]);
+                }
+                ModelEvent::ChocolateSpread(pos) => {
+                    // The cell's symbol is gone; drop its node under the chocolate
+                    self.view.repair(self.model.grid(), &[pos]);
+                    self.view.set_overlay(pos, Some(CellOverlay::Chocolate));
+                    self.emit_event("chocolate_spread", &[pos.to_variant()]);
+                }
+                ModelEvent::IceCracked { pos, layers } => {
+                    self.view.set_overlay(pos, Some(CellOverlay::Ice(layers)));
+                    self.emit_event("ice_cracked", &[pos.to_variant(), (layers as i32).to_variant()]);
+                }
+                ModelEvent::IceBroken(pos) => {
+                    self.view.set_overlay(pos, None);
+                    self.emit_event("ice_broken", &[pos.to_variant()]);
+                }
+                ModelEvent::JellyCleared { pos, remaining } => {
+                    self.view.set_tile(pos, self.cell_tile(pos));
+                    if remaining == 0 {
+                        self.level_won = Some("All jelly cleared");
+                    }
+                    self.emit_event("jelly_cleared", &[remaining.to_variant()]);
+                }
+                ModelEvent::IngredientCollected { pos, remaining } => {
+                    self.view.animate_exits(&[pos], self.animation_time(self.clear_duration));
+                    if remaining == 0 {
+                        self.level_won = Some("All ingredients collected");
+                    }
+                    self.emit_event("ingredient_collected", &[pos.to_variant(), remaining.to_variant()]);
+                }
+                ModelEvent::SymbolExpired(pos) => {
+                    self.emit_event("symbol_expired", &[pos.to_variant()]);
+                    self.teach(teaching::TOPIC_BLOCKERS);
+                }
+                ModelEvent::MultiplierConsumed(pos) => {
+                    self.view.set_tile(pos, self.cell_tile(pos));
+                    self.emit_event("multiplier_consumed", &[pos.to_variant()]);
+                }
+                ModelEvent::ChainResolved(chain) => {
+                    self.rotation_stats.record_chain(&chain, self.clock.now());
+                    self.pacing.on_activity();
+                    let steps = chain_steps(&chain);
+                    self.emit_event(
+                        "chain_resolved",
+                        &[GString::from(chain.cause.name()).to_variant(), steps.to_variant()],
+                    );
+                }
+            }
+        }
+        for percent in self.objectives.take_crossed() {
+            self.emit_event("completion_threshold", &[percent.to_variant()]);
+        }
+        self.announce_completion(completion);
+    }
+
+    /// Get the weighted objective completion and the objective color's cleared count
+    fn completion(&self) -> (f64, i32) {
+        (self.objectives.completion_percent(), self.objective_cleared)
+    }
+
+    /// Emit completion_changed if either half of `completion()` moved since `before`
+    fn announce_completion(&mut self, before: (f64, i32)) {
+        let (percent, cleared) = self.completion();
+        if (percent, cleared) != before {
+            self.emit_event("completion_changed", &[percent.to_variant(), cleared.to_variant()]);
+        }
+    }
+
+    /// Convert a viewport position (e.g. from an input event) to a grid cell
+    /// Returns (-1, -1) outside the board or over a hole; accounts for the node's transform and any Camera2D
+    #[func]
+    fn screen_to_grid(&self, screen_pos: Vector2) -> Vector2i {
+        self.cell_at_screen(screen_pos).unwrap_or(Vector2i::new(-1, -1))
+    }
+
+    /// Convert a grid cell to the viewport position of its center
+    #[func]
+    fn grid_to_screen(&self, grid_pos: Vector2i) -> Vector2 {
+        self.base().get_global_transform_with_canvas() * self.view.cell_center(grid_pos)
+    }
+
+    /// Find the cell under a viewport position
+    fn cell_at_screen(&self, screen_pos: Vector2) -> Option<Vector2i> {
+        let local_pos = self.base().get_global_transform_with_canvas().affine_inverse() * screen_pos;
+        let grid = self.model.grid();
+        self.view.cell_at(local_pos).filter(|pos| grid.is_enabled(pos.x as usize, pos.y as usize))
+    }
+
+    /// Handle a click on a board cell
+    fn handle_click(&mut self, grid_pos: Vector2i) {
+        godot_print!("Clicked on grid position: {:?}", grid_pos);
+
+        match self.state.current() {
+            GameState::Ready if self.model.rules().taps() => {
+                self.tap_symbol(grid_pos, true);
+            }
+            GameState::Ready if self.current_input_mode() == InputMode::Link => {
+                self.start_link(grid_pos);
+            }
+            GameState::Ready => {
+                // Select this symbol
+                self.select_symbol(grid_pos);
+            }
+            GameState::Selected => {
+                if let Some(selected) = self.selected_pos {
+                    if BoardModel::is_adjacent(selected, grid_pos) {
+                        // Try to swap; rejections are reported by try_swap
+                        let _ = self.try_swap(selected, grid_pos);
+                    } else {
+                        // Select new symbol instead
+                        self.deselect_symbol();
+                        self.select_symbol(grid_pos);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Select a symbol
+    fn select_symbol(&mut self, pos: Vector2i) {
+        if !self.input_mask.allows(input_mask::ACTION_SELECT, pos)
+            || !self.tutorial.allows_selection(pos)
+            || self.is_locked(pos)
+        {
+            godot_print!("Selecting {:?} is blocked", pos);
+            self.block_input(pos);
+            return;
+        }
+        if !self.set_state(GameState::Selected) {
+            return;
+        }
+        self.selected_pos = Some(pos);
+
+        // Visual feedback - scale up
+        self.view.set_highlighted(pos, true);
+
+        godot_print!("Selected symbol at {:?}", pos);
+    }
+
+    /// Deselect current symbol
+    fn deselect_symbol(&mut self) {
+        if let Some(pos) = self.selected_pos.take() {
+            self.view.set_highlighted(pos, false);
+        }
+        self.set_state(GameState::Ready);
+    }
+
+    /// Check if the player may swap two symbols right now
+    fn check_swap(&self, pos1: Vector2i, pos2: Vector2i) -> Result<(), SwapRejection> {
+        if !self.state.current().accepts_input() || self.clock.is_paused() {
+            return Err(SwapRejection::BoardBusy);
+        }
+        self.check_board_locks(pos1, pos2)?;
+        self.model.check_swap(pos1, pos2)
+    }
+
+    /// Check the board's own locks on a swap: locked cells, the input mask and the tutorial step
+    fn check_board_locks(&self, pos1: Vector2i, pos2: Vector2i) -> Result<(), SwapRejection> {
+        if !self.input_mask.allows(input_mask::ACTION_SELECT, pos1) || self.is_locked(pos1) {
+            return Err(SwapRejection::CellLocked(pos1));
+        }
+        if self.is_locked(pos2) {
+            return Err(SwapRejection::CellLocked(pos2));
+        }
+        if !self.input_mask.allows(input_mask::ACTION_SWAP, pos2) || !self.tutorial.allows_swap(pos1, pos2) {
+            return Err(SwapRejection::CellLocked(pos2));
+        }
+        Ok(())
+    }
+
+    /// Try to swap the selected symbol, emitting swap_rejected if the swap is not allowed
+    fn try_swap(&mut self, pos1: Vector2i, pos2: Vector2i) -> Result<(), SwapRejection> {
+        if let Err(reason) = self.check_swap(pos1, pos2) {
+            return Err(self.reject_swap(pos1, pos2, reason));
+        }
+
+        godot_print!("Swapping {:?} with {:?}", pos1, pos2);
+        self.input_log.record(format!("swap ({}, {}) -> ({}, {})", pos1.x, pos1.y, pos2.x, pos2.y));
+        if !self.set_state(GameState::Swapping) {
+            return Err(self.reject_swap(pos1, pos2, SwapRejection::BoardBusy));
+        }
+
+        // Only a swap that is going ahead gets graded
+        if self.trainer_mode {
+            self.grade_move(pos1, pos2);
+        }
+
+        if self.sandbox_mode {
+            self.sandbox_history.record(self.model.grid());
+        }
+
+        // Making the step's swap completes it
+        let completes_step = self.tutorial.current().is_some_and(|step| step.allowed_swap.is_some());
+
+        self.record_rewind_point();
+        self.begin_turn("swap");
+        self.queue_command(BoardCommand::Swap(pos1, pos2));
+        self.count_move();
+
+        if completes_step {
+            self.advance_tutorial();
+        }
+        Ok(())
+    }
+
+    /// Undo a rejected swap's selection and emit swap_rejected, handing the reason back
+    fn reject_swap(&mut self, pos1: Vector2i, pos2: Vector2i, reason: SwapRejection) -> SwapRejection {
+        godot_print!("Swap {:?} <-> {:?} rejected: {}", pos1, pos2, reason);
+        if let SwapRejection::CellLocked(pos) = reason {
+            self.block_input(pos);
+        }
+        if self.state.current() == GameState::Selected {
+            self.deselect_symbol();
+        }
+        self.emit_event(
+            "swap_rejected",
+            &[pos1.to_variant(), pos2.to_variant(), GString::from(reason.code()).to_variant()],
+        );
+        reason
+    }
+
+    /// Signal emitted in trainer mode for each move: its rank among all moves (1 is best),
+    /// the best move's score and this move's score, both before refills
+    #[signal]
+    fn move_graded(rank: i32, best_score: i32, your_score: i32, tick: i64);
+
+    /// Rank a move against every other legal move and emit move_graded
+    fn grade_move(&mut self, pos1: Vector2i, pos2: Vector2i) {
+        let budget = self.trainer_move_budget.max(1) as usize;
+        let moves = solver::evaluate_moves(&self.model, budget);
+        let your_score = solver::evaluate_move(&self.model, pos1, pos2);
+        let best_score = moves.first().map_or(your_score, |m| m.score).max(your_score);
+        let rank = solver::rank(&moves, your_score) as i32;
+
+        self.emit_event(
+            "move_graded",
+            &[rank.to_variant(), best_score.to_variant(), your_score.to_variant()],
+        );
+    }
+
+    /// Signal emitted when a swap is rejected, with the reason code
+    /// (not_adjacent, no_match, cell_locked, board_busy or swaps_disabled); its text is `get_text("swap.<code>", {})`
+    #[signal]
+    fn swap_rejected(from: Vector2i, to: Vector2i, reason: GString, tick: i64);
+
+    /// Swap two symbols as if the player had selected `from` and then `to`
+    /// Returns an empty string on success, otherwise the swap_rejected reason code
+    #[func]
+    fn request_swap(&mut self, from: Vector2i, to: Vector2i) -> GString {
+        let result = self.guarded("request_swap", |board| {
+            if board.check_swap(from, to).is_ok() && board.selected_pos != Some(from) {
+                board.deselect_symbol();
+                board.select_symbol(from);
+            }
+            board.try_swap(from, to)
+        });
+        match result {
+            Some(Ok(())) => GString::new(),
+            Some(Err(reason)) => reason.code().into(),
+            None => SwapRejection::BoardBusy.code().into(),
+        }
+    }
+
+    /// Get the input mode of the loaded level, or the board's own
+    fn current_input_mode(&self) -> InputMode {
+        match &self.level {
+            Some(level) => level.bind().input_mode(),
+            None => InputMode::from_index(self.input_mode).unwrap_or_default(),
+        }
+    }
+
+    /// Start dragging a link path at a cell
+    fn start_link(&mut self, pos: Vector2i) {
+        let Some(symbol_type) = self.model.symbol_type_at(pos) else {
+            return;
+        };
+        self.select_symbol(pos);
+        if self.state.current() == GameState::Selected {
+            self.link.start(pos, symbol_type);
+        }
+    }
+
+    /// Extend or shorten the dragged link path as the pointer reaches a cell
+    fn extend_link(&mut self, pos: Vector2i) {
+        let Some(symbol_type) = self.model.symbol_type_at(pos) else {
+            return;
+        };
+        if !self.input_mask.allows(input_mask::ACTION_SELECT, pos) || self.is_locked(pos) {
+            return;
+        }
+
+        self.reset_idle();
+        match self.link.extend(pos, symbol_type) {
+            LinkChange::Added => self.view.set_highlighted(pos, true),
+            LinkChange::Removed(removed) => self.view.set_highlighted(removed, false),
+            LinkChange::Unchanged => {}
+        }
+    }
+
+    /// Clear the dragged link path if it is long enough, otherwise drop it
+    fn finish_link(&mut self) {
+        let cells = self.link.take();
+        for pos in &cells {
+            self.view.set_highlighted(*pos, false);
+        }
+        self.selected_pos = None;
+
+        if cells.len() < link::MIN_LINK {
+            godot_print!("Link of {} cells is too short", cells.len());
+            self.set_state(GameState::Ready);
+            return;
+        }
+        if !self.set_state(GameState::Matching) {
+            return;
+        }
+
+        let path: Vec<String> = cells.iter().map(|pos| format!("({}, {})", pos.x, pos.y)).collect();
+        self.input_log.record(format!("link {}", path.join(" ")));
+        self.released_link = cells;
+        self.record_rewind_point();
+        self.begin_turn("link");
+        self.queue_command(BoardCommand::ClearLink);
+        self.count_move();
+    }
+
+    /// Turn the symbol at a position a face, as a tap does in tap rulesets; counterclockwise turns back a face
+    /// Returns false if the ruleset has no taps, the direction is locked or the board is not waiting for a move
+    #[func]
+    fn turn_symbol(&mut self, pos: Vector2i, clockwise: bool) -> bool {
+        self.guarded("turn_symbol", |board| {
+            board.state.current() == GameState::Ready && board.model.rules().taps() && board.tap_symbol(pos, clockwise)
+        })
+        .unwrap_or(false)
+    }
+
+    /// Turn a tapped symbol a face, clockwise if true
+    fn tap_symbol(&mut self, pos: Vector2i, clockwise: bool) -> bool {
+        let needed = if clockwise {
+            unlocks::UNLOCK_MANUAL_ROTATION
+        } else {
+            unlocks::UNLOCK_MANUAL_ROTATION | unlocks::UNLOCK_ROTATE_COUNTERCLOCKWISE
+        };
+        if !self.is_unlocked(needed) {
+            godot_print!("Turning {:?} {} is locked", pos, if clockwise { "clockwise" } else { "counterclockwise" });
+            return false;
+        }
+        if !self.input_mask.allows(input_mask::ACTION_SELECT, pos)
+            || !self.tutorial.allows_selection(pos)
+            || self.is_locked(pos)
+        {
+            godot_print!("Tapping {:?} is blocked", pos);
+            self.block_input(pos);
+            return false;
+        }
+        let tappable = self.model.symbol_type_at(pos).is_some() && !self.model.is_blocker(pos);
+        if !tappable || !self.set_state(GameState::Rotating) {
+            return false;
+        }
+
+        let direction = if clockwise { "" } else { " back" };
+        self.input_log.record(format!("tap{} ({}, {})", direction, pos.x, pos.y));
+        self.record_rewind_point();
+        self.begin_turn("tap");
+        self.queue_command(BoardCommand::Tap(pos, clockwise));
+        self.count_move();
+        true
+    }
+
+    /// Signal emitted when the move count changes
+    #[signal]
+    fn moves_changed(moves: i32, tick: i64);
+
+    /// Count a move made by the player
+    fn count_move(&mut self) {
+        self.moves_made += 1;
+        self.hold_used = false;
+        self.pacing.on_activity();
+        let moves = self.moves_made;
+        self.emit_event("moves_changed", &[moves.to_variant()]);
+    }
+
+    /// Signal emitted when the hold slot changes, with the held symbol type (-1 when empty)
+    #[signal]
+    fn hold_changed(symbol_type: i32, tick: i64);
+
+    /// Stash the symbol at a cell in the hold slot, or put the held symbol there and hold
+    /// that cell's symbol instead. A stashed symbol's cell is refilled; the slot can be used
+    /// once between moves. The held symbol can go onto any cell with a symbol, not only cells next to an
+    /// empty one: the board refills before the player can move again, so it never has empty cells then
+    /// Returns false if hold is disabled, used up, blocked or the board is busy
+    #[func]
+    fn use_hold(&mut self, pos: Vector2i) -> bool {
+        self.guarded("use_hold", |board| board.exchange_hold(pos)).unwrap_or(false)
+    }
+
+    /// Exchange the symbol at a cell with the hold slot
+    fn exchange_hold(&mut self, pos: Vector2i) -> bool {
+        if !self.hold_enabled || !self.is_unlocked(unlocks::UNLOCK_HOLD) {
+            return false;
+        }
+        if self.hold_used || self.state.current() != GameState::Ready || self.clock.is_paused() {
+            return false;
+        }
+        if !self.input_mask.allows(input_mask::ACTION_HOLD, pos)
+            || !self.tutorial.allows_selection(pos)
+            || self.is_locked(pos)
+        {
+            godot_print!("Holding {:?} is blocked", pos);
+            self.block_input(pos);
+            return false;
+        }
+        let holdable = self.model.symbol_type_at(pos).is_some() && !self.model.is_blocker(pos);
+        if !holdable || !self.set_state(GameState::Matching) {
+            return false;
+        }
+
+        self.hold_used = true;
+        self.reset_idle();
+        self.input_log.record(format!("hold ({}, {})", pos.x, pos.y));
+        self.record_rewind_point();
+        self.begin_turn("hold");
+        self.queue_command(BoardCommand::Hold(pos));
+        true
+    }
+
+    /// Get the hold slot for HUDs: { enabled, symbol_type (-1 when empty), available }
+    /// `available` is whether the slot can be used before the next move
+    #[func]
+    fn get_hold_state(&self) -> Dictionary {
+        let mut result = Dictionary::new();
+        let enabled = self.hold_enabled && self.is_unlocked(unlocks::UNLOCK_HOLD);
+        result.set("enabled", enabled);
+        result.set("symbol_type", self.model.held_type().map_or(-1, |symbol_type| symbol_type.index() as i32));
+        result.set("available", enabled && !self.hold_used);
+        result
+    }
+
+    /// Signal emitted when the whole grid starts turning a quarter turn; only square boards spin
+    #[signal]
+    fn board_spun(clockwise: bool, tick: i64);
+
+    /// Turn the whole grid a quarter turn, moving every symbol to its new cell, then let gravity
+    /// settle it and resolve whatever the spin lined up; unlike a rotation, faces stay as they are.
+    /// Only square boards spin: the board keeps its size and shape, so holes, jelly and multipliers stay put
+    /// Returns false if the board is not square, the direction is locked or the board is not waiting for a move
+    #[func]
+    fn spin_board(&mut self, clockwise: bool) -> bool {
+        self.guarded("spin_board", |board| board.spin_grid(clockwise)).unwrap_or(false)
+    }
+
+    /// Start a quarter turn of the whole grid
+    fn spin_grid(&mut self, clockwise: bool) -> bool {
+        let needed = if clockwise {
+            unlocks::UNLOCK_SPIN
+        } else {
+            unlocks::UNLOCK_SPIN | unlocks::UNLOCK_SPIN_COUNTERCLOCKWISE
+        };
+        if !self.is_unlocked(needed) {
+            godot_print!("Spinning {} is locked", if clockwise { "clockwise" } else { "counterclockwise" });
+            return false;
+        }
+        if self.grid_width != self.grid_height {
+            godot_warn!("Only square boards spin, this one is {}x{}", self.grid_width, self.grid_height);
+            return false;
+        }
+        if self.state.current() != GameState::Ready || self.clock.is_paused() || !self.set_state(GameState::Rotating) {
+            return false;
+        }
+
+        self.reset_idle();
+        self.input_log.record(format!("spin {}", if clockwise { "clockwise" } else { "counterclockwise" }));
+        self.queue_command(BoardCommand::Spin(clockwise));
+        true
+    }
+
+    /// Set how many moves the rewind booster can go back (0 for none)
+    #[func]
+    fn set_rewind_depth(&mut self, depth: i32) {
+        self.rewind_depth = depth.max(0);
+        self.rewind.set_capacity(self.rewind_depth as usize);
+    }
+
+    /// Get how many moves can be rewound right now
+    #[func]
+    fn get_rewind_available(&self) -> i32 {
+        self.rewind.available() as i32
+    }
+
+    /// Signal emitted when a rewind starts playing back, with the moves it goes back
+    #[signal]
+    fn board_rewound(moves: i32, tick: i64);
+
+    /// Put the board, score, objective progress, move count and hold slot back to how they were `moves` moves ago,
+    /// playing the states in between backwards; rotations and the replay go back with them
+    /// Returns false if the rewind booster is locked, that many moves are not recorded or the board
+    /// is not waiting for a move
+    #[func]
+    fn rewind(&mut self, moves: i32) -> bool {
+        self.guarded("rewind", |board| board.rewind_moves(moves)).unwrap_or(false)
+    }
+
+    /// Take back moves by playing the states in between backwards
+    fn rewind_moves(&mut self, moves: i32) -> bool {
+        if !self.is_unlocked(unlocks::UNLOCK_REWIND) {
+            godot_print!("Rewind is locked");
+            return false;
+        }
+        let available = self.rewind.available();
+        if moves < 1 || moves as usize > available {
+            godot_warn!("Cannot rewind {} moves, {} recorded", moves, available);
+            return false;
+        }
+        if self.state.current() != GameState::Ready || self.clock.is_paused() || !self.set_state(GameState::Rotating) {
+            return false;
+        }
+
+        self.reset_idle();
+        self.input_log.record(format!("rewind {}", moves));
+        self.queue_command(BoardCommand::Rewind(moves as usize));
+        true
+    }
+
+    /// Get the counts a turn summary works out its changes from
+    fn turn_counters(&self) -> TurnCounters {
+        TurnCounters {
+            score: self.model.score(),
+            moves: self.moves_made,
+            objective_cleared: self.objective_cleared,
+        }
+    }
+
+    /// Open a turn for a player's move of kind `cause`, before the move counts
+    fn begin_turn(&mut self, cause: &'static str) {
+        self.turn = Some(TurnSummary::begin(cause, self.turn_counters()));
+    }
+
+    /// Signal emitted once a player's move and its cascade have fully resolved:
+    /// { cause, moves_used, matches, cascades, specials_created, specials_activated, chocolate_eaten,
+    /// ingredients_collected, objective_cleared, score_gained }
+    #[signal]
+    fn turn_resolved(summary: Dictionary, tick: i64);
+
+    /// End the open turn, if any: let chocolate spread, then send the turn's summary
+    fn finish_turn(&mut self) {
+        let Some(turn) = self.turn.take() else {
+            return;
+        };
+        // The move has settled: chocolate left alone grows before the turn is reported
+        if turn.chocolate_eaten == 0 && self.model.spread_chocolate().is_some() {
+            self.flush_model_events();
+        }
+        let deltas = turn.deltas(self.turn_counters());
+        let mut summary = Dictionary::new();
+        summary.set("cause", turn.cause);
+        summary.set("moves_used", deltas.moves);
+        summary.set("matches", turn.matches);
+        summary.set("cascades", turn.cascades());
+        summary.set("specials_created", turn.specials_created);
+        summary.set("specials_activated", turn.specials_activated);
+        summary.set("chocolate_eaten", turn.chocolate_eaten);
+        summary.set("ingredients_collected", turn.ingredients_collected);
+        summary.set("objective_cleared", deltas.objective_cleared);
+        summary.set("score_gained", deltas.score);
+        self.emit_event("turn_resolved", &[summary.to_variant()]);
+    }
+
+    /// Remember the board before a player's move, for rewinding
+    fn record_rewind_point(&mut self) {
+        self.rewind.record(Snapshot {
+            model: self.model.clone(),
+            moves_made: self.moves_made,
+            objective_cleared: self.objective_cleared,
+            objectives: self.objectives.clone(),
+            replay_moves: self.replay.move_count(),
+        });
+    }
+
+    /// Trigger rotation of all symbols
+    fn trigger_rotation(&mut self) {
+        if self.state.current() != GameState::Ready {
+            return;
+        }
+
+        godot_print!("Triggering rotation!");
+        if !self.set_state(GameState::Rotating) {
+            return;
+        }
+
+        self.queue_command(BoardCommand::Rotate);
+    }
+
+    /// Queue a command and run the queue
+    fn queue_command(&mut self, command: BoardCommand) {
+        self.commands.push(command);
+        self.run_commands();
+    }
+
+    /// Run queued commands until one has to wait for its animation
+    fn run_commands(&mut self) {
+        while let Some(command) = self.commands.next() {
+            if self.start_command(command) {
+                self.commands.wait_for(command);
+            }
+        }
+    }
+
+    /// Called by the view when the waiting command's animation completes
+    #[func]
+    fn on_command_complete(&mut self, generation: i64) {
+        self.guarded("on_command_complete", |board| board.complete_command(generation));
+    }
+
+    /// Finish the waiting command and run the rest of the queue
+    fn complete_command(&mut self, generation: i64) {
+        if generation != self.generation {
+            godot_print!("Ignoring stale animation callback from generation {}", generation);
+            return;
+        }
+        let Some(command) = self.commands.complete() else {
+            self.report_error(BoardError::NoActiveCommand);
+            return;
+        };
+        self.finish_command(command);
+        self.run_commands();
+    }
+
+    /// Apply a command to the model and start its animation
+    /// Returns true if the command waits for `on_command_complete`
+    fn start_command(&mut self, command: BoardCommand) -> bool {
+        let on_complete = self
+            .base()
+            .callable("on_command_complete")
+            .bind(&[self.generation.to_variant()]);
+
+        match command {
+            BoardCommand::Swap(pos1, pos2) => {
+                if let Err(err) = self.model.swap(pos1, pos2) {
+                    self.report_error(err);
+                    self.selected_pos = None;
+                    self.set_state(GameState::Ready);
+                    return false;
+                }
+                self.view.swap(pos1, pos2);
+                // A mirror modifier may only repeat the swap where the player could have made it
+                let (mirror1, mirror2) = (self.model.mirror(pos1), self.model.mirror(pos2));
+                let locked: Vec<Vector2i> = match self.check_board_locks(mirror1, mirror2) {
+                    Err(SwapRejection::CellLocked(pos)) => vec![pos],
+                    _ => Vec::new(),
+                };
+                let events = self.run_modifiers(|stack, ctx| {
+                    ctx.locked = locked;
+                    stack.on_turn_start(ctx, command)
+                });
+                let mirrored = events.iter().any(|event| matches!(event, ModifierEvent::SwapMirrored(..)));
+                self.replay.record(ReplayMove::Swap {
+                    from: pos1,
+                    to: pos2,
+                    mirrored,
+                });
+                self.commands.push(BoardCommand::Clear);
+                false
+            }
+            BoardCommand::Tap(pos, clockwise) => {
+                if let Err(err) = self.model.rotate_cell(pos, clockwise) {
+                    self.report_error(err);
+                    self.set_state(GameState::Ready);
+                    return false;
+                }
+                self.replay.record(ReplayMove::Tap { pos, clockwise });
+                self.run_modifiers(|stack, ctx| stack.on_turn_start(ctx, command));
+                if let Some(symbol_type) = self.model.symbol_type_at(pos) {
+                    self.view.set_cell_color(pos, symbol_type.color());
+                }
+                self.set_state(GameState::Ready);
+                self.commands.push(BoardCommand::Clear);
+                false
+            }
+            BoardCommand::Hold(pos) => {
+                // Settle through Fall either way; a stashed symbol's cell is refilled
+                let stashing = self.model.held_type().is_none();
+                self.commands.push(BoardCommand::Fall);
+                self.commands.push(BoardCommand::Refill);
+                self.commands.push(BoardCommand::Clear);
+                if let Err(err) = self.model.exchange_held(pos) {
+                    self.report_error(err);
+                    return false;
+                }
+                self.replay.record(ReplayMove::Hold(pos));
+                self.run_modifiers(|stack, ctx| stack.on_turn_start(ctx, command));
+                let held = self.model.held_type().map_or(-1, |symbol_type| symbol_type.index() as i32);
+                self.emit_event("hold_changed", &[held.to_variant()]);
+
+                if stashing {
+                    self.view.animate_clear(&[pos], self.animation_time(self.clear_duration), &on_complete);
+                    return true;
+                }
+                if let Some(symbol_type) = self.model.symbol_type_at(pos) {
+                    self.view.set_cell_color(pos, symbol_type.color());
+                }
+                false
+            }
+            BoardCommand::Clear => {
+                let capped = self.model.cascade_depth() >= self.max_cascade_depth;
+                if capped || !(self.model.has_matches() || self.model.has_exiting_ingredients()) {
+                    if capped {
+                        // Degenerate rules can match forever; stop with any leftover matches in place
+                        let depth = self.model.cascade_depth();
+                        godot_warn!("Cascade stopped after {} resolutions", depth);
+                        self.emit_event("cascade_cap_reached", &[depth.to_variant()]);
+                    }
+                    self.model.end_cascade();
+                    self.flush_model_events();
+                    self.finish_turn();
+                    if self.garden_dirty {
+                        self.save_garden();
+                    }
+                    self.reconcile_view();
+                    // The board has changed under any hint; show where the symbols that stand out rest now
+                    self.view.clear_emphasis(EmphasisSource::Hint);
+                    self.emphasize_symbols();
+                    self.set_state(GameState::Ready);
+                    self.selected_pos = None;
+                    if let Some(reason) = self.level_won.take() {
+                        godot_print!("{}, level won", reason);
+                        self.finish_level(1);
+                        return false;
+                    }
+                    if !self.pending_edits.is_empty() {
+                        self.commands.push(BoardCommand::Edit);
+                    }
+                    return false;
+                }
+                if !self.set_state(GameState::Matching) {
+                    return false;
+                }
+                if !self.model.has_matches() {
+                    // Only ingredients waiting in the bottom row are left; let them out and settle again
+                    self.commands.push(BoardCommand::Fall);
+                    self.commands.push(BoardCommand::Refill);
+                    self.commands.push(BoardCommand::Clear);
+                    return false;
+                }
+
+                let positions = self.model.resolve_matches();
+                self.run_modifiers(|stack, ctx| stack.on_match(ctx, &positions));
+                self.flush_model_events();
+
+                // Clear matched symbols, then let the rest of the cascade settle
+                self.last_match_positions = positions.clone();
+                self.view.animate_clear(&positions, self.animation_time(self.clear_duration), &on_complete);
+                self.commands.push(BoardCommand::Fall);
+                self.commands.push(BoardCommand::Refill);
+                self.commands.push(BoardCommand::Clear);
+                true
+            }
+            BoardCommand::Fall => {
+                self.set_state(GameState::Falling);
+                let falls = self.model.apply_gravity();
+                self.flush_model_events();
+                if falls.is_empty() {
+                    return false;
+                }
+                self.announce_fast_falls(&falls);
+                let trail = self.fast_fall_trail && self.performance.flourishes();
+                let stretch_over = if trail { self.fast_fall_cells } else { 0 };
+                let duration = self.animation_time(self.fall_duration_per_cell);
+                self.view.animate_falls(&falls, duration, stretch_over, &on_complete);
+                true
+            }
+            BoardCommand::Refill => {
+                let spawns = self.model.refill();
+                if spawns.is_empty() {
+                    return false;
+                }
+                self.view.animate_spawns(&spawns, self.animation_time(self.spawn_duration), &on_complete);
+                true
+            }
+            BoardCommand::ClearLink => {
+                let cells = std::mem::take(&mut self.released_link);
+                let positions = self.model.clear_link(&cells);
+                self.replay.record(ReplayMove::Link(cells));
+                self.run_modifiers(|stack, ctx| {
+                    stack.on_turn_start(ctx, command);
+                    stack.on_match(ctx, &positions);
+                });
+                self.flush_model_events();
+
+                self.last_match_positions = positions.clone();
+                self.view.animate_clear(&positions, self.animation_time(self.clear_duration), &on_complete);
+                self.commands.push(BoardCommand::Fall);
+                self.commands.push(BoardCommand::Refill);
+                self.commands.push(BoardCommand::Clear);
+                true
+            }
+            BoardCommand::Rotate => {
+                self.view.animate_rotation(self.animation_time(self.rotation_duration), &on_complete);
+                self.emit_event("rotation_triggered", &[]);
+                true
+            }
+            BoardCommand::Spin(clockwise) => {
+                let duration = self.animation_time(self.rotation_duration);
+                self.view.animate_spin(self.model.grid(), clockwise, duration, &on_complete);
+                self.model.spin(clockwise);
+                self.replay.record(ReplayMove::Spin { clockwise });
+                self.emit_event("board_spun", &[clockwise.to_variant()]);
+                true
+            }
+            BoardCommand::Sweep { row, step } => {
+                let points = self.sweep_points * (step + 1);
+                let positions = self.model.sweep_row(row as usize, points);
+                self.flush_model_events();
+                let total = points * positions.len() as i32;
+                self.emit_event("sweep_row_cleared", &[row.to_variant(), step.to_variant(), total.to_variant()]);
+                self.view.animate_clear(&positions, self.animation_time(self.sweep_row_duration), &on_complete);
+                true
+            }
+            BoardCommand::Edit => {
+                let Some(edits) = self.pending_edits.pop_front() else {
+                    return false;
+                };
+                if !self.set_state(GameState::Matching) {
+                    self.pending_edits.push_front(edits);
+                    return false;
+                }
+                // Settle afterwards either way, which also picks up the next batch
+                self.commands.push(BoardCommand::Fall);
+                self.commands.push(BoardCommand::Refill);
+                self.commands.push(BoardCommand::Clear);
+                let cleared = match self.model.apply_edits(&edits) {
+                    Ok(cleared) => cleared,
+                    Err(err) => {
+                        self.report_error(err);
+                        return false;
+                    }
+                };
+                self.flush_model_events();
+                self.replay.record(ReplayMove::Edit(edits.clone()));
+                for edit in &edits {
+                    match edit {
+                        BoardEdit::Clear(_) => {}
+                        BoardEdit::Obstacle { pos, overlay } => self.view.set_overlay(*pos, *overlay),
+                        _ => edit.cells().iter().for_each(|pos| self.repaint_cell(*pos)),
+                    }
+                }
+                self.emit_event("board_edited", &[(edits.len() as i32).to_variant()]);
+                if cleared.is_empty() {
+                    return false;
+                }
+                self.view.animate_clear(&cleared, self.animation_time(self.clear_duration), &on_complete);
+                true
+            }
+            BoardCommand::Rewind(moves) => {
+                let Some((snapshot, mut frames)) = self.rewind.rewind(moves) else {
+                    self.set_state(GameState::Ready);
+                    return false;
+                };
+                frames.push(snapshot.model.grid().clone());
+                let completion = self.completion();
+                self.model.restore(snapshot.model);
+                self.replay.truncate(snapshot.replay_moves);
+                self.moves_made = snapshot.moves_made;
+                self.objective_cleared = snapshot.objective_cleared;
+                self.objectives = snapshot.objectives;
+                self.hold_used = false;
+                self.flush_model_events();
+                self.announce_completion(completion);
+                let held = self.model.held_type().map_or(-1, |symbol_type| symbol_type.index() as i32);
+                let moves_made = self.moves_made;
+                self.emit_event("hold_changed", &[held.to_variant()]);
+                self.emit_event("moves_changed", &[moves_made.to_variant()]);
+                self.emit_event("board_rewound", &[(moves as i32).to_variant()]);
+
+                let frame_duration = self.animation_time(self.rewind_frame_duration);
+                self.view.animate_frames(&frames, frame_duration, &on_complete);
+                true
+            }
+        }
+    }
+
+    /// Scale an animation duration for the performance preset, slowing it down in attract mode
+    fn animation_time(&self, seconds: f64) -> f64 {
+        if self.soak.is_active() && self.soak.is_headless() {
+            return 0.0;
+        }
+        let seconds = seconds * self.performance.animation_scale();
+        if self.attract.is_active() {
+            seconds / self.attract_speed.max(0.1)
+        } else {
+            seconds
+        }
+    }
+
+    /// Apply the effects of a command that happen after its animation
+    fn finish_command(&mut self, command: BoardCommand) {
+        godot_print!("{:?} complete", command);
+
+        if command == BoardCommand::Rotate {
+            // Rotate the logical grid (or fire the effect a modifier picked), then update visual colors
+            let mut effect = RouletteEffect::RotateFaces;
+            self.run_modifiers(|stack, ctx| effect = stack.pick_rotation(ctx));
+            match effect {
+                RouletteEffect::RotateFaces => self.model.rotate_all(),
+                RouletteEffect::ShuffleRow(row) => self.model.shuffle_row(row),
+                RouletteEffect::FreezeColumn(column) => {
+                    godot_print!("Column {} frozen until the next rotation", column);
+                }
+                RouletteEffect::SpawnSpecial(pos, kind) => {
+                    if let Err(err) = self.model.set_kind(pos, kind) {
+                        self.report_error(err);
+                    }
+                }
+            }
+            self.run_modifiers(|stack, ctx| stack.on_rotation(ctx, effect));
+            self.replay.record(ReplayMove::Rotate(effect));
+            self.rotation_stats.record_rotation(self.clock.now());
+            self.view.finish_rotation(self.model.grid());
+            self.show_symbol_ages();
+            if let RouletteEffect::SpawnSpecial(..) = effect {
+                self.emphasize_symbols();
+            }
+
+            // Check for new matches after rotation
+            self.set_state(GameState::Ready);
+            self.commands.push(BoardCommand::Clear);
+        } else if let BoardCommand::Sweep { row: 0, .. } = command {
+            // The top row is the last, so the board is clear
+            self.start_results(self.sweep_objectives);
+        } else if let BoardCommand::Rewind(_) = command {
+            // The restored model brings back jelly and used-up multipliers, which the tiles show
+            self.view.rebuild(self.model.grid());
+            self.show_tiles();
+            self.show_fog();
+            self.show_symbol_ages();
+            self.emphasize_symbols();
+            self.set_state(GameState::Ready);
+        } else if let BoardCommand::Spin(_) = command {
+            // Holes stay put, so move what the spin carried into them to where the model put it
+            let moved = self.view.find_mismatches(self.model.grid());
+            self.view.repair(self.model.grid(), &moved);
+            for y in 0..self.grid_height {
+                for x in 0..self.grid_width {
+                    let overlay = self.model.grid().overlay(x as usize, y as usize);
+                    self.view.set_overlay(Vector2i::new(x, y), overlay);
+                }
+            }
+
+            // Settle the grid in its new orientation, then resolve what the spin lined up
+            self.set_state(GameState::Ready);
+            self.set_state(GameState::Matching);
+            self.commands.push(BoardCommand::Fall);
+            self.commands.push(BoardCommand::Refill);
+            self.commands.push(BoardCommand::Clear);
+        }
+    }
+
+    /// Input mask action: selecting a symbol
+    #[constant]
+    const ACTION_SELECT: i32 = input_mask::ACTION_SELECT;
+
+    /// Input mask action: swapping the selected symbol
+    #[constant]
+    const ACTION_SWAP: i32 = input_mask::ACTION_SWAP;
+
+    /// Input mask action: using the hold slot
+    #[constant]
+    const ACTION_HOLD: i32 = input_mask::ACTION_HOLD;
+
+    /// Input mask action: everything
+    #[constant]
+    const ACTION_ALL: i32 = input_mask::ACTION_ALL;
+
+    /// Unlock flag: spinning the board clockwise
+    #[constant]
+    const UNLOCK_SPIN: i32 = unlocks::UNLOCK_SPIN;
+
+    /// Unlock flag: spinning the board counterclockwise too
+    #[constant]
+    const UNLOCK_SPIN_COUNTERCLOCKWISE: i32 = unlocks::UNLOCK_SPIN_COUNTERCLOCKWISE;
+
+    /// Unlock flag: the hold slot
+    #[constant]
+    const UNLOCK_HOLD: i32 = unlocks::UNLOCK_HOLD;
+
+    /// Unlock flag: the rewind booster
+    #[constant]
+    const UNLOCK_REWIND: i32 = unlocks::UNLOCK_REWIND;
+
+    /// Unlock flag: turning a single symbol by hand in tap rulesets
+    #[constant]
+    const UNLOCK_MANUAL_ROTATION: i32 = unlocks::UNLOCK_MANUAL_ROTATION;
+
+    /// Unlock flag: turning a single symbol counterclockwise too
+    #[constant]
+    const UNLOCK_ROTATE_COUNTERCLOCKWISE: i32 = unlocks::UNLOCK_ROTATE_COUNTERCLOCKWISE;
+
+    /// Unlock flag: everything
+    #[constant]
+    const UNLOCK_ALL: i32 = unlocks::UNLOCK_ALL;
+
+    /// Signal emitted when the player's unlocked mechanics change, with the UNLOCK_* flags now set
+    #[signal]
+    fn unlocks_changed(unlocks: i32, tick: i64);
+
+    /// Unlock or lock mechanics (UNLOCK_* flags), saving them with the current profile
+    #[func]
+    fn set_unlocked(&mut self, flags: i32, unlocked: bool) {
+        self.guarded("set_unlocked", |board| board.change_unlocks(flags, unlocked));
+    }
+
+    /// Set or clear unlock flags and save the profile if they changed
+    fn change_unlocks(&mut self, flags: i32, unlocked: bool) {
+        let unlocks = if unlocked { self.unlocks | flags } else { self.unlocks & !flags };
+        if unlocks == self.unlocks {
+            return;
+        }
+        self.unlocks = unlocks;
+        self.save_profile();
+        self.emit_event("unlocks_changed", &[unlocks.to_variant()]);
+    }
+
+    /// Check if every mechanic in `flags` (UNLOCK_* flags) is unlocked
+    #[func]
+    fn is_unlocked(&self, flags: i32) -> bool {
+        unlocks::allows(self.unlocks, flags)
+    }
+
+    /// Get the unlocked mechanics as UNLOCK_* flags
+    #[func]
+    fn get_unlocks(&self) -> i32 {
+        self.unlocks
+    }
+
+    /// Signal emitted when player input is rejected by the input mask or tutorial
+    #[signal]
+    fn blocked_input(pos: Vector2i, tick: i64);
+
+    /// Only accept the given cells (empty for all) and ACTION_* flags from the player
+    #[func]
+    fn set_input_mask(&mut self, allowed_cells: VariantArray, allowed_actions: i32) {
+        let cells = allowed_cells
+            .iter_shared()
+            .filter_map(|cell| match cell.try_to::<Vector2i>() {
+                Ok(pos) => Some(pos),
+                Err(_) => {
+                    godot_warn!("Ignoring non-Vector2i cell in input mask: {}", cell);
+                    None
+                }
+            })
+            .collect();
+        self.input_mask = InputMask::new(cells, allowed_actions);
+    }
+
+    /// Accept every cell and action again
+    #[func]
+    fn clear_input_mask(&mut self) {
+        self.input_mask = InputMask::default();
+    }
+
+    /// Lock or unlock cells: locked cells are dimmed and ignore the player, but keep their symbols
+    /// Locks stay until unlocked, across moves and resets
+    #[func]
+    fn lock_cells(&mut self, cells: VariantArray, locked: bool) {
+        self.guarded("lock_cells", |board| board.set_cells_locked(cells, locked));
+    }
+
+    /// Add cells to the locked cells, or take them out
+    fn set_cells_locked(&mut self, cells: VariantArray, locked: bool) {
+        for cell in cells.iter_shared() {
+            let Ok(pos) = cell.try_to::<Vector2i>() else {
+                godot_warn!("Ignoring non-Vector2i cell to lock: {}", cell);
+                continue;
+            };
+            self.locked_cells.retain(|locked_pos| *locked_pos != pos);
+            if locked {
+                self.locked_cells.push(pos);
+            }
+        }
+        self.view.set_locked(&self.locked_cells);
+    }
+
+    /// Get the cells locked by lock_cells
+    #[func]
+    fn get_locked_cells(&self) -> Array<Vector2i> {
+        self.locked_cells.iter().copied().collect()
+    }
+
+    /// Emphasize cells for the host game's own effects, from -1 (dimmed) to 1 (brightened and outlined),
+    /// replacing the cells emphasized before; an empty array clears them
+    #[func]
+    fn set_cell_emphasis(&mut self, cells: Array<Vector2i>, emphasis: f64) {
+        let cells: Vec<Vector2i> = cells.iter_shared().collect();
+        self.view.set_emphasis(EmphasisSource::Script, &cells, emphasis as f32);
+    }
+
+    /// Get how strongly a cell is drawn out, from -1 (dimmed) to 1, over hints, tutorials, objectives,
+    /// specials, selection, locks and set_cell_emphasis
+    #[func]
+    fn get_cell_emphasis(&self, pos: Vector2i) -> f64 {
+        f64::from(self.view.emphasis().blended(pos))
+    }
+
+    /// Emphasize the symbols of the objective color and the specials, where they rest now
+    fn emphasize_symbols(&mut self) {
+        let grid = self.model.grid();
+        let objective = (self.objective_color >= 0).then(|| Self::symbol_type_from_i32(self.objective_color)).flatten();
+        let objective_cells = objective.map(|symbol_type| grid.cells_of_type(symbol_type)).unwrap_or_default();
+        let special_cells: Vec<Vector2i> = grid
+            .cells
+            .iter()
+            .flatten()
+            .filter(|symbol| symbol.wild || SpecialKind::of(symbol).is_some())
+            .map(|symbol| symbol.grid_pos)
+            .collect();
+        self.view.set_emphasis(EmphasisSource::Objective, &objective_cells, self.objective_emphasis as f32);
+        self.view.set_emphasis(EmphasisSource::Special, &special_cells, self.special_emphasis as f32);
+    }
+
+    /// Signal emitted when a batch of scripted edits lands on the board, with the number of edits
+    #[signal]
+    fn board_edited(edits: i32, tick: i64);
+
+    /// Change the board from a story script: set cells, give symbols specials or clear regions
+    /// (see the `edits` module for the edit dictionaries). The batch is applied whole or not at all
+    /// once the board settles, then matches it lined up resolve as a cascade. Returns false if an
+    /// edit is malformed
+    #[func]
+    fn apply_board_edit(&mut self, edits: Array<Dictionary>) -> bool {
+        self.guarded("apply_board_edit", |board| board.queue_board_edits(edits)).unwrap_or(false)
+    }
+
+    /// Parse scripted edits and queue them for the board
+    fn queue_board_edits(&mut self, edits: Array<Dictionary>) -> bool {
+        let edits = match BoardEdit::from_dictionaries(&edits) {
+            Ok(edits) => edits,
+            Err(message) => {
+                godot_warn!("Ignoring board edit: {}", message);
+                return false;
+            }
+        };
+        self.pending_edits.push_back(edits);
+        let idle = matches!(self.state.current(), GameState::Ready | GameState::Selected);
+        if idle && !self.commands.is_waiting() {
+            self.deselect_symbol();
+            self.queue_command(BoardCommand::Edit);
+        }
+        true
+    }
+
+    /// Make the symbol at a cell wild, so it matches any color. Returns false if the cell is empty
+    /// or off the board
+    #[func]
+    fn make_wild(&mut self, pos: Vector2i) -> bool {
+        self.guarded("make_wild", |board| board.make_symbol_wild(pos)).unwrap_or(false)
+    }
+
+    /// Turn the symbol at a cell wild
+    fn make_symbol_wild(&mut self, pos: Vector2i) -> bool {
+        if let Err(err) = self.model.set_wild(pos) {
+            self.report_error(err);
+            return false;
+        }
+        self.repaint_cell(pos);
+        true
+    }
+
+    /// Tag the symbol at a cell; the tag moves with it through swaps and falls and comes back in
+    /// symbol_cleared. Returns false if the cell is empty or off the board
+    #[func]
+    fn set_cell_tag(&mut self, pos: Vector2i, key: GString, value: GString) -> bool {
+        let value = Some(value.to_string());
+        self.guarded("set_cell_tag", |board| board.tag_cell(pos, &key.to_string(), value)).unwrap_or(false)
+    }
+
+    /// Remove a tag from the symbol at a cell
+    #[func]
+    fn remove_cell_tag(&mut self, pos: Vector2i, key: GString) -> bool {
+        self.guarded("remove_cell_tag", |board| board.tag_cell(pos, &key.to_string(), None)).unwrap_or(false)
+    }
+
+    /// Set or remove (with None) a tag on the symbol at a cell
+    fn tag_cell(&mut self, pos: Vector2i, key: &str, value: Option<String>) -> bool {
+        if let Err(err) = self.model.set_tag(pos, key, value) {
+            self.report_error(err);
+            return false;
+        }
+        true
+    }
+
+    /// Get the tags of the symbol at a cell as { key: value } (empty for an empty cell)
+    #[func]
+    fn get_cell_tags(&self, pos: Vector2i) -> Dictionary {
+        self.model.tags(pos).map(tags_dictionary).unwrap_or_default()
+    }
+
+    /// Emit blocked_input for a rejected cell
+    fn block_input(&mut self, pos: Vector2i) {
+        self.emit_event("blocked_input", &[pos.to_variant()]);
+    }
+
+    /// Signal emitted when the tutorial moves to a new step (index -1 when it ends)
+    #[signal]
+    fn tutorial_step_changed(index: i32, text: GString, spotlight: Array<Vector2i>, tick: i64);
+
+    /// Start a tutorial from step dictionaries (see the `tutorial` module for the format)
+    /// Returns false and leaves any running tutorial alone if a step is invalid
+    #[func]
+    fn start_tutorial(&mut self, steps: Array<Dictionary>) -> bool {
+        match Tutorial::from_dictionaries(&steps) {
+            Ok(tutorial) => {
+                self.tutorial = tutorial;
+                self.on_tutorial_step();
+                true
+            }
+            Err(message) => {
+                godot_warn!("Invalid tutorial: {}", message);
+                false
+            }
+        }
+    }
+
+    /// Move the tutorial to its next step
+    #[func]
+    fn advance_tutorial(&mut self) {
+        if self.tutorial.current().is_none() {
+            return;
+        }
+        self.tutorial.advance();
+        self.on_tutorial_step();
+    }
+
+    /// End the tutorial, lifting all of its constraints
+    #[func]
+    fn stop_tutorial(&mut self) {
+        let was_running = self.tutorial.current().is_some();
+        self.tutorial = Tutorial::default();
+        if was_running {
+            self.on_tutorial_step();
+        }
+    }
+
+    /// Get the index of the current tutorial step (-1 when none is running)
+    #[func]
+    fn get_tutorial_step(&self) -> i32 {
+        match self.tutorial.current() {
+            Some(_) => self.tutorial.step_index() as i32,
+            None => -1,
+        }
+    }
+
+    /// Apply the current tutorial step's spotlight and announce it
+    fn on_tutorial_step(&mut self) {
+        let index = self.get_tutorial_step();
+        let (text, spotlight) = match self.tutorial.current() {
+            Some(step) => (step.text.clone(), step.spotlight.clone()),
+            None => (String::new(), Vec::new()),
+        };
+
+        self.view.set_spotlight(&spotlight);
+
+        let spotlight: Array<Vector2i> = spotlight.into_iter().collect();
+        self.emit_event(
+            "tutorial_step_changed",
+            &[index.to_variant(), GString::from(text.as_str()).to_variant(), spotlight.to_variant()],
+        );
+    }
+
+    /// Enter or leave sandbox mode; either way starts a fresh undo history
+    #[func]
+    fn set_sandbox_mode(&mut self, enabled: bool) {
+        self.guarded("set_sandbox_mode", |board| board.switch_sandbox_mode(enabled));
+    }
+
+    /// Turn sandbox mode on or off, with scoring off while it is on
+    fn switch_sandbox_mode(&mut self, enabled: bool) {
+        self.sandbox_mode = enabled;
+        self.model.set_scoring(!enabled);
+        self.sandbox_history.clear();
+    }
+
+    /// Place a palette piece (see the `sandbox` module) at a cell in sandbox mode: a symbol, a special,
+    /// an obstacle or several at once. Returns false if not in sandbox mode, the board is busy or the spec
+    /// is invalid
+    #[func]
+    fn sandbox_place(&mut self, pos: Vector2i, spec: Dictionary) -> bool {
+        self.guarded("sandbox_place", |board| board.place_in_sandbox(pos, spec)).unwrap_or(false)
+    }
+
+    /// Place a symbol, special or obstacle from a sandbox spec
+    fn place_in_sandbox(&mut self, pos: Vector2i, spec: Dictionary) -> bool {
+        if !self.sandbox_mode {
+            godot_warn!("sandbox_place is only available in sandbox mode");
+            return false;
+        }
+        if !self.state.current().accepts_input() {
+            godot_warn!("Cannot place while board is busy ({:?})", self.state.current());
+            return false;
+        }
+
+        let edits = match sandbox::parse_spec(pos, &spec) {
+            Ok(edits) => edits,
+            Err(message) => {
+                godot_warn!("Invalid sandbox spec: {}", message);
+                return false;
+            }
+        };
+
+        self.deselect_symbol();
+        let before = self.model.grid().clone();
+        if let Err(err) = self.model.apply_edits(&edits) {
+            self.report_error(err);
+            return false;
+        }
+        // Sandbox changes are neither announced nor tallied
+        self.model.drain_events();
+        self.sandbox_history.record(&before);
+        self.view.rebuild(self.model.grid());
+        self.emphasize_symbols();
+        true
+    }
+
+    /// Undo the last sandbox placement or swap
+    /// Returns false if not in sandbox mode, the board is busy or there is nothing to undo
+    #[func]
+    fn sandbox_undo(&mut self) -> bool {
+        self.guarded("sandbox_undo", |board| board.undo_sandbox()).unwrap_or(false)
+    }
+
+    /// Put back the grid from before the last sandbox change
+    fn undo_sandbox(&mut self) -> bool {
+        if !self.sandbox_mode || !self.state.current().accepts_input() {
+            return false;
+        }
+        let Some(grid) = self.sandbox_history.undo() else {
+            return false;
+        };
+
+        self.deselect_symbol();
+        self.model.load_grid(grid);
+        self.view.rebuild(self.model.grid());
+        self.emphasize_symbols();
+        true
+    }
+
+    /// Signal emitted when the board starts playing itself
+    #[signal]
+    fn attract_started(tick: i64);
+
+    /// Signal emitted in attract mode for the cell about to be moved
+    #[signal]
+    fn attract_highlight(pos: Vector2i, tick: i64);
+
+    /// Signal emitted when attract mode ends and the board is reset for play
+    #[signal]
+    fn attract_ended(tick: i64);
+
+    /// Start attract mode now, regardless of idle time
+    /// Returns false if the board is busy
+    #[func]
+    fn start_attract(&mut self) -> bool {
+        self.guarded("start_attract", |board| board.begin_attract()).unwrap_or(false)
+    }
+
+    /// Start the board playing itself
+    fn begin_attract(&mut self) -> bool {
+        if !self.state.current().accepts_input() {
+            return false;
+        }
+
+        self.deselect_symbol();
+        self.reset_idle();
+        match &self.attract_replay {
+            Some(replay) => {
+                self.attract.start_replay(replay.bind().replay(), self.max_cascade_depth);
+                // A demo restarted over a replay keeps the model saved the first time
+                if self.attract_saved_model.is_none() {
+                    self.attract_saved_model = Some(self.model.clone());
+                }
+                self.show_attract_replay();
+            }
+            None => self.attract.start(),
+        }
+        self.emit_event("attract_started", &[]);
+        true
+    }
+
+    /// End attract mode and reset the board so the demo leaves nothing behind
+    #[func]
+    fn stop_attract(&mut self) {
+        self.guarded("stop_attract", |board| board.end_attract());
+    }
+
+    /// Stop the board playing itself and reset it
+    fn end_attract(&mut self) {
+        if !self.leave_attract() {
+            return;
+        }
+        self.reset_board();
+        self.emit_event("attract_ended", &[]);
+    }
+
+    /// Stop attract mode, putting back the board's own model if a replay replaced it
+    /// Returns false if attract mode wasn't running
+    fn leave_attract(&mut self) -> bool {
+        if let Some(model) = self.attract_saved_model.take() {
+            self.model = model;
+        }
+        self.attract.stop()
+    }
+
+    /// Check if the board is playing itself
+    #[func]
+    fn is_attracting(&self) -> bool {
+        self.attract.is_active()
     }
 
-    /// Create visual nodes for all symbols
-    fn create_symbol_nodes(&mut self) {
-        // Clear existing nodes
-        for node in self.symbol_nodes.drain(..) {
-            if let Some(mut n) = node {
-                n.queue_free();
-            }
+    /// Highlight and make attract mode moves while the board is ready
+    fn advance_attract(&mut self, delta: f64) {
+        if self.state.current() != GameState::Ready {
+            return;
         }
 
-        self.symbol_nodes = vec![None; (self.grid_width * self.grid_height) as usize];
-
-        for y in 0..self.grid_height as usize {
-            for x in 0..self.grid_width as usize {
-                if let Some(symbol) = self.grid.get(x, y) {
-                    let color = symbol.current_color();
-                    let node = self.create_symbol_visual(x, y, color);
-                    let idx = y * self.grid_width as usize + x;
-                    self.symbol_nodes[idx] = Some(node);
+        let model = &self.model;
+        match self.attract.advance(delta, self.attract_move_interval, || model.find_hint()) {
+            Some(AttractStep::Highlight(from, _)) => {
+                self.emit_event("attract_highlight", &[from.to_variant()]);
+            }
+            // The board may have rotated since the move was highlighted
+            Some(AttractStep::Swap(from, to)) => {
+                if from == to {
+                    self.tap_symbol(from, true);
+                } else if self.model.can_swap(from, to) {
+                    self.request_swap(from, to);
                 }
             }
+            Some(AttractStep::Replayed) => self.show_attract_replay(),
+            None => {}
         }
     }
 
-    /// Create a visual representation of a symbol
-    fn create_symbol_visual(&mut self, x: usize, y: usize, color: Color) -> Gd<ColorRect> {
-        let mut rect = ColorRect::new_alloc();
-        let size = self.cell_size - self.cell_padding * 2.0;
-
-        rect.set_size(Vector2::new(size, size));
-        rect.set_color(color);
+    /// Show the board of the replay attract mode is playing back; `stop_attract` resets it for play
+    fn show_attract_replay(&mut self) {
+        let Some(model) = self.attract.replay_model() else {
+            return;
+        };
+        self.model = model.clone();
+        self.view.rebuild(self.model.grid());
+        self.emphasize_symbols();
+    }
 
-        let pos = self.grid_to_screen(x as i32, y as i32);
-        rect.set_position(pos);
+    /// Signal emitted when a soak run ends a level with more nodes or objects than after its first reset
+    #[signal]
+    fn soak_leak(level: i32, baseline: Dictionary, sample: Dictionary, tick: i64);
 
-        self.base_mut().add_child(&rect);
-        rect
-    }
+    /// Signal emitted when a soak run ends: { levels, moves, baseline, leaks }
+    /// `leaks` holds the samples of the levels that leaked, each with its level
+    #[signal]
+    fn soak_finished(report: Dictionary, tick: i64);
 
-    /// Convert grid coordinates to screen position
-    fn grid_to_screen(&self, x: i32, y: i32) -> Vector2 {
-        Vector2::new(
-            self.board_offset.x + x as f32 * self.cell_size + self.cell_padding,
-            self.board_offset.y + y as f32 * self.cell_size + self.cell_padding,
-        )
+    /// Play bot moves for `duration` game seconds, resetting the board every `level_seconds` and
+    /// checking that node and object counts return to where they were after the first reset.
+    /// A headless run hides the symbols and skips animations. Returns false if the board is busy
+    #[func]
+    fn start_soak(&mut self, duration: f64, level_seconds: f64, headless: bool) -> bool {
+        self.guarded("start_soak", |board| board.begin_soak(duration, level_seconds, headless)).unwrap_or(false)
     }
 
-    /// Convert screen position to grid coordinates
-    fn screen_to_grid(&self, pos: Vector2) -> Option<Vector2i> {
-        let local_x = pos.x - self.board_offset.x;
-        let local_y = pos.y - self.board_offset.y;
-
-        if local_x < 0.0 || local_y < 0.0 {
-            return None;
+    /// Start a soak run from a fresh board
+    fn begin_soak(&mut self, duration: f64, level_seconds: f64, headless: bool) -> bool {
+        if !self.state.current().accepts_input() || level_seconds <= 0.0 {
+            return false;
         }
 
-        let grid_x = (local_x / self.cell_size) as i32;
-        let grid_y = (local_y / self.cell_size) as i32;
-
-        if grid_x >= 0 && grid_x < self.grid_width && grid_y >= 0 && grid_y < self.grid_height {
-            Some(Vector2i::new(grid_x, grid_y))
-        } else {
-            None
+        self.leave_attract();
+        self.soak.start(duration, level_seconds, headless);
+        if headless {
+            self.view.root().clone().set_visible(false);
         }
+        self.reset_board();
+        true
     }
 
-    /// Handle a click on the board
-    fn handle_click(&mut self, screen_pos: Vector2) {
-        let Some(grid_pos) = self.screen_to_grid(screen_pos) else {
-            return;
-        };
+    /// Stop a soak run early, emitting soak_finished
+    #[func]
+    fn stop_soak(&mut self) {
+        self.guarded("stop_soak", |board| {
+            if board.soak.stop() {
+                board.finish_soak();
+            }
+        });
+    }
 
-        godot_print!("Clicked on grid position: {:?}", grid_pos);
+    /// Check if a soak run is in progress
+    #[func]
+    fn is_soaking(&self) -> bool {
+        self.soak.is_active()
+    }
 
-        match self.state {
-            GameState::Ready => {
-                // Select this symbol
-                self.select_symbol(grid_pos);
+    /// Make bot moves, end levels and check node counts for the soak run
+    fn advance_soak(&mut self, delta: f64) {
+        match self.soak.advance(delta) {
+            Some(SoakStep::Move) => {
+                if self.state.current() != GameState::Ready {
+                    return;
+                }
+                let Some((from, to)) = self.model.find_hint() else {
+                    return;
+                };
+                self.soak.record_move();
+                if from == to {
+                    self.tap_symbol(from, true);
+                } else {
+                    self.request_swap(from, to);
+                }
             }
-            GameState::Selected => {
-                if let Some(selected) = self.selected_pos {
-                    if self.is_adjacent(selected, grid_pos) {
-                        // Try to swap
-                        self.try_swap(selected, grid_pos);
-                    } else {
-                        // Select new symbol instead
-                        self.deselect_symbol();
-                        self.select_symbol(grid_pos);
-                    }
+            Some(SoakStep::EndLevel) => self.reset_board(),
+            Some(SoakStep::Sample) => {
+                let sample = self.sample_nodes();
+                if let Some(leak) = self.soak.record_sample(sample) {
+                    let baseline = self.soak.baseline().unwrap_or_default();
+                    godot_error!("Soak level {} leaked: {:?}, baseline {:?}", leak.level, leak.sample, baseline);
+                    let args = [
+                        (leak.level as i32).to_variant(),
+                        node_sample(&baseline).to_variant(),
+                        node_sample(&leak.sample).to_variant(),
+                    ];
+                    self.emit_event("soak_leak", &args);
+                }
+                if !self.soak.is_active() {
+                    self.finish_soak();
                 }
             }
-            _ => {}
+            None => {}
         }
     }
 
-    /// Check if two positions are adjacent
-    fn is_adjacent(&self, pos1: Vector2i, pos2: Vector2i) -> bool {
-        let dx = (pos1.x - pos2.x).abs();
-        let dy = (pos1.y - pos2.y).abs();
-        (dx == 1 && dy == 0) || (dx == 0 && dy == 1)
+    /// Count the nodes and objects alive, leaving out pooled symbol nodes
+    fn sample_nodes(&self) -> NodeSample {
+        let monitors = Performance::singleton();
+        let nodes = self.view.node_counts();
+        let pooled = nodes.pooled as i64;
+        NodeSample {
+            engine_nodes: monitors.get_monitor(Monitor::OBJECT_NODE_COUNT) as i64 - pooled,
+            engine_objects: monitors.get_monitor(Monitor::OBJECT_COUNT) as i64 - pooled,
+            view_nodes: nodes.children as i64 - pooled,
+        }
     }
 
-    /// Select a symbol
-    fn select_symbol(&mut self, pos: Vector2i) {
-        self.selected_pos = Some(pos);
-        self.state = GameState::Selected;
+    /// Show the symbols again after a headless run and report the run
+    fn finish_soak(&mut self) {
+        if self.soak.is_headless() {
+            self.view.root().clone().set_visible(true);
+        }
 
-        // Visual feedback - scale up
-        let idx = pos.y as usize * self.grid_width as usize + pos.x as usize;
-        let size = self.cell_size - self.cell_padding * 2.0;
-        let screen_pos = self.grid_to_screen(pos.x, pos.y);
+        let leaks: Array<Dictionary> = self
+            .soak
+            .leaks()
+            .iter()
+            .map(|leak| {
+                let mut entry = node_sample(&leak.sample);
+                entry.set("level", leak.level as i64);
+                entry
+            })
+            .collect();
+        let mut report = Dictionary::new();
+        report.set("levels", self.soak.levels() as i64);
+        report.set("moves", self.soak.moves() as i64);
+        report.set("baseline", node_sample(&self.soak.baseline().unwrap_or_default()));
+        report.set("leaks", leaks);
+        godot_print!("Soak run finished: {} levels, {} leaked", self.soak.levels(), self.soak.leaks().len());
+        self.emit_event("soak_finished", &[report.to_variant()]);
+    }
 
-        if let Some(Some(node)) = self.symbol_nodes.get_mut(idx) {
-            node.set_size(Vector2::new(size + 8.0, size + 8.0));
-            node.set_position(screen_pos - Vector2::new(4.0, 4.0));
-        }
+    /// Signal emitted the first time this profile meets a mechanic; its text is `get_text("teach.<topic>", {})`
+    #[signal]
+    fn teach_moment(topic: GString, tick: i64);
 
-        godot_print!("Selected symbol at {:?}", pos);
+    /// Set the teach moments file and load the topics already seen from it
+    #[func]
+    fn set_teach_moments_path(&mut self, path: GString) {
+        self.teach_moments_path = path;
+        self.load_teach_moments();
     }
 
-    /// Deselect current symbol
-    fn deselect_symbol(&mut self) {
-        if let Some(pos) = self.selected_pos.take() {
-            // Reset visual
-            let idx = pos.y as usize * self.grid_width as usize + pos.x as usize;
-            let size = self.cell_size - self.cell_padding * 2.0;
-            let screen_pos = self.grid_to_screen(pos.x, pos.y);
+    /// Check if a teach moment was already shown to this profile
+    #[func]
+    fn has_seen_teach_moment(&self, topic: GString) -> bool {
+        self.teaching.has_seen(&topic.to_string())
+    }
 
-            if let Some(Some(node)) = self.symbol_nodes.get_mut(idx) {
-                node.set_size(Vector2::new(size, size));
-                node.set_position(screen_pos);
-            }
-        }
-        self.state = GameState::Ready;
+    /// Forget every shown teach moment, e.g. for a new profile
+    #[func]
+    fn reset_teach_moments(&mut self) {
+        self.teaching = TeachingLog::default();
+        self.save_teach_moments();
     }
 
-    /// Try to swap two symbols
-    fn try_swap(&mut self, pos1: Vector2i, pos2: Vector2i) {
-        // Check if swap would create a match
-        if !MatchFinder::would_create_match(&self.grid, pos1, pos2) {
-            godot_print!("Invalid swap - no match would be created");
-            self.deselect_symbol();
+    /// Emit teach_moment if this profile has not seen the topic yet
+    fn teach(&mut self, topic: &str) {
+        if Engine::singleton().is_editor_hint() || !self.teaching.first_time(topic) {
             return;
         }
-
-        godot_print!("Swapping {:?} with {:?}", pos1, pos2);
-        self.state = GameState::Swapping;
-
-        // Perform the swap
-        self.swap_symbols(pos1, pos2);
-
-        // Process matches
-        self.process_matches();
+        self.save_teach_moments();
+        self.emit_event("teach_moment", &[GString::from(topic).to_variant()]);
     }
 
-    /// Swap two symbols in the grid and visually
-    fn swap_symbols(&mut self, pos1: Vector2i, pos2: Vector2i) {
-        // Swap in grid
-        let symbol1 = self.grid.take(pos1.x as usize, pos1.y as usize);
-        let symbol2 = self.grid.take(pos2.x as usize, pos2.y as usize);
-
-        if let (Some(mut s1), Some(mut s2)) = (symbol1, symbol2) {
-            s1.grid_pos = pos2;
-            s2.grid_pos = pos1;
-
-            self.grid.set(pos2.x as usize, pos2.y as usize, Some(s1));
-            self.grid.set(pos1.x as usize, pos1.y as usize, Some(s2));
+    /// Load seen teach moments, starting empty if the file is missing
+    fn load_teach_moments(&mut self) {
+        let mut config = ConfigFile::new_gd();
+        if config.load(&self.teach_moments_path) != Error::OK || !config.has_section("seen") {
+            self.teaching = TeachingLog::default();
+            return;
         }
 
-        // Swap visual nodes
-        let idx1 = pos1.y as usize * self.grid_width as usize + pos1.x as usize;
-        let idx2 = pos2.y as usize * self.grid_width as usize + pos2.x as usize;
-
-        self.symbol_nodes.swap(idx1, idx2);
-
-        // Calculate positions before borrowing
-        let size = self.cell_size - self.cell_padding * 2.0;
-        let screen_pos1 = self.grid_to_screen(pos1.x, pos1.y);
-        let screen_pos2 = self.grid_to_screen(pos2.x, pos2.y);
+        let topics = config.get_section_keys("seen");
+        self.teaching = TeachingLog::from_topics(topics.as_slice().iter().map(|topic| topic.to_string()));
+    }
 
-        // Update positions
-        if let Some(Some(node)) = self.symbol_nodes.get_mut(idx1) {
-            node.set_size(Vector2::new(size, size));
-            node.set_position(screen_pos1);
+    /// Save seen teach moments
+    fn save_teach_moments(&self) {
+        let mut config = ConfigFile::new_gd();
+        for topic in self.teaching.topics() {
+            config.set_value("seen", topic, &true.to_variant());
         }
-        if let Some(Some(node)) = self.symbol_nodes.get_mut(idx2) {
-            node.set_size(Vector2::new(size, size));
-            node.set_position(screen_pos2);
+
+        let err = config.save(&self.teach_moments_path);
+        if err != Error::OK {
+            godot_warn!("Cannot save teach moments to {}: {:?}", self.teach_moments_path, err);
         }
     }
 
-    /// Process all matches on the board
-    fn process_matches(&mut self) {
-        let matches = MatchFinder::find_all(&self.grid);
-
-        if matches.is_empty() {
-            self.combo = 1;
-            self.state = GameState::Ready;
-            self.selected_pos = None;
-            return;
-        }
+    /// Signal emitted when a match clears the jelly from a cell, with the jelly cells left
+    /// The level ends as a win once the move that cleared the last jelly settles
+    #[signal]
+    fn jelly_cleared(remaining: i32, tick: i64);
 
-        self.state = GameState::Matching;
+    /// Get the cells with jelly left
+    #[func]
+    fn get_jelly_cells(&self) -> Array<Vector2i> {
+        self.model.jelly().cells().collect()
+    }
 
-        // Calculate score
-        let mut match_score = 0;
-        for m in &matches {
-            match_score += m.score() * self.combo;
-        }
-        self.score += match_score;
+    /// Signal emitted when an ingredient leaves the board through the bottom row, with the ingredients left
+    /// The level ends as a win once the move that collected the last ingredient settles
+    #[signal]
+    fn ingredient_collected(pos: Vector2i, remaining: i32, tick: i64);
 
-        // Emit signals
-        let match_count = matches.len() as i32;
-        let current_score = self.score;
-        self.base_mut().emit_signal("match_found", &[match_count.to_variant()]);
-        self.base_mut().emit_signal("score_changed", &[current_score.to_variant()]);
+    /// Get the ingredients still on the board
+    #[func]
+    fn get_ingredients_left(&self) -> i32 {
+        self.model.ingredients_left() as i32
+    }
 
-        godot_print!("Found {} matches, score: {} (combo: {}x)", matches.len(), self.score, self.combo);
+    /// Signal emitted when a clear plants a new garden tile
+    #[signal]
+    fn garden_planted(pos: Vector2i, tick: i64);
 
-        // Increment combo for cascades
-        self.combo += 1;
+    /// Turn garden mode on or off, showing or hiding the garden
+    #[func]
+    fn set_garden_mode(&mut self, enabled: bool) {
+        self.garden_mode = enabled;
+        self.show_tiles();
+    }
 
-        // Clear matched symbols with animation
-        let positions = MatchFinder::get_matched_positions(&matches);
-        self.animate_clear_symbols(&positions);
+    /// Set the garden file and load the garden planted so far from it
+    #[func]
+    fn set_garden_path(&mut self, path: GString) {
+        self.garden_path = path;
+        self.load_garden();
+        self.show_tiles();
     }
 
-    /// Animate clearing symbols, then trigger gravity
-    fn animate_clear_symbols(&mut self, positions: &[Vector2i]) {
-        let clear_duration = 0.2;
+    /// Get every planted garden tile, including those off the current board
+    #[func]
+    fn get_garden_tiles(&self) -> Array<Vector2i> {
+        self.garden.tiles().collect()
+    }
 
-        // Collect nodes to animate
-        let mut nodes_to_clear: Vec<Gd<ColorRect>> = Vec::new();
-        for pos in positions {
-            let idx = pos.y as usize * self.grid_width as usize + pos.x as usize;
-            if let Some(Some(node)) = self.symbol_nodes.get(idx) {
-                nodes_to_clear.push(node.clone());
-            }
-            // Remove from grid immediately (logical state)
-            self.grid.set(pos.x as usize, pos.y as usize, None);
-        }
+    /// Dig up the whole garden, e.g. for a new profile
+    #[func]
+    fn clear_garden(&mut self) {
+        self.garden = GardenPlot::default();
+        self.save_garden();
+        self.show_tiles();
+    }
 
-        // Store positions for later cleanup
-        let positions_vec: Vec<Vector2i> = positions.to_vec();
+    /// Signal emitted when an L, T or cross shaped match leaves a seeker behind
+    #[signal]
+    fn seeker_created(pos: Vector2i, tick: i64);
 
-        // Get callable for after animation
-        let callable = self.base().callable("on_clear_complete");
+    /// Signal emitted when a cleared seeker flies to the cell most valuable for the objectives
+    #[signal]
+    fn seeker_launched(from: Vector2i, to: Vector2i, tick: i64);
 
-        // Create shrink/fade animation
-        if let Some(mut tween) = self.base_mut().create_tween() {
-            tween.set_parallel();
+    /// Signal emitted when a four-long match leaves a striped symbol behind
+    /// `kind` is "striped_row" (it will clear its row) or "striped_column"
+    #[signal]
+    fn striped_created(pos: Vector2i, kind: GString, tick: i64);
 
-            let zero_scale = Variant::from(Vector2::ZERO);
-            for node in &nodes_to_clear {
-                // Shrink to nothing
-                tween.tween_property(
-                    node,
-                    "scale",
-                    &zero_scale,
-                    clear_duration,
-                );
-            }
+    /// Signal emitted when a cleared striped symbol clears its row or column
+    #[signal]
+    fn stripe_fired(pos: Vector2i, kind: GString, tick: i64);
 
-            // After animation, clean up and apply gravity
-            tween.chain();
-            tween.tween_callback(&callable);
-        }
+    /// Signal emitted when a straight match of five or more leaves a color bomb behind
+    #[signal]
+    fn color_bomb_created(pos: Vector2i, tick: i64);
 
-        // Store positions to clear in a temporary way - we'll clean up in callback
-        // For now, mark which indices need cleanup
-        for pos in &positions_vec {
-            let idx = pos.y as usize * self.grid_width as usize + pos.x as usize;
-            if let Some(node_opt) = self.symbol_nodes.get_mut(idx) {
-                // We'll free these in the callback
-                *node_opt = None;
-            }
-        }
+    /// Signal emitted when a swapped color bomb clears every symbol of `symbol_type`
+    #[signal]
+    fn color_bomb_fired(pos: Vector2i, symbol_type: i32, tick: i64);
 
-        // Queue free the nodes after animation
-        for mut node in nodes_to_clear {
-            // Use a separate tween to delay the queue_free
-            if let Some(mut delay_tween) = self.base_mut().create_tween() {
-                let free_callable = node.callable("queue_free");
-                delay_tween.tween_interval(clear_duration + 0.01);
-                delay_tween.tween_callback(&free_callable);
-            }
-        }
-    }
+    /// Signal emitted when two specials swapped into each other go off together
+    /// `effect` is "cross", "clear_board", "blast" or "convert_and_fire"
+    #[signal]
+    fn special_combo(pos: Vector2i, effect: GString, tick: i64);
 
-    /// Called when clear animation completes
+    /// Set the symbol type the objectives most need (-1 for none)
     #[func]
-    fn on_clear_complete(&mut self) {
-        godot_print!("Clear animation complete, applying gravity");
-        self.animate_gravity();
+    fn set_objective_color(&mut self, index: i32) {
+        self.objective_color = index;
+        let objective = (index >= 0).then(|| Self::symbol_type_from_i32(index)).flatten();
+        self.model.set_objective(objective);
+        self.emphasize_symbols();
     }
 
-    /// Clear symbols at the given positions
-    fn clear_symbols(&mut self, positions: &[Vector2i]) {
-        for pos in positions {
-            let idx = pos.y as usize * self.grid_width as usize + pos.x as usize;
-
-            // Remove from grid
-            self.grid.set(pos.x as usize, pos.y as usize, None);
-
-            // Remove visual
-            if let Some(Some(mut node)) = self.symbol_nodes.get_mut(idx).map(|n| n.take()) {
-                node.queue_free();
-            }
+    /// Update a cell's node to the color of its symbol
+    fn repaint_cell(&mut self, pos: Vector2i) {
+        let color = self.model.grid().get(pos.x as usize, pos.y as usize).map(|symbol| symbol.current_color());
+        if let Some(color) = color {
+            self.view.set_cell_color(pos, color);
         }
     }
 
-    /// Apply gravity - make symbols fall down (no animation, instant)
-    fn apply_gravity(&mut self) {
-        self.state = GameState::Falling;
+    /// Signal emitted when a match next to a stone takes a hit point off it
+    #[signal]
+    fn stone_hit(pos: Vector2i, hit_points: i32, tick: i64);
 
-        for x in 0..self.grid_width as usize {
-            let mut write_y = self.grid_height as usize - 1;
+    /// Signal emitted when a stone loses its last hit point and its cell opens up
+    #[signal]
+    fn stone_broken(pos: Vector2i, tick: i64);
 
-            for read_y in (0..self.grid_height as usize).rev() {
-                if self.grid.get(x, read_y).is_some() {
-                    if read_y != write_y {
-                        // Move symbol down
-                        let symbol = self.grid.take(x, read_y);
-                        if let Some(mut s) = symbol {
-                            s.grid_pos = Vector2i::new(x as i32, write_y as i32);
-                            self.grid.set(x, write_y, Some(s));
-                        }
+    /// Signal emitted when a match next to chocolate eats it, opening its cell
+    #[signal]
+    fn chocolate_eaten(pos: Vector2i, tick: i64);
 
-                        // Move visual node
-                        let from_idx = read_y * self.grid_width as usize + x;
-                        let to_idx = write_y * self.grid_width as usize + x;
+    /// Signal emitted when chocolate grows into a cell after a move that ate none
+    #[signal]
+    fn chocolate_spread(pos: Vector2i, tick: i64);
 
-                        let screen_pos = self.grid_to_screen(x as i32, write_y as i32);
-                        let node = self.symbol_nodes[from_idx].take();
-                        if let Some(mut n) = node {
-                            n.set_position(screen_pos);
-                            self.symbol_nodes[to_idx] = Some(n);
-                        }
-                    }
-                    write_y = write_y.saturating_sub(1);
-                }
-            }
-        }
-    }
+    /// Signal emitted when the fog modifier clears the fog from a cell, showing its symbol
+    #[signal]
+    fn cell_revealed(pos: Vector2i, tick: i64);
+
+    /// Signal emitted when the fog covers a cell again, some moves after the last activity near it
+    #[signal]
+    fn cell_fogged(pos: Vector2i, tick: i64);
+
+    /// Signal emitted when a match through a frozen cell cracks a layer off its ice
+    #[signal]
+    fn ice_cracked(pos: Vector2i, layers: i32, tick: i64);
 
-    /// Apply gravity with falling animation
-    fn animate_gravity(&mut self) {
-        self.state = GameState::Falling;
+    /// Signal emitted when a frozen cell's last layer of ice breaks, freeing its symbol
+    #[signal]
+    fn ice_broken(pos: Vector2i, tick: i64);
 
-        let fall_duration_per_cell = 0.08;
+    /// Signal emitted when a symbol sits unmatched too long and turns into a blocker
+    #[signal]
+    fn symbol_expired(pos: Vector2i, tick: i64);
 
-        // Collect all moves needed: (from_idx, to_idx, from_y, to_y, x)
-        let mut moves: Vec<(usize, usize, usize, usize, usize)> = Vec::new();
+    /// Tint every symbol by how close it is to expiring
+    fn show_symbol_ages(&mut self) {
+        let expiry = self.model.expiry();
+        if expiry == 0 {
+            return;
+        }
+        let tints: Vec<(Vector2i, Color)> = self
+            .model
+            .grid()
+            .cells
+            .iter()
+            .flatten()
+            .map(|symbol| (symbol.grid_pos, expiry::tint(symbol, expiry)))
+            .collect();
+        for (pos, tint) in tints {
+            self.view.set_cell_tint(pos, tint);
+        }
+    }
 
-        for x in 0..self.grid_width as usize {
-            let mut write_y = self.grid_height as usize - 1;
+    /// Signal emitted when a match uses up the score multiplier on a cell
+    #[signal]
+    fn multiplier_consumed(pos: Vector2i, tick: i64);
+
+    /// Get the tile shown under a cell: its score multiplier, else its garden tile
+    fn cell_tile(&self, pos: Vector2i) -> Option<Color> {
+        let factor = self.model.cell_multipliers().factor(pos);
+        if self.model.jelly().contains(pos) {
+            Some(jelly::TILE_COLOR)
+        } else if factor > 1 {
+            Some(multipliers::tile_color(factor))
+        } else if self.garden_mode && self.garden.contains(pos) {
+            Some(garden::TILE_COLOR)
+        } else {
+            None
+        }
+    }
 
-            for read_y in (0..self.grid_height as usize).rev() {
-                if self.grid.get(x, read_y).is_some() {
-                    if read_y != write_y {
-                        let from_idx = read_y * self.grid_width as usize + x;
-                        let to_idx = write_y * self.grid_width as usize + x;
-                        moves.push((from_idx, to_idx, read_y, write_y, x));
+    /// Show the tiles under every cell: jelly, score multipliers, and garden tiles in garden mode
+    fn show_tiles(&mut self) {
+        self.view.clear_tiles();
+        let mut multiplied: Vec<Vector2i> = self.model.cell_multipliers().cells().map(|(pos, _)| pos).collect();
+        multiplied.extend(self.model.jelly().cells());
+        let planted: Vec<Vector2i> = if self.garden_mode {
+            self.garden.within(self.grid_width as usize, self.grid_height as usize).collect()
+        } else {
+            Vec::new()
+        };
+        for pos in multiplied.into_iter().chain(planted) {
+            self.view.set_tile(pos, self.cell_tile(pos));
+        }
+    }
 
-                        // Update logical grid
-                        let symbol = self.grid.take(x, read_y);
-                        if let Some(mut s) = symbol {
-                            s.grid_pos = Vector2i::new(x as i32, write_y as i32);
-                            self.grid.set(x, write_y, Some(s));
-                        }
-                    }
-                    write_y = write_y.saturating_sub(1);
-                }
+    /// Cover every cell a modifier hides in fog
+    fn show_fog(&mut self) {
+        for y in 0..self.grid_height {
+            for x in 0..self.grid_width {
+                let pos = Vector2i::new(x, y);
+                self.view.set_fogged(pos, self.modifier_stack.hides(pos));
             }
         }
+    }
 
-        if moves.is_empty() {
-            // No falling needed, go straight to refill
-            self.animate_refill();
+    /// Load the garden, starting empty if the file is missing
+    fn load_garden(&mut self) {
+        self.garden_dirty = false;
+        let mut config = ConfigFile::new_gd();
+        if config.load(&self.garden_path) != Error::OK || !config.has_section_key("garden", "tiles") {
+            self.garden = GardenPlot::default();
             return;
         }
 
-        // Collect nodes and target positions
-        let mut animations: Vec<(Gd<ColorRect>, Vector2, f64)> = Vec::new();
-        for (from_idx, to_idx, from_y, to_y, x) in &moves {
-            if let Some(node) = self.symbol_nodes[*from_idx].take() {
-                let target_pos = self.grid_to_screen(*x as i32, *to_y as i32);
-                let distance = *to_y - *from_y;
-                let duration = distance as f64 * fall_duration_per_cell;
-                animations.push((node, target_pos, duration));
-                // Will be placed at to_idx after animation
+        let tiles = config.get_value("garden", "tiles");
+        match tiles.try_to::<Array<Vector2i>>() {
+            Ok(tiles) => self.garden = GardenPlot::from_tiles(tiles.iter_shared()),
+            Err(_) => {
+                godot_warn!("Ignoring malformed garden in {}", self.garden_path);
+                self.garden = GardenPlot::default();
             }
         }
+    }
 
-        // Calculate max duration for callback timing
-        let max_duration = animations.iter().map(|(_, _, d)| *d).fold(0.0, f64::max);
-
-        // Get callback
-        let callable = self.base().callable("on_gravity_complete");
+    /// Save the garden
+    fn save_garden(&mut self) {
+        self.garden_dirty = false;
+        let tiles: Array<Vector2i> = self.garden.tiles().collect();
+        let mut config = ConfigFile::new_gd();
+        config.set_value("garden", "tiles", &tiles.to_variant());
 
-        // Create fall animations
-        if let Some(mut tween) = self.base_mut().create_tween() {
-            tween.set_parallel();
+        let err = config.save(&self.garden_path);
+        if err != Error::OK {
+            godot_warn!("Cannot save the garden to {}: {:?}", self.garden_path, err);
+        }
+    }
 
-            for (node, target_pos, duration) in &animations {
-                let pos_variant = Variant::from(*target_pos);
-                tween.tween_property(
-                    node,
-                    "position",
-                    &pos_variant,
-                    *duration,
-                );
-            }
+    /// Signal emitted after switching profiles, once the profile's saves are loaded
+    #[signal]
+    fn profile_switched(name: GString, tick: i64);
 
-            tween.chain();
-            tween.tween_callback(&callable);
+    /// List the profiles under profiles_dir
+    #[func]
+    fn list_profiles(&self) -> PackedStringArray {
+        match DirAccess::open(&self.profiles_dir) {
+            Some(mut dir) => dir.get_directories(),
+            None => PackedStringArray::new(),
         }
+    }
 
-        // Update symbol_nodes array with new positions
-        for (i, (from_idx, to_idx, _, _, _)) in moves.iter().enumerate() {
-            if i < animations.len() {
-                self.symbol_nodes[*to_idx] = Some(animations[i].0.clone());
-            }
+    /// Create an empty profile; names may hold letters, digits, spaces, '-' and '_'
+    /// Returns false if the name is invalid or taken, or the directory cannot be made
+    #[func]
+    fn create_profile(&mut self, name: GString) -> bool {
+        let name = name.to_string();
+        if let Err(message) = profiles::validate_name(&name) {
+            godot_warn!("Cannot create profile: {}", message);
+            return false;
+        }
+        let dir = GString::from(profiles::profile_dir(&self.profiles_dir.to_string(), &name));
+        if DirAccess::dir_exists_absolute(&dir) {
+            godot_warn!("Profile {} already exists", name);
+            return false;
         }
+        let err = DirAccess::make_dir_recursive_absolute(&dir);
+        if err != Error::OK {
+            godot_warn!("Cannot create profile directory {}: {:?}", dir, err);
+            return false;
+        }
+        true
     }
 
-    /// Called when gravity animation completes
+    /// Save the current profile and load another: its teach moments, garden, settings and statistics
+    /// Returns false if there is no such profile
     #[func]
-    fn on_gravity_complete(&mut self) {
-        godot_print!("Gravity complete, refilling board");
-        self.animate_refill();
+    fn switch_profile(&mut self, name: GString) -> bool {
+        self.guarded("switch_profile", |board| board.load_other_profile(name.to_string())).unwrap_or(false)
     }
 
-    /// Refill empty spaces with animation
-    fn animate_refill(&mut self) {
-        let spawn_duration = 0.15;
+    /// Save the current profile and load the named one
+    fn load_other_profile(&mut self, name: String) -> bool {
+        let root = self.profiles_dir.to_string();
+        let exists = profiles::validate_name(&name).is_ok()
+            && DirAccess::dir_exists_absolute(&GString::from(profiles::profile_dir(&root, &name)));
+        if !exists {
+            godot_warn!("No profile named {}", name);
+            return false;
+        }
 
-        // Find empty cells and create new symbols
-        let mut new_symbols: Vec<(usize, usize, Gd<ColorRect>)> = Vec::new();
+        self.save_profile();
+        if self.garden_dirty {
+            self.save_garden();
+        }
+        self.profile = Some(name.clone());
+        self.set_teach_moments_path(profiles::file_path(&root, &name, profiles::TEACH_MOMENTS_FILE).into());
+        self.set_garden_path(profiles::file_path(&root, &name, profiles::GARDEN_FILE).into());
+        self.load_profile();
+        self.emit_event("profile_switched", &[GString::from(name.as_str()).to_variant()]);
+        true
+    }
 
-        for x in 0..self.grid_width as usize {
-            for y in 0..self.grid_height as usize {
-                if self.grid.get(x, y).is_none() {
-                    // Create new symbol
-                    let symbol_type = SymbolType::random();
-                    let symbol = Symbol::with_type(Vector2i::new(x as i32, y as i32), symbol_type);
-                    let color = symbol_type.color();
+    /// Delete a profile and all its saves
+    /// Returns false if it is in use, does not exist or cannot be removed
+    #[func]
+    fn delete_profile(&mut self, name: GString) -> bool {
+        let name = name.to_string();
+        if self.profile.as_deref() == Some(name.as_str()) {
+            godot_warn!("Cannot delete profile {} while it is in use", name);
+            return false;
+        }
+        let path = GString::from(profiles::profile_dir(&self.profiles_dir.to_string(), &name));
+        let dir = profiles::validate_name(&name).is_ok().then(|| DirAccess::open(&path)).flatten();
+        let Some(mut dir) = dir else {
+            godot_warn!("No profile named {}", name);
+            return false;
+        };
 
-                    self.grid.set(x, y, Some(symbol));
+        for file in dir.get_files().as_slice() {
+            let err = dir.remove(file);
+            if err != Error::OK {
+                godot_warn!("Cannot remove {} from profile {}: {:?}", file, name, err);
+                return false;
+            }
+        }
+        let err = DirAccess::remove_absolute(&path);
+        if err != Error::OK {
+            godot_warn!("Cannot remove profile directory {}: {:?}", path, err);
+            return false;
+        }
+        true
+    }
 
-                    // Create visual starting from above the board
-                    let mut rect = ColorRect::new_alloc();
-                    let size = self.cell_size - self.cell_padding * 2.0;
-                    rect.set_size(Vector2::new(size, size));
-                    rect.set_color(color);
+    /// Get the name of the profile in use ("" when none was switched to)
+    #[func]
+    fn get_current_profile(&self) -> GString {
+        self.profile.as_deref().unwrap_or_default().into()
+    }
 
-                    // Start above the board
-                    let start_pos = Vector2::new(
-                        self.board_offset.x + x as f32 * self.cell_size + self.cell_padding,
-                        -self.cell_size, // Above the visible area
-                    );
-                    rect.set_position(start_pos);
-                    rect.set_scale(Vector2::new(1.0, 1.0)); // Ensure scale is reset
+    /// Get the current profile's settings and statistics file, if a profile is in use
+    fn profile_file(&self) -> Option<GString> {
+        let name = self.profile.as_ref()?;
+        Some(profiles::file_path(&self.profiles_dir.to_string(), name, profiles::PROFILE_FILE).into())
+    }
 
-                    self.base_mut().add_child(&rect);
+    /// Load the current profile's settings and statistics; a new profile keeps the settings
+    /// and starts its statistics and unlocks over
+    fn load_profile(&mut self) {
+        let Some(path) = self.profile_file() else {
+            return;
+        };
+        let mut config = ConfigFile::new_gd();
+        if config.load(&path) != Error::OK {
+            self.best_score = 0;
+            self.unlocks = self.starting_unlocks;
+            self.save_profile();
+            self.emit_event("unlocks_changed", &[self.starting_unlocks.to_variant()]);
+            return;
+        }
 
-                    new_symbols.push((x, y, rect));
-                }
-            }
+        let value = |section: &str, key: &str| {
+            config.has_section_key(section, key).then(|| config.get_value(section, key))
+        };
+        let best_score = value("stats", "best_score").and_then(|score| score.try_to::<i32>().ok());
+        let locale = value("settings", "locale").and_then(|code| code.try_to::<GString>().ok());
+        let preset = value("settings", "performance_preset").and_then(|level| level.try_to::<i32>().ok());
+        let unlocks = value("progress", "unlocks").and_then(|flags| flags.try_to::<i32>().ok());
+
+        self.best_score = best_score.unwrap_or(0);
+        self.unlocks = unlocks.unwrap_or(self.starting_unlocks);
+        self.emit_event("unlocks_changed", &[self.unlocks.to_variant()]);
+        if let Some(locale) = locale {
+            self.set_locale(locale);
         }
+        if let Some(preset) = preset {
+            self.set_performance_preset(preset);
+        }
+    }
 
-        if new_symbols.is_empty() {
-            // No refill needed, check for cascades
-            self.on_refill_complete();
+    /// Save the current profile's settings and statistics, if a profile is in use
+    fn save_profile(&self) {
+        let Some(path) = self.profile_file() else {
             return;
+        };
+        let mut config = ConfigFile::new_gd();
+        config.set_value("settings", "locale", &self.get_locale().to_variant());
+        config.set_value("settings", "performance_preset", &self.performance.index().to_variant());
+        config.set_value("stats", "best_score", &self.best_score.to_variant());
+        config.set_value("progress", "unlocks", &self.unlocks.to_variant());
+
+        let err = config.save(&path);
+        if err != Error::OK {
+            godot_warn!("Cannot save profile to {}: {:?}", path, err);
         }
+    }
 
-        // Pre-calculate target positions
-        let target_positions: Vec<(Vector2, f64)> = new_symbols
-            .iter()
-            .map(|(x, y, _)| {
-                let target_pos = self.grid_to_screen(*x as i32, *y as i32);
-                let duration = spawn_duration + (*y as f64 * 0.05);
-                (target_pos, duration)
-            })
-            .collect();
+    /// Signal emitted when game logic panicked and the board was reset, with a report:
+    /// { entry, message, seed, state, board, faces, inputs, recovered }, where `board` and `faces`
+    /// are snapshot rows of the board before the reset and `recovered` is false if the reset failed too
+    #[signal]
+    fn fatal_error(report: Dictionary, tick: i64);
 
-        // Get callback
-        let callable = self.base().callable("on_refill_complete");
-
-        // Animate new symbols falling into place
-        if let Some(mut tween) = self.base_mut().create_tween() {
-            tween.set_parallel();
-
-            for (i, (_, _, node)) in new_symbols.iter().enumerate() {
-                let (target_pos, duration) = target_positions[i];
-                let pos_variant = Variant::from(target_pos);
-                tween.tween_property(
-                    node,
-                    "position",
-                    &pos_variant,
-                    duration,
-                );
-            }
+    /// Signal emitted when a board operation fails or the board repairs itself, with text in the current locale
+    #[signal]
+    fn board_error(code: GString, detail: GString, tick: i64);
+
+    /// Log a board error and emit board_error
+    fn report_error(&mut self, err: BoardError) {
+        godot_warn!("Board error: {}", err);
+        let args: Vec<_> =
+            err.position().map(|pos| ("pos".to_string(), format!("({}, {})", pos.x, pos.y))).into_iter().collect();
+        let detail = self.catalog.text(&format!("error.{}", err.code()), &args);
+        self.emit_event("board_error", &[GString::from(err.code()).to_variant(), GString::from(detail).to_variant()]);
+    }
 
-            tween.chain();
-            tween.tween_callback(&callable);
+    /// Switch the locale of the text the board produces, e.g. "en" or "pt_BR"
+    /// Text missing from the locale falls back to its base language, then to English
+    #[func]
+    fn set_locale(&mut self, code: GString) {
+        let code = code.to_string();
+        if !self.catalog.has_locale(&code) {
+            godot_warn!("No text for locale {}; falling back to {}", code, locale::DEFAULT_LOCALE);
         }
+        self.catalog.set_locale(&code);
+        self.save_profile();
+    }
 
-        // Store nodes in symbol_nodes
-        for (x, y, node) in new_symbols {
-            let idx = y * self.grid_width as usize + x;
-            self.symbol_nodes[idx] = Some(node);
-        }
+    /// Get the current locale code
+    #[func]
+    fn get_locale(&self) -> GString {
+        self.catalog.locale().into()
     }
 
-    /// Called when refill animation completes
+    /// Add text for a locale from a dictionary of message keys to text
     #[func]
-    fn on_refill_complete(&mut self) {
-        godot_print!("Refill complete, checking for cascades");
-        // Check for new matches (cascades)
-        self.process_matches();
+    fn add_translations(&mut self, code: GString, messages: Dictionary) {
+        let messages = messages.iter_shared().map(|(key, text)| (key.to_string(), text.to_string()));
+        self.catalog.add_messages(&code.to_string(), messages);
     }
 
-    /// Refill empty spaces with new symbols
-    fn refill_board(&mut self) {
-        for x in 0..self.grid_width as usize {
-            for y in 0..self.grid_height as usize {
-                if self.grid.get(x, y).is_none() {
-                    // Create new symbol
-                    let symbol_type = SymbolType::random();
-                    let symbol = Symbol::with_type(Vector2i::new(x as i32, y as i32), symbol_type);
-                    let color = symbol_type.color();
+    /// Get the text for a message key in the current locale, filling `{name}` placeholders from `args`
+    #[func]
+    fn get_text(&self, key: GString, args: Dictionary) -> GString {
+        let args: Vec<_> = args.iter_shared().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+        self.catalog.text(&key.to_string(), &args).into()
+    }
 
-                    self.grid.set(x, y, Some(symbol));
+    /// Bring the symbol nodes back in line with the grid, e.g. after tween callbacks were missed or nodes
+    /// freed while the app was in the background; runs by itself when the app regains focus or resumes.
+    /// A command still waiting on an animation that is no longer running is completed first.
+    /// Returns the number of cells rebuilt, or -1 while the board is paused or animating, in which
+    /// case it runs again once the animations finish
+    #[func]
+    fn resync_visuals(&mut self) -> i32 {
+        self.resync_pending = false;
+        if self.symbols_away || self.state.current() == GameState::Transitioning {
+            return 0;
+        }
+        if self.clock.is_paused() || self.view.is_animating() {
+            self.resync_pending = true;
+            return -1;
+        }
 
-                    // Create visual
-                    let node = self.create_symbol_visual(x, y, color);
-                    let idx = y * self.grid_width as usize + x;
-                    self.symbol_nodes[idx] = Some(node);
-                }
+        if self.commands.is_waiting() {
+            godot_warn!("Completing a command whose animation never called back");
+            self.complete_command(self.generation);
+            if self.view.is_animating() {
+                self.resync_pending = true;
+                return -1;
+            }
+        }
+
+        let mut cells = self.view.find_mismatches(self.model.grid());
+        for pos in self.view.find_misplaced() {
+            if !cells.contains(&pos) {
+                cells.push(pos);
             }
         }
+        if cells.is_empty() {
+            return 0;
+        }
+        godot_print!("Resynced {} symbol nodes with the grid", cells.len());
+        self.view.repair(self.model.grid(), &cells);
+        self.show_symbol_ages();
+        cells.len() as i32
     }
 
-    /// Trigger rotation of all symbols
-    fn trigger_rotation(&mut self) {
-        if self.state != GameState::Ready {
+    /// Rebuild any symbol nodes that no longer reflect the grid
+    fn reconcile_view(&mut self) {
+        let mismatches = self.view.find_mismatches(self.model.grid());
+        if mismatches.is_empty() {
             return;
         }
 
-        godot_print!("Triggering rotation!");
-        self.state = GameState::Rotating;
+        for pos in &mismatches {
+            self.report_error(BoardError::ViewMismatch(*pos));
+        }
+        self.view.repair(self.model.grid(), &mismatches);
+    }
 
-        let rotation_duration = 0.3;
-        let size = self.cell_size - self.cell_padding * 2.0;
+    /// Signal emitted when the intro has finished and the board takes input
+    #[signal]
+    fn intro_finished(tick: i64);
 
-        // Set pivot to center for all symbols first
-        for idx in 0..self.symbol_nodes.len() {
-            if let Some(Some(node)) = self.symbol_nodes.get_mut(idx) {
-                node.set_pivot_offset(Vector2::new(size / 2.0, size / 2.0));
-            }
-        }
+    /// Signal emitted when the outro has finished and every symbol has left the board
+    #[signal]
+    fn outro_finished(tick: i64);
 
-        // Collect nodes to animate (to avoid borrow issues)
-        let nodes_to_animate: Vec<Gd<ColorRect>> = self.symbol_nodes
-            .iter()
-            .filter_map(|opt| opt.as_ref().map(|n| n.clone()))
-            .collect();
+    /// Drop the symbols in column by column, e.g. when the scene opens
+    /// Returns false if the board is not waiting for the player
+    #[func]
+    fn play_intro(&mut self) -> bool {
+        self.guarded("play_intro", |board| board.play_transition(false)).unwrap_or(false)
+    }
 
-        // Get callable before creating tween
-        let callable = self.base().callable("finish_rotation");
-
-        // Create tween for rotation animation
-        if let Some(mut tween) = self.base_mut().create_tween() {
-            tween.set_parallel(); // Enable parallel tweening
-
-            // Animate each symbol's rotation by 90 degrees
-            let final_rotation = Variant::from(std::f64::consts::FRAC_PI_2); // 90 degrees
-            for node in &nodes_to_animate {
-                tween.tween_property(
-                    node,
-                    "rotation",
-                    &final_rotation,
-                    rotation_duration,
-                );
-            }
+    /// Send the symbols flying off the board, e.g. before leaving the scene
+    /// Returns false if the board is not waiting for the player; afterwards the board takes no input
+    /// until an intro brings the symbols back or it is reset
+    #[func]
+    fn play_outro(&mut self) -> bool {
+        self.guarded("play_outro", |board| board.play_transition(true)).unwrap_or(false)
+    }
 
-            // Chain a callback to finish the rotation (after parallel tweens complete)
-            tween.chain(); // Switch back to sequential
-            tween.tween_callback(&callable);
+    /// Start the intro or outro animation
+    fn play_transition(&mut self, outro: bool) -> bool {
+        if self.state.current() == GameState::Selected {
+            self.deselect_symbol();
         }
-
-        self.base_mut().emit_signal("rotation_triggered", &[]);
+        // After an outro the board is still transitioning, waiting for the intro that brings the symbols back
+        let returning = !outro && self.symbols_away;
+        if !returning && (self.state.current() != GameState::Ready || !self.set_state(GameState::Transitioning)) {
+            return false;
+        }
+        self.symbols_away = false;
+
+        let on_complete = self
+            .base()
+            .callable("on_transition_complete")
+            .bind(&[self.generation.to_variant(), outro.to_variant()]);
+        let duration = self.animation_time(self.transition_duration);
+        let column_delay =
+            if self.performance.flourishes() { self.animation_time(self.transition_column_delay) } else { 0.0 };
+        if outro {
+            self.view.animate_outro(duration, column_delay, &on_complete);
+        } else {
+            self.view.animate_intro(duration, column_delay, &on_complete);
+        }
+        true
     }
 
-    /// Called when rotation animation finishes
+    /// Called by the view when the intro or outro animation completes
     #[func]
-    fn finish_rotation(&mut self) {
-        godot_print!("Finishing rotation");
-
-        // Rotate the logical grid
-        self.grid.rotate_all();
+    fn on_transition_complete(&mut self, generation: i64, outro: bool) {
+        self.guarded("on_transition_complete", |board| board.complete_transition(generation, outro));
+    }
 
-        // Update visual colors and reset rotation angle
-        for y in 0..self.grid_height as usize {
-            for x in 0..self.grid_width as usize {
-                if let Some(symbol) = self.grid.get(x, y) {
-                    let color = symbol.current_color();
-                    let idx = y * self.grid_width as usize + x;
-                    if let Some(Some(node)) = self.symbol_nodes.get_mut(idx) {
-                        node.set_color(color);
-                        node.set_rotation(0.0);
-                    }
-                }
-            }
+    /// Settle the board once an intro or outro animation is done
+    fn complete_transition(&mut self, generation: i64, outro: bool) {
+        if generation != self.generation {
+            return;
+        }
+        if outro {
+            self.symbols_away = true;
+            self.emit_event("outro_finished", &[]);
+        } else {
+            self.set_state(GameState::Ready);
+            self.emit_event("intro_finished", &[]);
         }
-
-        // Check for new matches after rotation
-        self.state = GameState::Ready;
-        self.process_matches();
     }
 
     /// Reset the board
     #[func]
     fn reset(&mut self) {
-        self.score = 0;
-        self.combo = 1;
-        self.rotation_timer = 0.0;
-        self.state = GameState::Ready;
+        self.guarded("reset", |board| board.reset_board());
+    }
+
+    /// Reset score, timers and state and deal a fresh board
+    fn reset_board(&mut self) {
+        self.reset_score();
+        self.moves_made = 0;
+        self.hold_used = false;
+        self.turn = None;
+        self.rotation_stats = RotationStats::default();
+        self.objective_cleared = 0;
+        self.pacing.on_activity();
+        self.emit_event("moves_changed", &[0.to_variant()]);
+        self.rotation_timer.reset();
+        self.reset_idle();
+        self.last_match_positions.clear();
+        self.commands.clear();
+        self.pending_edits.clear();
+        let previous = self.state.reset();
+        self.emit_state_changed(previous, GameState::Ready);
         self.selected_pos = None;
-        self.initialize_board();
+        self.deal_board();
+        if self.hold_enabled {
+            self.emit_event("hold_changed", &[(-1).to_variant()]);
+        }
+    }
+}
+
+/// Convert soak node counts to a dictionary for the soak signals
+fn node_sample(sample: &NodeSample) -> Dictionary {
+    let mut result = Dictionary::new();
+    result.set("engine_nodes", sample.engine_nodes);
+    result.set("engine_objects", sample.engine_objects);
+    result.set("view_nodes", sample.view_nodes);
+    result
+}
+
+/// Convert a chain's steps to dictionaries for the chain_resolved signal
+fn chain_steps(chain: &Chain) -> Array<Dictionary> {
+    let cells = |cells: &[Vector2i]| -> Array<Vector2i> { cells.iter().copied().collect() };
+    chain
+        .steps
+        .iter()
+        .map(|step| {
+            let matches: Array<Dictionary> = step
+                .matches
+                .iter()
+                .map(|m| {
+                    let mut entry = Dictionary::new();
+                    entry.set("positions", cells(&m.positions));
+                    entry.set("symbol_type", m.symbol_type.index() as i32);
+                    entry.set("moved", cells(&m.moved));
+                    entry
+                })
+                .collect();
+
+            let mut entry = Dictionary::new();
+            entry.set("matches", matches);
+            entry.set("cleared", cells(&step.cleared));
+            entry.set("created", specials_array(&step.created));
+            entry.set("fired", specials_array(&step.fired));
+            entry
+        })
+        .collect()
+}
+
+/// Convert specials by cell to [{ pos, special }] for scripts
+fn specials_array(specials: &[(Vector2i, ChainSpecial)]) -> Array<Dictionary> {
+    specials
+        .iter()
+        .map(|(pos, special)| {
+            let mut entry = Dictionary::new();
+            entry.set("pos", *pos);
+            entry.set("special", special.name());
+            entry
+        })
+        .collect()
+}
+
+/// Convert a symbol's tags to a dictionary for scripts
+fn tags_dictionary(tags: &SymbolTags) -> Dictionary {
+    let mut result = Dictionary::new();
+    for (key, value) in tags {
+        result.set(key.as_str(), value.as_str());
     }
+    result
+}
+
+/// Read a float or integer variant as f64
+fn variant_to_f64(value: &Variant) -> Option<f64> {
+    value
+        .try_to::<f64>()
+        .or_else(|_| value.try_to::<i64>().map(|v| v as f64))
+        .ok()
 }
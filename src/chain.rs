@@ -0,0 +1,169 @@
+//! Chain reactions - the cause and effect of one cascade, step by step
+//!
+//! A chain starts with what set it off (a swap or a rotation). Each step is
+//! one match resolution: the matches found, the cells they cleared, the
+//! specials it left behind and set off, and for every match the cells that
+//! had just fallen or spawned into it, which is what links it to the step
+//! before.
+
+use crate::combo::ComboEffect;
+use crate::symbols::{SymbolKind, SymbolType};
+use godot::prelude::*;
+
+/// What started a chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainCause {
+    /// The player swapped two cells
+    Swap(Vector2i, Vector2i),
+    /// The board rotated
+    Rotation,
+    /// The player cleared a linked path
+    Link,
+    /// The player tapped a symbol to rotate it
+    Tap(Vector2i),
+    /// The player stashed a symbol in the hold slot or swapped the held one back in
+    Hold(Vector2i),
+    /// The whole grid turned a quarter turn
+    Spin,
+}
+
+impl ChainCause {
+    /// Get a stable name for scripts to match on
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChainCause::Swap(..) => "swap",
+            ChainCause::Rotation => "rotation",
+            ChainCause::Link => "link",
+            ChainCause::Tap(_) => "tap",
+            ChainCause::Hold(_) => "hold",
+            ChainCause::Spin => "spin",
+        }
+    }
+}
+
+/// A special a chain step left behind or set off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainSpecial {
+    Seeker,
+    /// A striped symbol of the given kind
+    Striped(SymbolKind),
+    ColorBomb,
+    /// Two specials swapped into each other, going off together
+    Combo(ComboEffect),
+}
+
+impl ChainSpecial {
+    /// Get a stable name for scripts to match on: seeker, striped_row, striped_column, color_bomb,
+    /// or a combo's effect (cross, clear_board, blast, convert_and_fire)
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChainSpecial::Seeker => "seeker",
+            ChainSpecial::Striped(kind) => kind.name(),
+            ChainSpecial::ColorBomb => "color_bomb",
+            ChainSpecial::Combo(effect) => effect.name(),
+        }
+    }
+}
+
+/// The specials one resolution left behind and set off, by cell
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StepSpecials {
+    pub created: Vec<(Vector2i, ChainSpecial)>,
+    pub fired: Vec<(Vector2i, ChainSpecial)>,
+}
+
+/// One match within a chain step
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainMatch {
+    pub positions: Vec<Vector2i>,
+    pub symbol_type: SymbolType,
+    /// Cells of the match that fell or spawned since the previous step
+    pub moved: Vec<Vector2i>,
+}
+
+/// One match resolution within a chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainStep {
+    pub matches: Vec<ChainMatch>,
+    pub cleared: Vec<Vector2i>,
+    /// Specials the step's matches left behind, by cell
+    pub created: Vec<(Vector2i, ChainSpecial)>,
+    /// Specials the step set off, by the cell they went off from
+    pub fired: Vec<(Vector2i, ChainSpecial)>,
+}
+
+/// A complete chain reaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chain {
+    pub cause: ChainCause,
+    pub steps: Vec<ChainStep>,
+}
+
+/// Builds a chain as the model resolves a cascade
+#[derive(Debug, Clone, Default)]
+pub struct ChainRecorder {
+    cause: Option<ChainCause>,
+    steps: Vec<ChainStep>,
+    moved: Vec<Vector2i>,
+}
+
+impl ChainRecorder {
+    /// Start a new chain, dropping any unfinished one
+    pub fn begin(&mut self, cause: ChainCause) {
+        *self = Self {
+            cause: Some(cause),
+            ..Self::default()
+        };
+        if let ChainCause::Swap(pos1, pos2) = cause {
+            self.moved = vec![pos1, pos2];
+        }
+    }
+
+    /// Get what started the chain being recorded, if any
+    pub fn cause(&self) -> Option<ChainCause> {
+        self.cause
+    }
+
+    /// Record cells that a symbol just fell or spawned into
+    pub fn record_moves(&mut self, cells: impl IntoIterator<Item = Vector2i>) {
+        self.moved.extend(cells);
+    }
+
+    /// Record a match resolution as the next step, with the specials it created and fired
+    pub fn record_step(
+        &mut self,
+        matches: Vec<(Vec<Vector2i>, SymbolType)>,
+        cleared: Vec<Vector2i>,
+        specials: StepSpecials,
+    ) {
+        if self.cause.is_none() {
+            return;
+        }
+
+        let moved = std::mem::take(&mut self.moved);
+        let matches = matches
+            .into_iter()
+            .map(|(positions, symbol_type)| ChainMatch {
+                moved: positions.iter().copied().filter(|pos| moved.contains(pos)).collect(),
+                positions,
+                symbol_type,
+            })
+            .collect();
+        self.steps.push(ChainStep {
+            matches,
+            cleared,
+            created: specials.created,
+            fired: specials.fired,
+        });
+    }
+
+    /// End the chain, returning it if anything was matched
+    pub fn finish(&mut self) -> Option<Chain> {
+        let recorder = std::mem::take(self);
+        let cause = recorder.cause?;
+        (!recorder.steps.is_empty()).then_some(Chain {
+            cause,
+            steps: recorder.steps,
+        })
+    }
+}
@@ -0,0 +1,72 @@
+//! Chocolate - an obstacle that spreads when left alone
+//!
+//! Chocolate sits in the grid's overlay layer as `CellOverlay::Chocolate` and,
+//! like a stone, fills its cell: it never matches or falls. Any resolution
+//! that clears a cell next to it eats it in one go. Each player move that
+//! settles without eating any chocolate lets it grow into a neighboring cell,
+//! swallowing a plain symbol or taking an empty cell, so it has to be kept in
+//! check.
+
+use crate::symbols::{CellOverlay, Grid, SymbolKind};
+use godot::prelude::*;
+use rand::Rng;
+
+/// Neighbors chocolate reaches, and is eaten from
+const OFFSETS: [Vector2i; 4] = [Vector2i::new(1, 0), Vector2i::new(-1, 0), Vector2i::new(0, 1), Vector2i::new(0, -1)];
+
+/// Remove the chocolate next to the cleared cells, returning the cells it was on
+pub fn eat_adjacent(grid: &mut Grid, cleared: &[Vector2i]) -> Vec<Vector2i> {
+    let mut eaten = Vec::new();
+    for pos in cleared {
+        for offset in OFFSETS {
+            let neighbor = *pos + offset;
+            if !grid.is_valid(neighbor.x, neighbor.y) || eaten.contains(&neighbor) {
+                continue;
+            }
+            if grid.overlay(neighbor.x as usize, neighbor.y as usize) == Some(CellOverlay::Chocolate) {
+                let _ = grid.set_overlay(neighbor, None);
+                eaten.push(neighbor);
+            }
+        }
+    }
+    eaten
+}
+
+/// Grow chocolate into one random cell next to it, returning that cell
+/// Chocolate only takes empty cells and plain symbols: specials, wilds, seekers, blockers, frozen cells
+/// and other obstacles stop it
+pub fn spread(grid: &mut Grid, rng: &mut impl Rng) -> Option<Vector2i> {
+    let mut targets = Vec::new();
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            if grid.overlay(x, y) != Some(CellOverlay::Chocolate) {
+                continue;
+            }
+            for offset in OFFSETS {
+                let target = Vector2i::new(x as i32, y as i32) + offset;
+                if can_take(grid, target) && !targets.contains(&target) {
+                    targets.push(target);
+                }
+            }
+        }
+    }
+    if targets.is_empty() {
+        return None;
+    }
+    let target = targets[rng.gen_range(0..targets.len())];
+    let _ = grid.set_overlay(target, Some(CellOverlay::Chocolate));
+    Some(target)
+}
+
+/// Check if chocolate can grow into a cell
+fn can_take(grid: &Grid, pos: Vector2i) -> bool {
+    if !grid.is_valid(pos.x, pos.y) {
+        return false;
+    }
+    let (x, y) = (pos.x as usize, pos.y as usize);
+    if !grid.is_enabled(x, y) || grid.overlay(x, y).is_some() {
+        return false;
+    }
+    grid.get(x, y)
+        .is_none_or(|symbol| symbol.kind == SymbolKind::Plain && !symbol.wild && !symbol.seeker && !symbol.blocker)
+}
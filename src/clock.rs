@@ -0,0 +1,67 @@
+//! Game time - a per-board clock and the intervals measured against it
+//!
+//! Deltas come from `process`, which Godot already scales by `Engine.time_scale`
+//! and stops while the tree is paused; the clock adds a board-level pause on top.
+
+/// Game time for one board
+#[derive(Debug, Default, Clone)]
+pub struct GameClock {
+    now: f64,
+    paused: bool,
+}
+
+impl GameClock {
+    /// Advance by a frame delta, returning the game time that passed
+    pub fn advance(&mut self, delta: f64) -> f64 {
+        if self.paused {
+            return 0.0;
+        }
+        self.now += delta;
+        delta
+    }
+
+    /// Get the game time elapsed since creation
+    pub fn now(&self) -> f64 {
+        self.now
+    }
+
+    /// Check if the clock is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pause or resume the clock
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+}
+
+/// A repeating period measured in game time
+#[derive(Debug, Default, Clone)]
+pub struct Interval {
+    elapsed: f64,
+}
+
+impl Interval {
+    /// Advance by `delta`, returning true each time `period` is reached
+    /// Leftover time carries into the next period so long frames don't drift
+    pub fn advance(&mut self, delta: f64, period: f64) -> bool {
+        self.elapsed += delta;
+        if self.elapsed < period {
+            return false;
+        }
+
+        self.elapsed = if period > 0.0 { (self.elapsed - period) % period } else { 0.0 };
+        true
+    }
+
+    /// Get the time elapsed in the current period
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Start the current period over
+    pub fn reset(&mut self) {
+        self.elapsed = 0.0;
+    }
+}
@@ -0,0 +1,174 @@
+//! Special combos - what happens when two specials are swapped into each other
+//!
+//! A `ComboMatrix` maps each pair of special kinds to a blast. Swapping two
+//! specials with an entry is a legal move even without a match; the blast is
+//! cleared with the first resolution of the cascade, on top of any matches,
+//! and the specials inside it still fire their own effects. A convert-and-fire
+//! blast first turns every symbol of one type striped, so all of them fire. Each ruleset
+//! supplies its own matrix through `Ruleset::special_combos`; the model takes
+//! a copy whenever the ruleset changes, which scripts may then adjust.
+
+use crate::symbols::{Grid, Symbol, SymbolKind};
+use godot::prelude::*;
+
+/// A kind of special symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpecialKind {
+    /// Flies to and clears the cell most valuable for the objectives
+    Seeker,
+    /// Clears its row or column
+    Striped,
+    /// Clears every symbol of the type it is swapped with
+    ColorBomb,
+}
+
+impl SpecialKind {
+    /// All special kinds, in matrix order
+    pub const ALL: [SpecialKind; 3] = [SpecialKind::Seeker, SpecialKind::Striped, SpecialKind::ColorBomb];
+
+    /// Get a special kind from the name scripts use for it
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "seeker" => Some(SpecialKind::Seeker),
+            "striped" => Some(SpecialKind::Striped),
+            "color_bomb" => Some(SpecialKind::ColorBomb),
+            _ => None,
+        }
+    }
+
+    /// Get the special kind of a symbol, if it is a special
+    pub fn of(symbol: &Symbol) -> Option<Self> {
+        match symbol.kind {
+            SymbolKind::Ingredient => None,
+            _ if symbol.seeker => Some(SpecialKind::Seeker),
+            SymbolKind::StripedRow | SymbolKind::StripedColumn => Some(SpecialKind::Striped),
+            SymbolKind::ColorBomb => Some(SpecialKind::ColorBomb),
+            SymbolKind::Plain => None,
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// What a combo clears
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComboEffect {
+    /// The whole row and column through the cell swapped into
+    Cross,
+    /// Every cell on the board
+    ClearBoard,
+    /// The square `BLAST_RADIUS` cells around the cell swapped into
+    Blast,
+    /// Every symbol of the swapped symbol's type, turned striped first; the type is that of the
+    /// first of the two swapped symbols that is not a color bomb
+    ConvertAndFire,
+}
+
+/// Cells a blast reaches on each side of its center, making a 5x5 square
+pub const BLAST_RADIUS: i32 = 2;
+
+impl ComboEffect {
+    /// Get the name scripts see for this effect
+    pub fn name(&self) -> &'static str {
+        match self {
+            ComboEffect::Cross => "cross",
+            ComboEffect::ClearBoard => "clear_board",
+            ComboEffect::Blast => "blast",
+            ComboEffect::ConvertAndFire => "convert_and_fire",
+        }
+    }
+
+    /// Get the color the board flashes over the cells the effect clears
+    pub fn flash_color(&self) -> Color {
+        match self {
+            ComboEffect::Cross => Color::from_rgba(1.0, 1.0, 1.0, 0.8),
+            ComboEffect::ClearBoard => Color::from_rgba(1.0, 0.95, 0.6, 0.8),
+            ComboEffect::Blast => Color::from_rgba(1.0, 0.55, 0.15, 0.8),
+            ComboEffect::ConvertAndFire => Color::from_rgba(0.6, 0.9, 1.0, 0.8),
+        }
+    }
+
+    /// Get an effect from its name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "cross" => Some(ComboEffect::Cross),
+            "clear_board" => Some(ComboEffect::ClearBoard),
+            "blast" => Some(ComboEffect::Blast),
+            "convert_and_fire" => Some(ComboEffect::ConvertAndFire),
+            _ => None,
+        }
+    }
+
+    /// Get the occupied cells the effect clears when centered on `at`, the cell swapped into from `other`
+    pub fn cells(&self, grid: &Grid, at: Vector2i, other: Vector2i) -> Vec<Vector2i> {
+        let converted = [at, other]
+            .into_iter()
+            .filter_map(|pos| grid.get(pos.x as usize, pos.y as usize))
+            .find(|symbol| symbol.kind != SymbolKind::ColorBomb)
+            .map(|symbol| symbol.current_type());
+        let mut cells = Vec::new();
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                let pos = Vector2i::new(x as i32, y as i32);
+                let hit = match self {
+                    ComboEffect::Cross => pos.x == at.x || pos.y == at.y,
+                    ComboEffect::ClearBoard => true,
+                    ComboEffect::Blast => (pos.x - at.x).abs() <= BLAST_RADIUS && (pos.y - at.y).abs() <= BLAST_RADIUS,
+                    ComboEffect::ConvertAndFire => {
+                        pos == at
+                            || pos == other
+                            || grid.matchable(x, y).is_some_and(|symbol| Some(symbol.current_type()) == converted)
+                    }
+                };
+                if hit && grid.get(x, y).is_some() {
+                    cells.push(pos);
+                }
+            }
+        }
+        cells
+    }
+}
+
+/// The effect of every pair of special kinds; pairs are unordered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ComboMatrix {
+    effects: [[Option<ComboEffect>; SpecialKind::ALL.len()]; SpecialKind::ALL.len()],
+}
+
+impl ComboMatrix {
+    /// The combos of the built-in variants: two seekers (the bombs L and T shapes leave) blast a 5x5
+    /// square, two striped symbols blast a cross, two color bombs clear the board, and a color bomb
+    /// with a striped symbol converts and fires the striped one's type
+    pub fn standard() -> Self {
+        Self::default()
+            .with(SpecialKind::Seeker, SpecialKind::Seeker, ComboEffect::Blast)
+            .with(SpecialKind::Striped, SpecialKind::Striped, ComboEffect::Cross)
+            .with(SpecialKind::ColorBomb, SpecialKind::ColorBomb, ComboEffect::ClearBoard)
+            .with(SpecialKind::ColorBomb, SpecialKind::Striped, ComboEffect::ConvertAndFire)
+    }
+
+    /// Set the effect of a pair, in either order
+    pub fn with(mut self, a: SpecialKind, b: SpecialKind, effect: ComboEffect) -> Self {
+        self.set(a, b, Some(effect));
+        self
+    }
+
+    /// Set or remove the effect of a pair, in either order
+    pub fn set(&mut self, a: SpecialKind, b: SpecialKind, effect: Option<ComboEffect>) {
+        self.effects[a.index()][b.index()] = effect;
+        self.effects[b.index()][a.index()] = effect;
+    }
+
+    /// Get the effect of a pair, if the pair combines
+    pub fn effect(&self, a: SpecialKind, b: SpecialKind) -> Option<ComboEffect> {
+        self.effects[a.index()][b.index()]
+    }
+
+    /// Get the effect of swapping two cells into each other, if both hold specials that combine
+    pub fn effect_at(&self, grid: &Grid, pos1: Vector2i, pos2: Vector2i) -> Option<ComboEffect> {
+        let kind = |pos: Vector2i| grid.get(pos.x as usize, pos.y as usize).and_then(SpecialKind::of);
+        self.effect(kind(pos1)?, kind(pos2)?)
+    }
+}
@@ -0,0 +1,69 @@
+//! Combo timer - a combo kept alive by matching again before a window runs out
+//!
+//! Under the default `ComboMode::Cascade` the combo multiplier grows through a
+//! cascade and drops back to 1 once it settles. Rules using `ComboMode::Timed`
+//! carry it across moves instead: every resolution refills a window that
+//! drains with game time between moves, and the combo only drops back when the
+//! window runs out. HUDs draw what is left of the window as a bar.
+
+/// How a ruleset keeps up the combo multiplier
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ComboMode {
+    /// The combo lasts for one cascade
+    #[default]
+    Cascade,
+    /// The combo lasts while matches keep coming within `window` seconds of each other
+    Timed { window: f64 },
+}
+
+/// The draining window of a timed combo
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ComboTimer {
+    window: f64,
+    remaining: f64,
+}
+
+impl ComboTimer {
+    /// Create the timer a combo mode needs, if any
+    pub fn for_mode(mode: ComboMode) -> Option<Self> {
+        match mode {
+            ComboMode::Cascade => None,
+            ComboMode::Timed { window } => Some(Self {
+                window: window.max(0.0),
+                remaining: 0.0,
+            }),
+        }
+    }
+
+    /// Start the window over, after a match
+    pub fn refill(&mut self) {
+        self.remaining = self.window;
+    }
+
+    /// Check if the window is open
+    pub fn is_running(&self) -> bool {
+        self.remaining > 0.0
+    }
+
+    /// Get the share of the window left, from 0 to 1
+    pub fn fraction(&self) -> f64 {
+        if self.window <= 0.0 {
+            return 0.0;
+        }
+        self.remaining / self.window
+    }
+
+    /// Drain the window by `delta` seconds, returning true if that closed it
+    pub fn advance(&mut self, delta: f64) -> bool {
+        if !self.is_running() {
+            return false;
+        }
+        self.remaining = (self.remaining - delta).max(0.0);
+        !self.is_running()
+    }
+
+    /// Close the window
+    pub fn reset(&mut self) {
+        self.remaining = 0.0;
+    }
+}
@@ -0,0 +1,80 @@
+//! Board commands - the steps of a move, processed one at a time
+//!
+//! Each command either finishes immediately or waits for its animation to
+//! complete before the next one starts. Commands may queue follow-up commands,
+//! e.g. a clear that found matches queues gravity, refill and another clear.
+
+use godot::prelude::*;
+use std::collections::VecDeque;
+
+/// A single board mutation step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardCommand {
+    /// Swap two adjacent symbols
+    Swap(Vector2i, Vector2i),
+    /// Score and remove all current matches
+    Clear,
+    /// Drop symbols into emptied cells
+    Fall,
+    /// Spawn new symbols into empty cells
+    Refill,
+    /// Rotate every symbol to its next face
+    Rotate,
+    /// Score and remove the linked path the player just released
+    ClearLink,
+    /// Turn one symbol a face, clockwise if true
+    Tap(Vector2i, bool),
+    /// Exchange a symbol with the hold slot
+    Hold(Vector2i),
+    /// Turn the whole grid a quarter turn, clockwise if true
+    Spin(bool),
+    /// Go back to the board as it was this many moves ago
+    Rewind(usize),
+    /// Clear a whole row at the level's end; `step` counts the rows swept before it
+    Sweep { row: i32, step: i32 },
+    /// Apply the next batch of scripted board edits
+    Edit,
+}
+
+/// FIFO of pending commands plus the one waiting on an animation
+#[derive(Debug, Default)]
+pub struct CommandQueue {
+    pending: VecDeque<BoardCommand>,
+    active: Option<BoardCommand>,
+}
+
+impl CommandQueue {
+    /// Add a command to the end of the queue
+    pub fn push(&mut self, command: BoardCommand) {
+        self.pending.push_back(command);
+    }
+
+    /// Take the next command, unless one is still waiting on its animation
+    pub fn next(&mut self) -> Option<BoardCommand> {
+        if self.active.is_some() {
+            return None;
+        }
+        self.pending.pop_front()
+    }
+
+    /// Mark a command as waiting for its animation to complete
+    pub fn wait_for(&mut self, command: BoardCommand) {
+        self.active = Some(command);
+    }
+
+    /// Check if a command is waiting for its animation
+    pub fn is_waiting(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// Finish the waiting command, returning it
+    pub fn complete(&mut self) -> Option<BoardCommand> {
+        self.active.take()
+    }
+
+    /// Drop all pending and waiting commands
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.active = None;
+    }
+}
@@ -0,0 +1,95 @@
+//! Control variant of the board - a `GameBoard` hosted inside Godot's UI layout
+//!
+//! `GameBoardControl` owns a `GameBoard` child and keeps the board's rect in
+//! step with its own size, so anchors, containers and size flags place the
+//! board like any other control. All game logic stays in the hosted board;
+//! scripts reach it through `get_board`.
+
+use crate::board::GameBoard;
+use godot::classes::control::FocusMode;
+use godot::classes::notify::ControlNotification;
+use godot::classes::{Control, IControl};
+use godot::prelude::*;
+
+/// A match-3 board that lays out like a Control
+#[derive(GodotClass)]
+#[class(tool, base=Control)]
+pub struct GameBoardControl {
+    base: Base<Control>,
+
+    /// Smallest cell size the control asks its container for
+    #[export(range = (8.0, 128.0, 1.0, or_greater))]
+    min_cell_size: f32,
+
+    /// The hosted board
+    board: Option<Gd<GameBoard>>,
+}
+
+#[godot_api]
+impl IControl for GameBoardControl {
+    fn init(base: Base<Control>) -> Self {
+        Self {
+            base,
+            min_cell_size: 32.0,
+            board: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        // Host a board placed under the control in the editor, or a new one
+        let existing = self.base().get_children().iter_shared().find_map(|child| child.try_cast::<GameBoard>().ok());
+        let board = match existing {
+            Some(board) => board,
+            None => {
+                let mut board = GameBoard::new_alloc();
+                board.set_name("Board");
+                self.base_mut().add_child(&board);
+                board
+            }
+        };
+        self.board = Some(board);
+
+        self.base_mut().set_focus_mode(FocusMode::ALL);
+        self.fit_board();
+        self.base_mut().update_minimum_size();
+    }
+
+    fn on_notification(&mut self, what: ControlNotification) {
+        if what == ControlNotification::RESIZED {
+            self.fit_board();
+        }
+    }
+
+    fn get_minimum_size(&self) -> Vector2 {
+        let Some(board) = &self.board else {
+            return Vector2::ZERO;
+        };
+        let width = board.get("grid_width").try_to::<i32>().unwrap_or(0).max(0) as f32;
+        let height = board.get("grid_height").try_to::<i32>().unwrap_or(0).max(0) as f32;
+        let margin = board.get("board_margin").try_to::<f32>().unwrap_or(0.0) * 2.0;
+        Vector2::new(width * self.min_cell_size + margin, height * self.min_cell_size + margin)
+    }
+}
+
+#[godot_api]
+impl GameBoardControl {
+    /// Get the hosted board, for its signals and game functions
+    #[func]
+    fn get_board(&self) -> Option<Gd<GameBoard>> {
+        self.board.clone()
+    }
+
+    /// Ask the container for space again, e.g. after the board's grid size changed
+    #[func]
+    fn refresh_minimum_size(&mut self) {
+        self.base_mut().update_minimum_size();
+    }
+
+    /// Place the board in the control's current rect
+    fn fit_board(&mut self) {
+        let rect = Rect2::new(Vector2::ZERO, self.base().get_size());
+        if let Some(board) = self.board.as_mut() {
+            board.set("board_rect", &rect.to_variant());
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Diagnostics - panic capture and the recent input log sent with crash reports
+//!
+//! Board entry points run their logic through `guard`, which turns a panic
+//! into an error message instead of unwinding into the engine. Builds that
+//! abort on panic (such as wasm) cannot catch it and still go down.
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Input entries kept before the oldest is dropped
+pub const MAX_ENTRIES: usize = 32;
+
+/// The most recent player inputs, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct InputLog {
+    entries: VecDeque<String>,
+}
+
+impl InputLog {
+    /// Remember an input
+    pub fn record(&mut self, entry: String) {
+        if self.entries.len() == MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Get the number of remembered inputs
+    pub fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Iterate over the remembered inputs, oldest first
+    pub fn entries(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(String::as_str)
+    }
+}
+
+/// Run `f`, returning the panic message instead if it panics
+/// Whatever `f` was mutating may be left half-updated and must be reset by the caller
+pub fn guard<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(payload.as_ref()))
+}
+
+/// Get the message a panic was raised with
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
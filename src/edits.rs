@@ -0,0 +1,163 @@
+//! Board edits - scripted changes to the board, applied as one batch
+//!
+//! Story scripts describe edits as dictionaries:
+//!
+//! ```text
+//! { "op": "set", "pos": Vector2i(2, 3), "symbol_type": 1 }
+//! { "op": "special", "pos": Vector2i(2, 3), "kind": "striped_row" }
+//! { "op": "obstacle", "pos": Vector2i(2, 3), "kind": "stone", "strength": 2 }
+//! { "op": "clear", "rect": Rect2i(0, 0, 3, 2) }
+//! ```
+//!
+//! The board queues a parsed batch and applies it between moves. Either every
+//! edit in a batch lands or none does; afterwards the board settles through
+//! the usual fall, refill and clear steps, so anything the edit lined up
+//! matches as if the player had made it.
+
+use crate::symbols::{CellOverlay, SymbolKind, SymbolType};
+use godot::prelude::*;
+
+/// A special a scripted edit can give a symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Special {
+    Seeker,
+    Wild,
+    Kind(SymbolKind),
+}
+
+impl Special {
+    /// Names accepted for `kind`
+    pub const NAMES: [&'static str; 6] =
+        ["seeker", "wild", "striped_row", "striped_column", "color_bomb", "ingredient"];
+
+    /// Look a special up by name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "seeker" => Some(Special::Seeker),
+            "wild" => Some(Special::Wild),
+            _ => [SymbolKind::StripedRow, SymbolKind::StripedColumn, SymbolKind::ColorBomb, SymbolKind::Ingredient]
+                .into_iter()
+                .find(|kind| kind.name() == name)
+                .map(Special::Kind),
+        }
+    }
+}
+
+/// Names accepted for an obstacle's `kind`; "none" takes the cell's obstacle off
+pub const OBSTACLES: [&str; 4] = ["stone", "ice", "chocolate", "none"];
+
+/// Look an obstacle up by name, with the hit points or ice layers it starts with
+pub fn parse_obstacle(name: &str, strength: u32) -> Result<Option<CellOverlay>, String> {
+    match name {
+        "stone" => Ok(Some(CellOverlay::Stone(strength))),
+        "ice" => Ok(Some(CellOverlay::Ice(strength))),
+        "chocolate" => Ok(Some(CellOverlay::Chocolate)),
+        "none" => Ok(None),
+        _ => Err(format!("unknown obstacle: {} (available: {})", name, OBSTACLES.join(", "))),
+    }
+}
+
+/// Parse a symbol type given as an index
+pub fn parse_symbol_type(index: i32) -> Result<SymbolType, String> {
+    usize::try_from(index)
+        .ok()
+        .filter(|index| *index < SymbolType::ALL.len())
+        .map(SymbolType::from_index)
+        .ok_or(format!("unknown symbol_type: {}", index))
+}
+
+/// One change to the board
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoardEdit {
+    /// Put a new plain symbol of a type in a cell
+    Set { pos: Vector2i, symbol_type: SymbolType },
+    /// Turn the symbol in a cell into a special
+    Special { pos: Vector2i, special: Special },
+    /// Put an obstacle on a cell, or take it off with None; a stone or chocolate removes the cell's symbol
+    Obstacle { pos: Vector2i, overlay: Option<CellOverlay> },
+    /// Remove the symbols in these cells, without scoring them
+    Clear(Vec<Vector2i>),
+}
+
+impl BoardEdit {
+    /// Parse an edit from its dictionary form
+    pub fn from_dictionary(dict: &Dictionary) -> Result<Self, String> {
+        let op = dict.get("op").ok_or("missing op")?;
+        let op = op.try_to::<GString>().map_err(|_| "op must be a string")?.to_string();
+        match op.as_str() {
+            "set" => {
+                let index = dict.get("symbol_type").ok_or("missing symbol_type")?;
+                let index = index.try_to::<i32>().map_err(|_| "symbol_type must be an int")?;
+                Ok(BoardEdit::Set {
+                    pos: parse_pos(dict)?,
+                    symbol_type: parse_symbol_type(index)?,
+                })
+            }
+            "special" => {
+                let kind = dict.get("kind").ok_or("missing kind")?;
+                let kind = kind.try_to::<GString>().map_err(|_| "kind must be a string")?.to_string();
+                let special = Special::from_name(&kind)
+                    .ok_or(format!("unknown special: {} (available: {})", kind, Special::NAMES.join(", ")))?;
+                Ok(BoardEdit::Special {
+                    pos: parse_pos(dict)?,
+                    special,
+                })
+            }
+            "obstacle" => {
+                let kind = dict.get("kind").ok_or("missing kind")?;
+                let kind = kind.try_to::<GString>().map_err(|_| "kind must be a string")?.to_string();
+                Ok(BoardEdit::Obstacle {
+                    pos: parse_pos(dict)?,
+                    overlay: parse_obstacle(&kind, parse_strength(dict)?)?,
+                })
+            }
+            "clear" => {
+                let rect = dict.get("rect").ok_or("missing rect")?;
+                let rect = rect.try_to::<Rect2i>().map_err(|_| "rect must be a Rect2i")?;
+                let end = rect.end();
+                let cells = (rect.position.y..end.y)
+                    .flat_map(|y| (rect.position.x..end.x).map(move |x| Vector2i::new(x, y)))
+                    .collect();
+                Ok(BoardEdit::Clear(cells))
+            }
+            _ => Err(format!("unknown op: {} (available: set, special, obstacle, clear)", op)),
+        }
+    }
+
+    /// Parse a batch of edits, failing on the first bad one
+    pub fn from_dictionaries(edits: &Array<Dictionary>) -> Result<Vec<Self>, String> {
+        edits
+            .iter_shared()
+            .enumerate()
+            .map(|(i, dict)| Self::from_dictionary(&dict).map_err(|err| format!("edit {}: {}", i, err)))
+            .collect()
+    }
+
+    /// Get the cells the edit changes
+    pub fn cells(&self) -> &[Vector2i] {
+        match self {
+            BoardEdit::Set { pos, .. } | BoardEdit::Special { pos, .. } | BoardEdit::Obstacle { pos, .. } => {
+                std::slice::from_ref(pos)
+            }
+            BoardEdit::Clear(cells) => cells,
+        }
+    }
+}
+
+/// Read the `pos` of an edit
+fn parse_pos(dict: &Dictionary) -> Result<Vector2i, String> {
+    let pos = dict.get("pos").ok_or("missing pos")?;
+    pos.try_to::<Vector2i>().map_err(|_| "pos must be a Vector2i".to_string())
+}
+
+/// Read the optional `strength` of an obstacle: a stone's hit points or ice layers, 1 if left out
+pub fn parse_strength(dict: &Dictionary) -> Result<u32, String> {
+    let Some(strength) = dict.get("strength") else {
+        return Ok(1);
+    };
+    let strength = strength.try_to::<i32>().map_err(|_| "strength must be an int")?;
+    u32::try_from(strength)
+        .ok()
+        .filter(|strength| *strength > 0)
+        .ok_or(format!("strength must be positive: {}", strength))
+}
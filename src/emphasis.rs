@@ -0,0 +1,109 @@
+//! Emphasis - one per-cell channel for drawing attention to cells
+//!
+//! Selections, hints, tutorial spotlights, objectives, specials and cell locks
+//! all want some cells to stand out and others to recede. Rather than each
+//! reaching for its own node property, every source sets an emphasis from -1
+//! (fully dimmed) to 1 (fully lit) per cell. The view draws the blend of all
+//! sources: positive emphasis brightens and outlines a cell's symbol, negative
+//! emphasis dims it.
+
+use godot::prelude::*;
+use std::collections::HashMap;
+
+/// Emphasis of a selected cell
+pub const SELECTED: f32 = 1.0;
+
+/// Emphasis of a dimmed cell, outside a tutorial's spotlight or locked
+pub const DIMMED: f32 = -1.0;
+
+/// What sets an emphasis; each source keeps its own values, so clearing one leaves the others showing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmphasisSource {
+    /// The selected cell and the dragged link path
+    Selection,
+    /// The two cells of the last hint
+    Hint,
+    /// Cells outside the current tutorial step's spotlight
+    Tutorial,
+    /// Symbols of the objective color
+    Objective,
+    /// Seekers, wilds, striped symbols and color bombs
+    Special,
+    /// Cells locked by lock_cells
+    Lock,
+    /// Cells emphasized by the host game's scripts
+    Script,
+}
+
+impl EmphasisSource {
+    /// Every source
+    pub const ALL: [EmphasisSource; 7] = [
+        EmphasisSource::Selection,
+        EmphasisSource::Hint,
+        EmphasisSource::Tutorial,
+        EmphasisSource::Objective,
+        EmphasisSource::Special,
+        EmphasisSource::Lock,
+        EmphasisSource::Script,
+    ];
+}
+
+/// The emphasis a source gives cells
+#[derive(Debug, Clone, Default)]
+struct Layer {
+    /// Emphasis of the cells not set individually
+    rest: f32,
+    cells: HashMap<Vector2i, f32>,
+}
+
+/// Emphasis per cell from every source
+#[derive(Debug, Clone, Default)]
+pub struct EmphasisMap {
+    layers: [Layer; EmphasisSource::ALL.len()],
+}
+
+impl EmphasisMap {
+    /// Set a source's emphasis for one cell, clamped to -1..=1
+    pub fn set(&mut self, source: EmphasisSource, pos: Vector2i, emphasis: f32) {
+        let layer = &mut self.layers[source as usize];
+        let emphasis = emphasis.clamp(-1.0, 1.0);
+        if emphasis == layer.rest {
+            layer.cells.remove(&pos);
+        } else {
+            layer.cells.insert(pos, emphasis);
+        }
+    }
+
+    /// Set a source's emphasis for every cell, clamped to -1..=1
+    pub fn fill(&mut self, source: EmphasisSource, emphasis: f32) {
+        let layer = &mut self.layers[source as usize];
+        layer.rest = emphasis.clamp(-1.0, 1.0);
+        layer.cells.clear();
+    }
+
+    /// Take a source's emphasis off every cell
+    pub fn clear(&mut self, source: EmphasisSource) {
+        self.fill(source, 0.0);
+    }
+
+    /// Check if a source gives no cell any emphasis
+    pub fn is_clear(&self, source: EmphasisSource) -> bool {
+        let layer = &self.layers[source as usize];
+        layer.rest == 0.0 && layer.cells.is_empty()
+    }
+
+    /// Get the emphasis a source gives a cell
+    pub fn get(&self, source: EmphasisSource, pos: Vector2i) -> f32 {
+        let layer = &self.layers[source as usize];
+        layer.cells.get(&pos).copied().unwrap_or(layer.rest)
+    }
+
+    /// Get a cell's emphasis over every source: the strongest lift plus the strongest dimming,
+    /// so a lit cell outside a spotlight still shows dimmed, but less so
+    pub fn blended(&self, pos: Vector2i) -> f32 {
+        let values = EmphasisSource::ALL.map(|source| self.get(source, pos));
+        let lift = values.iter().copied().fold(0.0, f32::max);
+        let dim = values.iter().copied().fold(0.0, f32::min);
+        (lift + dim).clamp(-1.0, 1.0)
+    }
+}
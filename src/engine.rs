@@ -0,0 +1,130 @@
+//! Game engine - whole moves played on a board model without a running Godot instance
+//!
+//! `BoardModel` splits a move into steps (the move, then clear, fall and refill
+//! until nothing matches) so `GameBoard` can animate between them through its
+//! command queue. `GameEngine` runs the same steps back to back, so cascades
+//! can be played, checked and simulated outside the editor: replays play back
+//! through it, and tests or bots can drive it directly. Nothing here touches
+//! nodes, tweens or the engine's print functions.
+
+use crate::error::BoardError;
+use crate::model::{BoardModel, ModelEvent};
+use crate::replay::ReplayMove;
+use crate::roulette::RouletteEffect;
+use crate::symbols::Grid;
+
+/// A board model played a whole move at a time
+#[derive(Debug, Clone)]
+pub struct GameEngine {
+    model: BoardModel,
+    max_cascade_depth: i32,
+}
+
+impl GameEngine {
+    /// Play on a model, stopping any cascade after `max_cascade_depth` resolutions as the board does
+    pub fn new(model: BoardModel, max_cascade_depth: i32) -> Self {
+        Self {
+            model,
+            max_cascade_depth,
+        }
+    }
+
+    /// Get the model
+    pub fn model(&self) -> &BoardModel {
+        &self.model
+    }
+
+    /// Get the model to change settings or set up a board
+    pub fn model_mut(&mut self) -> &mut BoardModel {
+        &mut self.model
+    }
+
+    /// Take the model back
+    pub fn into_model(self) -> BoardModel {
+        self.model
+    }
+
+    /// Make a move and settle the cascade it sets off, returning the model's events
+    /// `visit` sees the grid after each step that changed it: the move itself, then each
+    /// clear, fall and refill. A rejected move changes nothing
+    pub fn play(&mut self, mv: &ReplayMove, mut visit: impl FnMut(&Grid)) -> Result<Vec<ModelEvent>, BoardError> {
+        match mv {
+            ReplayMove::Swap { from, to, mirrored } => {
+                self.model.swap(*from, *to)?;
+                if *mirrored {
+                    let _ = self.model.swap_mirrored(*from, *to);
+                }
+                visit(self.model.grid());
+            }
+            ReplayMove::Tap { pos, clockwise } => {
+                self.model.rotate_cell(*pos, *clockwise)?;
+                visit(self.model.grid());
+            }
+            ReplayMove::Rotate(effect) => {
+                match effect {
+                    RouletteEffect::RotateFaces => self.model.rotate_all(),
+                    RouletteEffect::ShuffleRow(row) => self.model.shuffle_row(*row),
+                    RouletteEffect::FreezeColumn(_) => {}
+                    RouletteEffect::SpawnSpecial(pos, kind) => self.model.set_kind(*pos, *kind)?,
+                }
+                self.model.age_symbols();
+                visit(self.model.grid());
+            }
+            ReplayMove::Link(cells) => {
+                self.model.clear_link(cells);
+                visit(self.model.grid());
+                self.drop_and_refill(&mut visit);
+            }
+            ReplayMove::Hold(pos) => {
+                self.model.exchange_held(*pos)?;
+                visit(self.model.grid());
+                self.drop_and_refill(&mut visit);
+            }
+            ReplayMove::Spin { clockwise } => {
+                self.model.spin(*clockwise);
+                visit(self.model.grid());
+                self.drop_and_refill(&mut visit);
+            }
+            ReplayMove::Edit(edits) => {
+                self.model.apply_edits(edits)?;
+                visit(self.model.grid());
+                self.drop_and_refill(&mut visit);
+            }
+        }
+
+        self.settle(&mut visit);
+        let mut events = self.model.drain_events();
+        // As on the board, chocolate grows after a turn that ate none
+        let eaten = events.iter().any(|event| matches!(event, ModelEvent::ChocolateEaten(_)));
+        if mv.is_turn() && !eaten && self.model.spread_chocolate().is_some() {
+            visit(self.model.grid());
+            events.extend(self.model.drain_events());
+        }
+        Ok(events)
+    }
+
+    /// Resolve matches and let ingredients out until neither is left or the cascade cap is reached,
+    /// then end the cascade
+    fn settle(&mut self, visit: &mut impl FnMut(&Grid)) {
+        while self.model.cascade_depth() < self.max_cascade_depth
+            && (self.model.has_matches() || self.model.has_exiting_ingredients())
+        {
+            if self.model.has_matches() {
+                self.model.resolve_matches();
+                visit(self.model.grid());
+            }
+            self.drop_and_refill(visit);
+        }
+        self.model.end_cascade();
+    }
+
+    /// Let gravity settle the grid and refill it, visiting after each step that changed it
+    fn drop_and_refill(&mut self, visit: &mut impl FnMut(&Grid)) {
+        if !self.model.apply_gravity().is_empty() {
+            visit(self.model.grid());
+        }
+        if !self.model.refill().is_empty() {
+            visit(self.model.grid());
+        }
+    }
+}
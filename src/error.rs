@@ -0,0 +1,105 @@
+//! Errors raised by board operations
+
+use godot::prelude::*;
+use std::fmt;
+
+/// A board operation that could not be carried out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardError {
+    /// Position is outside the grid
+    OutOfBounds(Vector2i),
+    /// Position has no symbol
+    EmptyCell(Vector2i),
+    /// Ice holds the symbol at a position in place
+    CellFrozen(Vector2i),
+    /// A stone or chocolate fills the cell, so it can't hold a symbol
+    CellBlocked(Vector2i),
+    /// The cell is a hole in the board
+    CellDisabled(Vector2i),
+    /// The view's node at a position does not reflect the grid
+    ViewMismatch(Vector2i),
+    /// An animation completed with no command waiting for it
+    NoActiveCommand,
+}
+
+impl BoardError {
+    /// Get a stable code for scripts to match on
+    pub fn code(&self) -> &'static str {
+        match self {
+            BoardError::OutOfBounds(_) => "out_of_bounds",
+            BoardError::EmptyCell(_) => "empty_cell",
+            BoardError::CellFrozen(_) => "cell_frozen",
+            BoardError::CellBlocked(_) => "cell_blocked",
+            BoardError::CellDisabled(_) => "cell_disabled",
+            BoardError::ViewMismatch(_) => "view_mismatch",
+            BoardError::NoActiveCommand => "no_active_command",
+        }
+    }
+
+    /// Get the cell the error is about, if any
+    pub fn position(&self) -> Option<Vector2i> {
+        match self {
+            BoardError::OutOfBounds(pos)
+            | BoardError::EmptyCell(pos)
+            | BoardError::CellFrozen(pos)
+            | BoardError::CellBlocked(pos)
+            | BoardError::CellDisabled(pos)
+            | BoardError::ViewMismatch(pos) => Some(*pos),
+            BoardError::NoActiveCommand => None,
+        }
+    }
+}
+
+/// Why a requested swap was not made
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapRejection {
+    /// The cells are not neighbors
+    NotAdjacent,
+    /// The swap would not create a match
+    NoMatch,
+    /// A cell is locked by the input mask or tutorial
+    CellLocked(Vector2i),
+    /// The board is animating, paused or otherwise not taking moves
+    BoardBusy,
+    /// The active ruleset has no swapping
+    SwapsDisabled,
+}
+
+impl SwapRejection {
+    /// Get a stable code for scripts to match on
+    pub fn code(&self) -> &'static str {
+        match self {
+            SwapRejection::NotAdjacent => "not_adjacent",
+            SwapRejection::NoMatch => "no_match",
+            SwapRejection::CellLocked(_) => "cell_locked",
+            SwapRejection::BoardBusy => "board_busy",
+            SwapRejection::SwapsDisabled => "swaps_disabled",
+        }
+    }
+}
+
+impl fmt::Display for SwapRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwapRejection::NotAdjacent => write!(f, "cells are not adjacent"),
+            SwapRejection::NoMatch => write!(f, "no match would be created"),
+            SwapRejection::CellLocked(pos) => write!(f, "cell {:?} is locked", pos),
+            SwapRejection::BoardBusy => write!(f, "board is busy"),
+            SwapRejection::SwapsDisabled => write!(f, "the ruleset does not allow swaps"),
+        }
+    }
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardError::OutOfBounds(pos) => write!(f, "position out of bounds: {:?}", pos),
+            BoardError::EmptyCell(pos) => write!(f, "no symbol at {:?}", pos),
+            BoardError::CellFrozen(pos) => write!(f, "symbol at {:?} is frozen", pos),
+            BoardError::CellBlocked(pos) => write!(f, "{:?} is filled and has no room for a symbol", pos),
+            BoardError::CellDisabled(pos) => write!(f, "{:?} is a hole in the board", pos),
+            BoardError::ViewMismatch(pos) => write!(f, "view out of sync with grid at {:?}", pos),
+            BoardError::NoActiveCommand => write!(f, "animation completed with no active command"),
+        }
+    }
+}
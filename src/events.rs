@@ -0,0 +1,53 @@
+//! Event emission helpers - rate limiting of high-frequency signals
+
+use std::collections::HashMap;
+
+/// Per-event rate limits for signals emitted by the board
+#[derive(Debug, Clone, Default)]
+pub struct EventThrottle {
+    /// Minimum seconds between two emissions, per event name
+    min_intervals: HashMap<String, f64>,
+    /// Clock time of the last emission, per event name
+    last_emitted: HashMap<String, f64>,
+}
+
+impl EventThrottle {
+    /// Limit an event to `max_per_second` emissions (zero or less removes the limit)
+    pub fn set_rate_limit(&mut self, event: &str, max_per_second: f64) {
+        if max_per_second > 0.0 {
+            self.min_intervals.insert(event.to_string(), 1.0 / max_per_second);
+        } else {
+            self.min_intervals.remove(event);
+        }
+        self.last_emitted.remove(event);
+    }
+
+    /// Remove all rate limits
+    pub fn clear(&mut self) {
+        self.min_intervals.clear();
+        self.last_emitted.clear();
+    }
+
+    /// Iterate over the configured limits as (event, max_per_second)
+    pub fn rate_limits(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.min_intervals
+            .iter()
+            .map(|(event, interval)| (event.as_str(), 1.0 / interval))
+    }
+
+    /// Check whether an event may be emitted at clock time `now`, recording it if so
+    pub fn allow(&mut self, event: &str, now: f64) -> bool {
+        let Some(&interval) = self.min_intervals.get(event) else {
+            return true;
+        };
+
+        if let Some(&last) = self.last_emitted.get(event) {
+            if now - last < interval {
+                return false;
+            }
+        }
+
+        self.last_emitted.insert(event.to_string(), now);
+        true
+    }
+}
@@ -0,0 +1,85 @@
+//! Piece expiry - symbols left unmatched for too many rotations turn into blockers
+//!
+//! Every rotation ages each symbol on the board. A symbol that reaches the
+//! level's expiry age becomes a blocker, which stays in place until a match
+//! next to it breaks it. Symbols darken as they age.
+
+use crate::modifiers::{Modifier, ModifierContext};
+use crate::roulette::RouletteEffect;
+use crate::symbols::{Grid, Symbol};
+use godot::prelude::*;
+
+/// Tint of a symbol about to expire
+const FADED: Color = Color::from_rgba(0.4, 0.4, 0.4, 1.0);
+
+/// Age every symbol by one rotation, turning those that reach `expiry` into blockers
+/// Returns the cells that expired
+pub fn age(grid: &mut Grid, expiry: u32) -> Vec<Vector2i> {
+    let mut expired = Vec::new();
+    for symbol in grid.cells.iter_mut().flatten() {
+        if symbol.blocker {
+            continue;
+        }
+        symbol.age += 1;
+        if symbol.age >= expiry {
+            symbol.blocker = true;
+            expired.push(symbol.grid_pos);
+        }
+    }
+    expired
+}
+
+/// Get the blockers next to any of the given cells, which a match there breaks
+pub fn adjacent_blockers(grid: &Grid, cells: &[Vector2i]) -> Vec<Vector2i> {
+    let mut blockers = Vec::new();
+    for pos in cells {
+        for offset in [Vector2i::new(1, 0), Vector2i::new(-1, 0), Vector2i::new(0, 1), Vector2i::new(0, -1)] {
+            let neighbor = *pos + offset;
+            let is_blocker = grid.is_valid(neighbor.x, neighbor.y)
+                && grid.get(neighbor.x as usize, neighbor.y as usize).is_some_and(|symbol| symbol.blocker);
+            if is_blocker && !cells.contains(&neighbor) && !blockers.contains(&neighbor) {
+                blockers.push(neighbor);
+            }
+        }
+    }
+    blockers
+}
+
+/// Get the tint for a symbol's age, applied on top of its color (0 expiry disables it)
+pub fn tint(symbol: &Symbol, expiry: u32) -> Color {
+    if symbol.blocker || expiry == 0 {
+        return Color::WHITE;
+    }
+    Color::WHITE.lerp(FADED, symbol.age.min(expiry) as f64 / expiry as f64)
+}
+
+/// Piece expiry as a level modifier: every rotation ages the board
+#[derive(Debug, Clone, Copy)]
+pub struct ExpiryModifier {
+    rotations: u32,
+}
+
+impl ExpiryModifier {
+    /// Create the modifier with the rotations a symbol survives unmatched (0 for never)
+    pub fn new(rotations: u32) -> Self {
+        Self { rotations }
+    }
+}
+
+impl Modifier for ExpiryModifier {
+    fn name(&self) -> &'static str {
+        "expiry"
+    }
+
+    fn on_attach(&mut self, ctx: &mut ModifierContext) {
+        ctx.model.set_expiry(self.rotations);
+    }
+
+    fn on_detach(&mut self, ctx: &mut ModifierContext) {
+        ctx.model.set_expiry(0);
+    }
+
+    fn on_rotation(&mut self, ctx: &mut ModifierContext, _effect: RouletteEffect) {
+        ctx.model.age_symbols();
+    }
+}
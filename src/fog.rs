@@ -0,0 +1,130 @@
+//! Fog - a level modifier hiding the board away from the player's recent activity
+//!
+//! Under fog a cell only shows its symbol while it is revealed. Each move
+//! reveals the cells within `fog_radius` of the cells it touched, and each
+//! resolution reveals around the cells it cleared, so the fog recedes from
+//! wherever matches are being made. A revealed cell fogs over again after
+//! `fog_moves` more moves unless activity nearby reveals it anew.
+
+use crate::commands::BoardCommand;
+use crate::modifiers::{Modifier, ModifierContext, ModifierEvent};
+use crate::symbols::Grid;
+use godot::prelude::*;
+use std::collections::HashMap;
+
+/// The cells the fog has cleared from, and for how many more moves
+#[derive(Debug, Clone, Default)]
+pub struct Visibility {
+    revealed: HashMap<Vector2i, u32>,
+}
+
+impl Visibility {
+    /// Check if a cell is revealed
+    pub fn is_revealed(&self, pos: Vector2i) -> bool {
+        self.revealed.contains_key(&pos)
+    }
+
+    /// Reveal the cells of `grid` within `radius` of any of `around` for `moves` moves
+    /// Returns the cells that were fogged, row by row
+    pub fn reveal(&mut self, grid: &Grid, around: &[Vector2i], radius: i32, moves: u32) -> Vec<Vector2i> {
+        let mut revealed = Vec::new();
+        for center in around {
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    let pos = *center + Vector2i::new(dx, dy);
+                    if grid.is_valid(pos.x, pos.y) && self.revealed.insert(pos, moves.max(1)).is_none() {
+                        revealed.push(pos);
+                    }
+                }
+            }
+        }
+        revealed.sort_by_key(|pos| (pos.y, pos.x));
+        revealed
+    }
+
+    /// Count a move off every revealed cell, returning those that fog over again, row by row
+    pub fn count_move(&mut self) -> Vec<Vector2i> {
+        let mut fogged = Vec::new();
+        self.revealed.retain(|pos, moves| {
+            *moves -= 1;
+            if *moves == 0 {
+                fogged.push(*pos);
+            }
+            *moves > 0
+        });
+        fogged.sort_by_key(|pos| (pos.y, pos.x));
+        fogged
+    }
+
+    /// Fog over every cell, returning those that were revealed, row by row
+    pub fn clear(&mut self) -> Vec<Vector2i> {
+        let mut fogged: Vec<Vector2i> = self.revealed.drain().map(|(pos, _)| pos).collect();
+        fogged.sort_by_key(|pos| (pos.y, pos.x));
+        fogged
+    }
+}
+
+/// Fog of war as a level modifier, sized by the board's fog settings
+#[derive(Debug, Clone, Default)]
+pub struct FogModifier {
+    visibility: Visibility,
+}
+
+impl FogModifier {
+    /// Reveal around some cells, reporting each cell the fog left
+    fn reveal(&mut self, ctx: &mut ModifierContext, around: &[Vector2i]) {
+        let (radius, moves) = (ctx.tuning.fog_radius.max(0), ctx.tuning.fog_moves.max(1) as u32);
+        for pos in self.visibility.reveal(ctx.model.grid(), around, radius, moves) {
+            ctx.emit(ModifierEvent::CellRevealed(pos));
+        }
+    }
+}
+
+impl Modifier for FogModifier {
+    fn name(&self) -> &'static str {
+        "fog"
+    }
+
+    fn on_attach(&mut self, ctx: &mut ModifierContext) {
+        self.visibility.clear();
+        let grid = ctx.model.grid();
+        let cells: Vec<Vector2i> = (0..grid.height)
+            .flat_map(|y| (0..grid.width).map(move |x| Vector2i::new(x as i32, y as i32)))
+            .collect();
+        for pos in cells {
+            ctx.emit(ModifierEvent::CellFogged(pos));
+        }
+    }
+
+    fn on_detach(&mut self, ctx: &mut ModifierContext) {
+        let grid = ctx.model.grid();
+        let fogged: Vec<Vector2i> = (0..grid.height)
+            .flat_map(|y| (0..grid.width).map(move |x| Vector2i::new(x as i32, y as i32)))
+            .filter(|pos| !self.visibility.is_revealed(*pos))
+            .collect();
+        for pos in fogged {
+            ctx.emit(ModifierEvent::CellRevealed(pos));
+        }
+        self.visibility.clear();
+    }
+
+    fn on_turn_start(&mut self, ctx: &mut ModifierContext, command: BoardCommand) {
+        for pos in self.visibility.count_move() {
+            ctx.emit(ModifierEvent::CellFogged(pos));
+        }
+        let touched = match command {
+            BoardCommand::Swap(pos1, pos2) => vec![pos1, pos2],
+            BoardCommand::Tap(pos, _) | BoardCommand::Hold(pos) => vec![pos],
+            _ => Vec::new(),
+        };
+        self.reveal(ctx, &touched);
+    }
+
+    fn on_match(&mut self, ctx: &mut ModifierContext, cleared: &[Vector2i]) {
+        self.reveal(ctx, cleared);
+    }
+
+    fn hides(&self, pos: Vector2i) -> bool {
+        !self.visibility.is_revealed(pos)
+    }
+}
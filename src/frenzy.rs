@@ -0,0 +1,141 @@
+//! Frenzy windows - periodic bursts of multiplied scoring
+//!
+//! Each cycle is `interval` seconds of normal play followed by `duration`
+//! seconds of frenzy. The upcoming frenzy is announced `warning` seconds
+//! before it starts. Timing runs on game time, so it is the same every run.
+
+use crate::modifiers::{Modifier, ModifierContext, ModifierEvent};
+
+/// Chance that a refilled symbol is the favored type during a frenzy
+pub const FAVOR_CHANCE: f64 = 0.4;
+
+/// A change in the frenzy cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrenzyEvent {
+    /// A frenzy starts soon
+    Upcoming,
+    /// A frenzy started
+    Started,
+    /// The frenzy ended
+    Ended,
+}
+
+/// Position within the frenzy cycle
+#[derive(Debug, Clone, Default)]
+pub struct FrenzySchedule {
+    elapsed: f64,
+    warned: bool,
+    active: bool,
+}
+
+impl FrenzySchedule {
+    /// Advance by `delta`, returning the change that happened, if any
+    pub fn advance(&mut self, delta: f64, interval: f64, duration: f64, warning: f64) -> Option<FrenzyEvent> {
+        if interval <= 0.0 || duration <= 0.0 {
+            return self.active.then(|| {
+                self.reset();
+                FrenzyEvent::Ended
+            });
+        }
+
+        self.elapsed += delta;
+
+        if self.active {
+            if self.elapsed < interval + duration {
+                return None;
+            }
+            self.elapsed = (self.elapsed - interval - duration) % (interval + duration);
+            self.active = false;
+            self.warned = false;
+            return Some(FrenzyEvent::Ended);
+        }
+
+        if self.elapsed >= interval {
+            self.active = true;
+            return Some(FrenzyEvent::Started);
+        }
+        if !self.warned && self.elapsed >= interval - warning {
+            self.warned = true;
+            return Some(FrenzyEvent::Upcoming);
+        }
+        None
+    }
+
+    /// Get the seconds until the next frenzy starts, or 0 during one
+    pub fn time_until(&self, interval: f64) -> f64 {
+        if self.active {
+            0.0
+        } else {
+            (interval - self.elapsed).max(0.0)
+        }
+    }
+
+    /// Check if a frenzy is running
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Start the cycle over, outside a frenzy
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// The frenzy cycle as a level modifier, timed and tuned by the board's frenzy settings
+#[derive(Debug, Clone, Default)]
+pub struct FrenzyModifier {
+    schedule: FrenzySchedule,
+}
+
+impl FrenzyModifier {
+    /// Put the model back to normal scoring and announce the end
+    fn end(&mut self, ctx: &mut ModifierContext) {
+        ctx.model.set_score_multiplier(1);
+        ctx.model.set_favored_symbol(None);
+        ctx.emit(ModifierEvent::FrenzyEnded);
+    }
+}
+
+impl Modifier for FrenzyModifier {
+    fn name(&self) -> &'static str {
+        "frenzy"
+    }
+
+    fn on_detach(&mut self, ctx: &mut ModifierContext) {
+        if self.schedule.is_active() {
+            self.end(ctx);
+        }
+        self.schedule.reset();
+    }
+
+    fn on_tick(&mut self, ctx: &mut ModifierContext, delta: f64) {
+        let tuning = ctx.tuning;
+        let event = self.schedule.advance(
+            delta,
+            tuning.frenzy_interval,
+            tuning.frenzy_duration,
+            tuning.frenzy_warning,
+        );
+        match event {
+            Some(FrenzyEvent::Upcoming) => {
+                let seconds = self.schedule.time_until(tuning.frenzy_interval);
+                ctx.emit(ModifierEvent::FrenzyUpcoming { seconds });
+            }
+            Some(FrenzyEvent::Started) => {
+                let multiplier = tuning.frenzy_multiplier.max(1);
+                ctx.model.set_score_multiplier(multiplier);
+                ctx.model.set_favored_symbol(tuning.favored.map(|symbol_type| (symbol_type, FAVOR_CHANCE)));
+                ctx.emit(ModifierEvent::FrenzyStarted {
+                    multiplier,
+                    duration: tuning.frenzy_duration,
+                });
+            }
+            Some(FrenzyEvent::Ended) => self.end(ctx),
+            None => {}
+        }
+    }
+
+    fn boosting(&self) -> bool {
+        self.schedule.is_active()
+    }
+}
@@ -0,0 +1,47 @@
+//! Garden - tiles grown by matches that stay on the board from level to level
+//!
+//! In garden mode every cleared symbol plants a tile in its cell. The plot
+//! outlives level loads and is saved between sessions; each level shows the
+//! tiles that fit on its board and keeps the rest for larger boards.
+
+use godot::prelude::*;
+use std::collections::BTreeSet;
+
+/// Color of a garden tile, drawn under the symbols
+pub const TILE_COLOR: Color = Color::from_rgba(0.25, 0.45, 0.2, 1.0);
+
+/// Cells that hold a garden tile
+#[derive(Debug, Clone, Default)]
+pub struct GardenPlot {
+    tiles: BTreeSet<(i32, i32)>,
+}
+
+impl GardenPlot {
+    /// Create a plot from previously planted cells
+    pub fn from_tiles(tiles: impl IntoIterator<Item = Vector2i>) -> Self {
+        Self {
+            tiles: tiles.into_iter().map(|pos| (pos.x, pos.y)).collect(),
+        }
+    }
+
+    /// Plant a tile, returning true if the cell had none
+    pub fn plant(&mut self, pos: Vector2i) -> bool {
+        self.tiles.insert((pos.x, pos.y))
+    }
+
+    /// Check if a cell has a tile
+    pub fn contains(&self, pos: Vector2i) -> bool {
+        self.tiles.contains(&(pos.x, pos.y))
+    }
+
+    /// Iterate over every planted cell
+    pub fn tiles(&self) -> impl Iterator<Item = Vector2i> + '_ {
+        self.tiles.iter().map(|(x, y)| Vector2i::new(*x, *y))
+    }
+
+    /// Iterate over the planted cells that fit on a board of the given size
+    pub fn within(&self, width: usize, height: usize) -> impl Iterator<Item = Vector2i> + '_ {
+        self.tiles()
+            .filter(move |pos| pos.x >= 0 && pos.y >= 0 && (pos.x as usize) < width && (pos.y as usize) < height)
+    }
+}
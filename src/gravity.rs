@@ -0,0 +1,118 @@
+//! Gravity planner - where symbols land after a clear, and the path they take
+//!
+//! Symbols fall one cell per step. A falling symbol above a gravity well and
+//! within its radius slides diagonally down toward the well's column whenever
+//! that cell is free, so fall paths bend toward wells. Without wells every fall
+//! is straight. Stones and frozen symbols never move, and symbols come to rest
+//! on them. Holes don't stop a fall: symbols drop straight past them to the
+//! next playable cell below.
+
+use crate::model::Fall;
+use crate::symbols::Grid;
+use godot::prelude::*;
+
+/// A cell that pulls falling symbols toward its column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GravityWell {
+    pub pos: Vector2i,
+    /// Reach in cells, measured along either axis
+    pub radius: i32,
+}
+
+impl GravityWell {
+    /// Get the distance to a cell if it is within reach and above the well
+    fn reach(&self, pos: Vector2i) -> Option<i32> {
+        let distance = (pos.x - self.pos.x).abs().max((pos.y - self.pos.y).abs());
+        (pos.y < self.pos.y && distance <= self.radius).then_some(distance)
+    }
+}
+
+/// Let every symbol fall as far as it can, returning the falls with their paths
+pub fn settle(grid: &mut Grid, wells: &[GravityWell]) -> Vec<Fall> {
+    // Origin and steps taken so far, indexed by the symbol's current cell
+    let mut paths: Vec<Option<(Vector2i, Vec<Vector2i>)>> = vec![None; grid.width * grid.height];
+
+    // Move every symbol at most one cell per pass, bottom row first, so symbols
+    // in a column fall together and paths reflect when each cell frees up
+    let mut moved = true;
+    while moved {
+        moved = false;
+        for y in (0..grid.height.saturating_sub(1)).rev() {
+            for x in 0..grid.width {
+                let pos = Vector2i::new(x as i32, y as i32);
+                if grid.get(x, y).is_none() || grid.is_frozen(x, y) {
+                    continue;
+                }
+                let Some(next) = next_cell(grid, wells, pos) else {
+                    continue;
+                };
+
+                if let Some(mut symbol) = grid.take(x, y) {
+                    symbol.grid_pos = next;
+                    grid.set(next.x as usize, next.y as usize, Some(symbol));
+                }
+                let (origin, mut steps) = paths[y * grid.width + x].take().unwrap_or((pos, Vec::new()));
+                steps.push(next);
+                paths[next.y as usize * grid.width + next.x as usize] = Some((origin, steps));
+                moved = true;
+            }
+        }
+    }
+
+    paths
+        .into_iter()
+        .flatten()
+        .map(|(from, steps)| Fall {
+            from,
+            to: *steps.last().unwrap_or(&from),
+            bends: bends(from, &steps),
+        })
+        .collect()
+}
+
+/// Pick the cell a symbol moves to next, if any
+fn next_cell(grid: &Grid, wells: &[GravityWell], pos: Vector2i) -> Option<Vector2i> {
+    let pull = wells
+        .iter()
+        .filter_map(|well| well.reach(pos).map(|distance| (distance, well.pos.x)))
+        .min_by_key(|(distance, _)| *distance)
+        .map_or(0, |(_, column)| (column - pos.x).signum());
+
+    // Only symbols that are already falling get pulled; resting ones stay put
+    let below = below(grid, pos);
+    if !is_free(grid, below) {
+        return None;
+    }
+    let diagonal = pos + Vector2i::new(pull, 1);
+    Some(if pull != 0 && is_free(grid, diagonal) { diagonal } else { below })
+}
+
+/// Get the cell straight below a position, skipping over holes
+fn below(grid: &Grid, pos: Vector2i) -> Vector2i {
+    let mut below = pos + Vector2i::new(0, 1);
+    while grid.is_valid(below.x, below.y) && !grid.is_enabled(below.x as usize, below.y as usize) {
+        below.y += 1;
+    }
+    below
+}
+
+/// Check if a cell is on the board, playable, empty and not filled by a stone or chocolate
+fn is_free(grid: &Grid, pos: Vector2i) -> bool {
+    grid.is_valid(pos.x, pos.y)
+        && grid.is_enabled(pos.x as usize, pos.y as usize)
+        && grid.get(pos.x as usize, pos.y as usize).is_none()
+        && !grid.is_filled(pos.x as usize, pos.y as usize)
+}
+
+/// Get the cells where a path changes direction, excluding its end
+fn bends(from: Vector2i, steps: &[Vector2i]) -> Vec<Vector2i> {
+    let mut previous = from;
+    steps
+        .windows(2)
+        .filter_map(|pair| {
+            let turned = pair[0] - previous != pair[1] - pair[0];
+            previous = pair[0];
+            turned.then_some(pair[0])
+        })
+        .collect()
+}
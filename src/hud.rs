@@ -0,0 +1,37 @@
+//! HUD summary - per-type counts and dangers read off the grid in one pass
+//!
+//! HUD widgets and minimaps show aggregates of the board; the summary works
+//! them out on the Rust side so scripts never have to walk the cells.
+
+use crate::symbols::{Grid, SymbolType};
+use godot::prelude::*;
+
+/// Aggregates of the board for HUD widgets
+#[derive(Debug, Clone, Default)]
+pub struct HudSummary {
+    /// Symbols of each type, indexed by `SymbolType::index`; blockers are not counted
+    pub counts: [i32; SymbolType::ALL.len()],
+    pub blockers: i32,
+    pub seekers: i32,
+    /// Symbols that turn into blockers on the next rotation
+    pub expiring: Vec<Vector2i>,
+}
+
+/// Summarize the grid; `expiry` is the level's expiry age (0 when symbols never expire)
+pub fn summarize(grid: &Grid, expiry: u32) -> HudSummary {
+    let mut summary = HudSummary::default();
+    for symbol in grid.cells.iter().flatten() {
+        if symbol.blocker {
+            summary.blockers += 1;
+            continue;
+        }
+        summary.counts[symbol.current_type().index()] += 1;
+        if symbol.seeker {
+            summary.seekers += 1;
+        }
+        if expiry > 0 && symbol.age + 1 >= expiry {
+            summary.expiring.push(symbol.grid_pos);
+        }
+    }
+    summary
+}
@@ -0,0 +1,26 @@
+//! Ice - frozen cells that hold their symbol until matches break them free
+//!
+//! Ice sits in the grid's overlay layer as `CellOverlay::Ice` with its
+//! layers, over a symbol. A frozen symbol still matches, but it can't be
+//! swapped, held or moved by gravity, and symbols above it rest on it. A
+//! resolution clearing a frozen cell cracks one layer off the ice instead of
+//! taking the symbol; once the last layer breaks, the symbol is free.
+
+use crate::symbols::{CellOverlay, Grid};
+use godot::prelude::*;
+
+/// Crack one layer off the ice over any of the cleared cells, taking those cells out of `cleared`
+/// since their symbols stay. Returns each cell cracked with the layers it has left
+pub fn crack(grid: &mut Grid, cleared: &mut Vec<Vector2i>) -> Vec<(Vector2i, u32)> {
+    let mut cracked = Vec::new();
+    cleared.retain(|pos| {
+        let Some(CellOverlay::Ice(layers)) = grid.overlay(pos.x as usize, pos.y as usize) else {
+            return true;
+        };
+        let left = layers.saturating_sub(1);
+        let _ = grid.set_overlay(*pos, (left > 0).then_some(CellOverlay::Ice(left)));
+        cracked.push((*pos, left));
+        false
+    });
+    cracked
+}
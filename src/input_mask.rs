@@ -0,0 +1,48 @@
+//! Input masks - whitelists of cells and actions the player may use
+//!
+//! Used by tutorials and cutscenes to steer the player; anything outside the
+//! mask is rejected so the UI can point at where to try instead.
+
+use godot::prelude::*;
+
+/// Selecting a symbol
+pub const ACTION_SELECT: i32 = 1;
+
+/// Swapping the selected symbol with a neighbor
+pub const ACTION_SWAP: i32 = 2;
+
+/// Stashing a symbol in the hold slot or swapping the held one back
+pub const ACTION_HOLD: i32 = 4;
+
+/// Every action
+pub const ACTION_ALL: i32 = ACTION_SELECT | ACTION_SWAP | ACTION_HOLD;
+
+/// Cells and actions currently accepted from the player
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputMask {
+    /// Allowed cells; empty allows every cell
+    cells: Vec<Vector2i>,
+    /// Bitmask of allowed actions
+    actions: i32,
+}
+
+impl Default for InputMask {
+    fn default() -> Self {
+        Self {
+            cells: Vec::new(),
+            actions: ACTION_ALL,
+        }
+    }
+}
+
+impl InputMask {
+    /// Create a mask; an empty cell list allows every cell
+    pub fn new(cells: Vec<Vector2i>, actions: i32) -> Self {
+        Self { cells, actions }
+    }
+
+    /// Check if an action on a cell is allowed
+    pub fn allows(&self, action: i32, pos: Vector2i) -> bool {
+        self.actions & action != 0 && (self.cells.is_empty() || self.cells.contains(&pos))
+    }
+}
@@ -0,0 +1,66 @@
+//! Jelly - a level layer under the symbols that matches wipe away
+//!
+//! Jelly belongs to cells rather than symbols, so it stays put while symbols
+//! fall and refill over it. A match through a jelly cell removes its jelly;
+//! a level painted with jelly is won once none is left.
+
+use godot::prelude::*;
+
+/// Color of the tile shown under a jelly cell
+pub const TILE_COLOR: Color = Color::from_rgba(0.85, 0.4, 0.6, 1.0);
+
+/// Jelly per cell, as painted on the level
+#[derive(Debug, Clone, Default)]
+pub struct JellyLayer {
+    width: usize,
+    cells: Vec<bool>,
+}
+
+impl JellyLayer {
+    /// Create jelly for a board `width` cells wide from one flag per cell
+    pub fn new(width: usize, cells: Vec<bool>) -> Self {
+        Self { width, cells }
+    }
+
+    /// Check if a cell has jelly
+    pub fn contains(&self, pos: Vector2i) -> bool {
+        self.index(pos).and_then(|idx| self.cells.get(idx)).is_some_and(|jelly| *jelly)
+    }
+
+    /// Get the cells with jelly left
+    pub fn remaining(&self) -> usize {
+        self.cells.iter().filter(|jelly| **jelly).count()
+    }
+
+    /// Remove the jelly under the given cells, returning the cells that had some
+    pub fn clear(&mut self, positions: &[Vector2i]) -> Vec<Vector2i> {
+        positions
+            .iter()
+            .copied()
+            .filter(|pos| {
+                let Some(jelly) = self.index(*pos).and_then(|idx| self.cells.get_mut(idx)) else {
+                    return false;
+                };
+                std::mem::take(jelly)
+            })
+            .collect()
+    }
+
+    /// Iterate over the cells with jelly
+    pub fn cells(&self) -> impl Iterator<Item = Vector2i> + '_ {
+        let width = self.width.max(1);
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, jelly)| **jelly)
+            .map(move |(idx, _)| Vector2i::new((idx % width) as i32, (idx / width) as i32))
+    }
+
+    /// Get the cell index for a position
+    fn index(&self, pos: Vector2i) -> Option<usize> {
+        if pos.x < 0 || pos.y < 0 || pos.x as usize >= self.width {
+            return None;
+        }
+        Some(pos.y as usize * self.width + pos.x as usize)
+    }
+}
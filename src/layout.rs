@@ -0,0 +1,85 @@
+//! ASCII board layouts - one character per cell, one line per row
+//!
+//! Symbols use their letter code (`R`, `B`, `G`, `Y`, `P`, `O`), `.` marks a
+//! random cell, `#` a blocker and `_` a hole outside the playable area.
+
+use crate::symbols::SymbolType;
+
+/// Character marking a cell to be filled randomly
+pub const RANDOM_CHAR: char = '.';
+
+/// Character marking a blocker
+pub const BLOCKER_CHAR: char = '#';
+
+/// Character marking a hole (disabled cell)
+pub const HOLE_CHAR: char = '_';
+
+/// A single cell of a layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutCell {
+    Random,
+    Symbol(SymbolType),
+    Blocker,
+    Hole,
+}
+
+impl LayoutCell {
+    /// Parse a cell from its character
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            RANDOM_CHAR => Some(LayoutCell::Random),
+            BLOCKER_CHAR => Some(LayoutCell::Blocker),
+            HOLE_CHAR => Some(LayoutCell::Hole),
+            _ => SymbolType::from_letter(c).map(LayoutCell::Symbol),
+        }
+    }
+
+    /// Get the character for this cell
+    pub fn to_char(self) -> char {
+        match self {
+            LayoutCell::Random => RANDOM_CHAR,
+            LayoutCell::Symbol(symbol_type) => symbol_type.letter(),
+            LayoutCell::Blocker => BLOCKER_CHAR,
+            LayoutCell::Hole => HOLE_CHAR,
+        }
+    }
+}
+
+/// A parsed layout: rows of cells
+pub type Layout = Vec<Vec<LayoutCell>>;
+
+/// Parse a layout; rows are separated by newlines or `/`
+/// Whitespace and commas are ignored so spreadsheet pastes work as-is
+pub fn parse(text: &str) -> Result<Layout, String> {
+    let mut rows: Layout = Vec::new();
+
+    for line in text.split(['\n', '/']) {
+        let row = line
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != ',')
+            .map(|c| LayoutCell::from_char(c).ok_or(format!("unknown cell '{}'", c)))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        if !row.is_empty() {
+            rows.push(row);
+        }
+    }
+
+    let Some(width) = rows.first().map(Vec::len) else {
+        return Err("layout is empty".to_string());
+    };
+    if rows.iter().any(|row| row.len() != width) {
+        return Err("layout rows differ in length".to_string());
+    }
+
+    Ok(rows)
+}
+
+/// Format a layout as newline-separated rows
+pub fn format(layout: &Layout) -> String {
+    layout
+        .iter()
+        .map(|row| row.iter().map(|cell| cell.to_char()).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
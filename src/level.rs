@@ -0,0 +1,634 @@
+//! Level data resource and the editor-side level painter
+
+use crate::gravity::GravityWell;
+use crate::jelly::JellyLayer;
+use crate::layout::{self, LayoutCell};
+use crate::link::InputMode;
+use crate::multipliers::CellMultipliers;
+use crate::objectives::{Objective, ObjectiveKind};
+use crate::refill::SpawnGuarantee;
+use crate::symbols::{CellOverlay, SymbolType};
+use godot::prelude::*;
+
+/// Cell value meaning "fill with a random symbol"
+pub const RANDOM_CELL: i32 = -1;
+
+/// Authored level data - board size plus per-cell layers
+#[derive(GodotClass)]
+#[class(tool, base=Resource)]
+pub struct LevelResource {
+    base: Base<Resource>,
+
+    /// Board width in cells
+    #[export]
+    width: i32,
+
+    /// Board height in cells
+    #[export]
+    height: i32,
+
+    /// Preplaced symbol type index per cell (-1 for random)
+    #[export]
+    cells: PackedInt32Array,
+
+    /// Blocker hit points per cell (0 for none)
+    #[export]
+    blockers: PackedInt32Array,
+
+    /// Overlay layers per cell (0 for none)
+    #[export]
+    overlays: PackedInt32Array,
+
+    /// Chocolate per cell (1 for chocolate, 0 for none); it spreads after moves that don't eat any
+    #[export]
+    chocolate: PackedInt32Array,
+
+    /// Playable mask per cell (1 enabled, 0 hole)
+    #[export]
+    mask: PackedByteArray,
+
+    /// Gravity well radius per cell (0 for none)
+    #[export]
+    wells: PackedInt32Array,
+
+    /// Score multiplier per cell (0 for none)
+    #[export]
+    multipliers: PackedInt32Array,
+
+    /// Whether a cell's multiplier is used up by the first match over it
+    #[export]
+    consume_multipliers: bool,
+
+    /// Jelly per cell (1 for jelly, 0 for none); the level is won once matches clear it all
+    #[export]
+    jelly: PackedInt32Array,
+
+    /// Ingredient per cell (1 for an ingredient, 0 for none); ingredients fall with the board and are collected
+    /// from the bottom row
+    #[export]
+    ingredients: PackedInt32Array,
+
+    /// Rotations a symbol survives unmatched before it turns into a blocker, with the expiry modifier
+    #[export(range = (0.0, 20.0, 1.0, or_greater))]
+    expiry_rotations: i32,
+
+    /// Tutorial steps run when the level loads (see the `tutorial` module)
+    #[export]
+    tutorial: Array<Dictionary>,
+
+    /// How the player makes moves on this level
+    #[export(enum = (Swap, Link))]
+    input_mode: i32,
+
+    /// Modifiers stacked on this level, after the board's own: frenzy, expiry, roulette, mirror, fog
+    #[export]
+    modifiers: PackedStringArray,
+
+    /// Symbol type index the level's goal is collecting (-1 for none); it becomes the board's objective
+    #[export]
+    collect_color: i32,
+
+    /// Least share of each column's refills that are the collect color (0 for no guarantee),
+    /// so the goal can't become out of reach
+    #[export(range = (0.0, 1.0, 0.01))]
+    collect_spawn_rate: f64,
+
+    /// Tags put on the starting symbols, as { "cell": Vector2i, "key": String, "value": any }
+    /// (the value is kept as text)
+    #[export]
+    cell_tags: Array<Dictionary>,
+
+    /// Weighted goals making up the level's completion, as { "kind": "score", "collect", "stones", "ice", "jelly" or
+    /// "ingredients", "target": int, "weight": float (1 if left out), "symbol_type": int (collect only) }
+    #[export]
+    objectives: Array<Dictionary>,
+}
+
+#[godot_api]
+impl IResource for LevelResource {
+    fn init(base: Base<Resource>) -> Self {
+        let mut level = Self {
+            base,
+            width: 8,
+            height: 8,
+            cells: PackedInt32Array::new(),
+            blockers: PackedInt32Array::new(),
+            overlays: PackedInt32Array::new(),
+            chocolate: PackedInt32Array::new(),
+            mask: PackedByteArray::new(),
+            wells: PackedInt32Array::new(),
+            multipliers: PackedInt32Array::new(),
+            consume_multipliers: false,
+            jelly: PackedInt32Array::new(),
+            ingredients: PackedInt32Array::new(),
+            expiry_rotations: 0,
+            tutorial: Array::new(),
+            input_mode: InputMode::Swap as i32,
+            modifiers: PackedStringArray::new(),
+            collect_color: -1,
+            collect_spawn_rate: 0.0,
+            cell_tags: Array::new(),
+            objectives: Array::new(),
+        };
+        level.ensure_layers();
+        level
+    }
+}
+
+#[godot_api]
+impl LevelResource {
+    /// Resize the level, discarding all painted cells
+    #[func]
+    pub fn resize(&mut self, width: i32, height: i32) {
+        self.width = width.max(1);
+        self.height = height.max(1);
+        self.cells = PackedInt32Array::new();
+        self.blockers = PackedInt32Array::new();
+        self.overlays = PackedInt32Array::new();
+        self.chocolate = PackedInt32Array::new();
+        self.mask = PackedByteArray::new();
+        self.wells = PackedInt32Array::new();
+        self.multipliers = PackedInt32Array::new();
+        self.jelly = PackedInt32Array::new();
+        self.ingredients = PackedInt32Array::new();
+        self.ensure_layers();
+    }
+
+    /// Replace the level with an ASCII layout (blockers get 1 hit point)
+    /// Returns false and leaves the level untouched if the layout is invalid
+    #[func]
+    pub fn import_layout(&mut self, text: GString) -> bool {
+        let rows = match layout::parse(&text.to_string()) {
+            Ok(rows) => rows,
+            Err(message) => {
+                godot_warn!("Invalid layout: {}", message);
+                return false;
+            }
+        };
+
+        self.resize(rows[0].len() as i32, rows.len() as i32);
+        let width = rows[0].len();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let idx = y * width + x;
+                match cell {
+                    LayoutCell::Random => {}
+                    LayoutCell::Symbol(symbol_type) => self.cells.as_mut_slice()[idx] = symbol_type.index() as i32,
+                    LayoutCell::Blocker => self.blockers.as_mut_slice()[idx] = 1,
+                    LayoutCell::Hole => self.mask.as_mut_slice()[idx] = 0,
+                }
+            }
+        }
+
+        self.base_mut().emit_changed();
+        true
+    }
+
+    /// Export the level as an ASCII layout
+    #[func]
+    pub fn export_layout(&self) -> GString {
+        let (width, height) = self.size();
+        let rows: layout::Layout = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let idx = y * width + x;
+                        if !self.is_enabled(idx) {
+                            LayoutCell::Hole
+                        } else if self.has_blocker(idx) {
+                            LayoutCell::Blocker
+                        } else if self.cell(idx) >= 0 {
+                            LayoutCell::Symbol(SymbolType::from_index(self.cell(idx) as usize))
+                        } else {
+                            LayoutCell::Random
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        layout::format(&rows).into()
+    }
+}
+
+impl LevelResource {
+    /// Board size in cells
+    pub fn size(&self) -> (usize, usize) {
+        (self.width.max(1) as usize, self.height.max(1) as usize)
+    }
+
+    /// Number of entries in the preplaced symbol layer
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Make sure every layer holds exactly one entry per cell
+    pub fn ensure_layers(&mut self) {
+        let (width, height) = self.size();
+        let count = width * height;
+
+        if self.cells.len() != count {
+            self.cells = vec![RANDOM_CELL; count].into_iter().collect();
+        }
+        if self.blockers.len() != count {
+            self.blockers = vec![0; count].into_iter().collect();
+        }
+        if self.overlays.len() != count {
+            self.overlays = vec![0; count].into_iter().collect();
+        }
+        if self.chocolate.len() != count {
+            self.chocolate = vec![0; count].into_iter().collect();
+        }
+        if self.mask.len() != count {
+            self.mask = vec![1u8; count].into_iter().collect();
+        }
+        if self.wells.len() != count {
+            self.wells = vec![0; count].into_iter().collect();
+        }
+        if self.multipliers.len() != count {
+            self.multipliers = vec![0; count].into_iter().collect();
+        }
+        if self.jelly.len() != count {
+            self.jelly = vec![0; count].into_iter().collect();
+        }
+        if self.ingredients.len() != count {
+            self.ingredients = vec![0; count].into_iter().collect();
+        }
+    }
+
+    /// Get the cell index for a position
+    pub fn index(&self, pos: Vector2i) -> Option<usize> {
+        let (width, height) = self.size();
+        if pos.x < 0 || pos.y < 0 || pos.x as usize >= width || pos.y as usize >= height {
+            return None;
+        }
+        Some(pos.y as usize * width + pos.x as usize)
+    }
+
+    /// Get all preplaced symbols as (x, y, type)
+    pub fn preplaced_symbols(&self) -> Vec<(usize, usize, SymbolType)> {
+        let (width, _) = self.size();
+        self.cells
+            .as_slice()
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value >= 0)
+            .map(|(idx, value)| (idx % width, idx / width, SymbolType::from_index(*value as usize)))
+            .collect()
+    }
+
+    /// Get the tags put on the starting symbols as (cell, key, value), skipping malformed entries
+    pub fn cell_tags(&self) -> Vec<(Vector2i, String, String)> {
+        let mut tags = Vec::new();
+        for entry in self.cell_tags.iter_shared() {
+            let cell = entry.get("cell").and_then(|cell| cell.try_to::<Vector2i>().ok());
+            let key = entry.get("key").and_then(|key| key.try_to::<GString>().ok());
+            match (cell, key, entry.get("value")) {
+                (Some(cell), Some(key), Some(value)) => tags.push((cell, key.to_string(), value.to_string())),
+                _ => godot_warn!("Ignoring malformed cell tag: {}", entry),
+            }
+        }
+        tags
+    }
+
+    /// Get the weighted objectives, skipping malformed entries
+    pub fn objectives(&self) -> Vec<Objective> {
+        let mut objectives = Vec::new();
+        for entry in self.objectives.iter_shared() {
+            let symbol_type = entry
+                .get("symbol_type")
+                .and_then(|index| index.try_to::<i32>().ok())
+                .and_then(|index| usize::try_from(index).ok())
+                .filter(|index| *index < SymbolType::ALL.len())
+                .map(SymbolType::from_index);
+            let kind = entry
+                .get("kind")
+                .and_then(|kind| kind.try_to::<GString>().ok())
+                .and_then(|kind| ObjectiveKind::from_name(&kind.to_string(), symbol_type));
+            let target = entry.get("target").and_then(|target| target.try_to::<i32>().ok());
+            let weight = match entry.get("weight") {
+                Some(weight) => weight.try_to::<f64>().ok().or_else(|| weight.try_to::<i32>().ok().map(f64::from)),
+                None => Some(1.0),
+            };
+            match (kind, target, weight) {
+                (Some(kind), Some(target), Some(weight)) => objectives.push(Objective::new(kind, target, weight)),
+                _ => godot_warn!("Ignoring malformed objective: {}", entry),
+            }
+        }
+        objectives
+    }
+
+    /// Get the gravity wells
+    pub fn gravity_wells(&self) -> Vec<GravityWell> {
+        let (width, _) = self.size();
+        self.wells
+            .as_slice()
+            .iter()
+            .enumerate()
+            .filter(|(_, radius)| **radius > 0)
+            .map(|(idx, radius)| GravityWell {
+                pos: Vector2i::new((idx % width) as i32, (idx / width) as i32),
+                radius: *radius,
+            })
+            .collect()
+    }
+
+    /// Get the score multipliers painted on cells
+    pub fn cell_multipliers(&self) -> CellMultipliers {
+        let (width, _) = self.size();
+        CellMultipliers::new(width, self.multipliers.to_vec(), self.consume_multipliers)
+    }
+
+    /// Get the jelly painted on cells
+    pub fn jelly(&self) -> JellyLayer {
+        let (width, _) = self.size();
+        JellyLayer::new(width, self.jelly.as_slice().iter().map(|jelly| *jelly > 0).collect())
+    }
+
+    /// Get the cells that start with an ingredient
+    pub fn ingredient_cells(&self) -> Vec<Vector2i> {
+        let (width, _) = self.size();
+        let ingredients = self.ingredients.as_slice().iter().enumerate();
+        ingredients
+            .filter(|(idx, ingredient)| **ingredient > 0 && self.is_enabled(*idx))
+            .map(|(idx, _)| Vector2i::new((idx % width) as i32, (idx / width) as i32))
+            .collect()
+    }
+
+    /// Get the cells masked out of the board as holes
+    pub fn hole_cells(&self) -> Vec<Vector2i> {
+        let (width, _) = self.size();
+        let mask = self.mask.as_slice().iter().enumerate();
+        mask.filter(|(_, enabled)| **enabled == 0)
+            .map(|(idx, _)| Vector2i::new((idx % width) as i32, (idx / width) as i32))
+            .collect()
+    }
+
+    /// Get the rotations a symbol survives unmatched before it turns into a blocker (0 for never)
+    pub fn expiry_rotations(&self) -> u32 {
+        self.expiry_rotations.max(0) as u32
+    }
+
+    /// Get the tutorial step dictionaries
+    pub fn tutorial_steps(&self) -> Array<Dictionary> {
+        self.tutorial.clone()
+    }
+
+    /// Get how the player makes moves on this level
+    pub fn input_mode(&self) -> InputMode {
+        InputMode::from_index(self.input_mode).unwrap_or_default()
+    }
+
+    /// Get the names of the modifiers stacked on this level
+    pub fn modifiers(&self) -> Vec<String> {
+        self.modifiers.as_slice().iter().map(GString::to_string).collect()
+    }
+
+    /// Get the symbol type the level's goal is collecting, if any
+    pub fn collect_type(&self) -> Option<SymbolType> {
+        usize::try_from(self.collect_color)
+            .ok()
+            .filter(|index| *index < SymbolType::ALL.len())
+            .map(SymbolType::from_index)
+    }
+
+    /// Get the floor on how often the collect color spawns, if the level sets one
+    pub fn spawn_guarantee(&self) -> Option<SpawnGuarantee> {
+        SpawnGuarantee::from_rate(self.collect_type()?, self.collect_spawn_rate)
+    }
+
+    /// Get the preplaced symbol type at a cell (-1 for random)
+    pub fn cell(&self, idx: usize) -> i32 {
+        self.cells.as_slice().get(idx).copied().unwrap_or(RANDOM_CELL)
+    }
+
+    /// Check if a cell is playable
+    pub fn is_enabled(&self, idx: usize) -> bool {
+        self.mask.as_slice().get(idx).is_some_and(|m| *m != 0)
+    }
+
+    /// Check if a cell holds a blocker
+    pub fn has_blocker(&self, idx: usize) -> bool {
+        self.blockers.as_slice().get(idx).is_some_and(|hp| *hp > 0)
+    }
+
+    /// Check if chocolate is painted on a cell
+    pub fn has_chocolate(&self, idx: usize) -> bool {
+        self.chocolate.as_slice().get(idx).is_some_and(|chocolate| *chocolate > 0)
+    }
+
+    /// Get the cell overlays: a stone for each blocker, then chocolate and ice where no blocker is painted
+    pub fn cell_overlays(&self) -> Vec<(Vector2i, CellOverlay)> {
+        let (width, _) = self.size();
+        let pos = |idx: usize| Vector2i::new((idx % width) as i32, (idx / width) as i32);
+        let stones = self.blockers.as_slice().iter().enumerate().filter(|(_, hp)| **hp > 0);
+        let chocolate = (0..self.chocolate.len()).filter(|idx| self.has_chocolate(*idx) && !self.has_blocker(*idx));
+        let ice = self.overlays.as_slice().iter().enumerate();
+        let ice = ice.filter(|(idx, layers)| **layers > 0 && !self.has_blocker(*idx) && !self.has_chocolate(*idx));
+        stones
+            .map(|(idx, hp)| (pos(idx), CellOverlay::Stone(*hp as u32)))
+            .chain(chocolate.map(|idx| (pos(idx), CellOverlay::Chocolate)))
+            .chain(ice.map(|(idx, layers)| (pos(idx), CellOverlay::Ice(*layers as u32))))
+            .collect()
+    }
+
+    /// Check if any cell has a blocker
+    pub fn has_any_blocker(&self) -> bool {
+        self.blockers.as_slice().iter().any(|hp| *hp > 0)
+    }
+
+    /// Check if placing `symbol_type` at `pos` would complete a preplaced run of 3
+    fn creates_run(&self, pos: Vector2i, symbol_type: i32) -> bool {
+        let same = |dx: i32, dy: i32| {
+            self.index(Vector2i::new(pos.x + dx, pos.y + dy))
+                .is_some_and(|idx| self.cell(idx) == symbol_type)
+        };
+
+        let count_dir = |dx: i32, dy: i32| (1..3).take_while(|i| same(dx * i, dy * i)).count();
+
+        count_dir(-1, 0) + count_dir(1, 0) >= 2 || count_dir(0, -1) + count_dir(0, 1) >= 2
+    }
+}
+
+/// Editor backend for painting cells into a `LevelResource`
+/// All validity rules live here so the editor plugin UI stays thin
+#[derive(GodotClass)]
+#[class(tool, init, base=RefCounted)]
+pub struct EditorLevelPainter {
+    base: Base<RefCounted>,
+
+    /// The level being edited
+    level: Option<Gd<LevelResource>>,
+}
+
+#[godot_api]
+impl EditorLevelPainter {
+    /// Set the level to paint into
+    #[func]
+    fn set_level(&mut self, mut level: Gd<LevelResource>) {
+        level.bind_mut().ensure_layers();
+        self.level = Some(level);
+    }
+
+    /// Get the level being painted
+    #[func]
+    fn get_level(&self) -> Option<Gd<LevelResource>> {
+        self.level.clone()
+    }
+
+    /// Paint a preplaced symbol (-1 for random)
+    /// Rejected on holes, blockers, or when it would create a starting match
+    #[func]
+    fn paint_symbol(&mut self, pos: Vector2i, symbol_type: i32) -> bool {
+        if symbol_type < RANDOM_CELL || symbol_type >= SymbolType::ALL.len() as i32 {
+            godot_warn!("Invalid symbol type index: {}", symbol_type);
+            return false;
+        }
+
+        self.edit(pos, |level, idx| {
+            if !level.is_enabled(idx) || level.has_blocker(idx) {
+                return false;
+            }
+            if symbol_type != RANDOM_CELL && level.creates_run(pos, symbol_type) {
+                return false;
+            }
+            level.cells.as_mut_slice()[idx] = symbol_type;
+            true
+        })
+    }
+
+    /// Paint a blocker with the given hit points (0 removes it)
+    /// A blocker replaces any preplaced symbol in the cell
+    #[func]
+    fn paint_blocker(&mut self, pos: Vector2i, hit_points: i32) -> bool {
+        self.edit(pos, |level, idx| {
+            if !level.is_enabled(idx) || hit_points < 0 {
+                return false;
+            }
+            level.blockers.as_mut_slice()[idx] = hit_points;
+            if hit_points > 0 {
+                level.cells.as_mut_slice()[idx] = RANDOM_CELL;
+                level.ingredients.as_mut_slice()[idx] = 0;
+            }
+            true
+        })
+    }
+
+    /// Paint an overlay with the given number of layers (0 removes it)
+    #[func]
+    fn paint_overlay(&mut self, pos: Vector2i, layers: i32) -> bool {
+        self.edit(pos, |level, idx| {
+            if !level.is_enabled(idx) || layers < 0 {
+                return false;
+            }
+            level.overlays.as_mut_slice()[idx] = layers;
+            true
+        })
+    }
+
+    /// Paint chocolate on a cell, or remove it
+    #[func]
+    fn paint_chocolate(&mut self, pos: Vector2i, chocolate: bool) -> bool {
+        self.edit(pos, |level, idx| {
+            if !level.is_enabled(idx) {
+                return false;
+            }
+            level.chocolate.as_mut_slice()[idx] = chocolate as i32;
+            if chocolate {
+                level.cells.as_mut_slice()[idx] = RANDOM_CELL;
+                level.ingredients.as_mut_slice()[idx] = 0;
+            }
+            true
+        })
+    }
+
+    /// Paint a gravity well reaching the given number of cells (0 removes it)
+    #[func]
+    fn paint_well(&mut self, pos: Vector2i, radius: i32) -> bool {
+        self.edit(pos, |level, idx| {
+            if !level.is_enabled(idx) || radius < 0 {
+                return false;
+            }
+            level.wells.as_mut_slice()[idx] = radius;
+            true
+        })
+    }
+
+    /// Paint a score multiplier (0 removes it)
+    #[func]
+    fn paint_multiplier(&mut self, pos: Vector2i, factor: i32) -> bool {
+        self.edit(pos, |level, idx| {
+            if !level.is_enabled(idx) || factor < 0 {
+                return false;
+            }
+            level.multipliers.as_mut_slice()[idx] = factor;
+            true
+        })
+    }
+
+    /// Paint jelly on a cell, or remove it
+    #[func]
+    fn paint_jelly(&mut self, pos: Vector2i, jelly: bool) -> bool {
+        self.edit(pos, |level, idx| {
+            if !level.is_enabled(idx) {
+                return false;
+            }
+            level.jelly.as_mut_slice()[idx] = jelly as i32;
+            true
+        })
+    }
+
+    /// Start a cell with an ingredient, or remove it
+    #[func]
+    fn paint_ingredient(&mut self, pos: Vector2i, ingredient: bool) -> bool {
+        self.edit(pos, |level, idx| {
+            if !level.is_enabled(idx) || level.has_blocker(idx) || level.has_chocolate(idx) {
+                return false;
+            }
+            level.ingredients.as_mut_slice()[idx] = ingredient as i32;
+            true
+        })
+    }
+
+    /// Toggle whether a cell is playable
+    /// Disabling a cell clears everything painted on it
+    #[func]
+    fn toggle_mask(&mut self, pos: Vector2i) -> bool {
+        self.edit(pos, |level, idx| {
+            let enabled = !level.is_enabled(idx);
+            level.mask.as_mut_slice()[idx] = enabled as u8;
+            if !enabled {
+                level.cells.as_mut_slice()[idx] = RANDOM_CELL;
+                level.blockers.as_mut_slice()[idx] = 0;
+                level.overlays.as_mut_slice()[idx] = 0;
+                level.chocolate.as_mut_slice()[idx] = 0;
+                level.wells.as_mut_slice()[idx] = 0;
+                level.multipliers.as_mut_slice()[idx] = 0;
+                level.jelly.as_mut_slice()[idx] = 0;
+                level.ingredients.as_mut_slice()[idx] = 0;
+            }
+            true
+        })
+    }
+
+    /// Apply a validated edit to one cell, notifying listeners on success
+    fn edit(&mut self, pos: Vector2i, apply: impl FnOnce(&mut LevelResource, usize) -> bool) -> bool {
+        let Some(level) = self.level.as_mut() else {
+            godot_warn!("EditorLevelPainter has no level set");
+            return false;
+        };
+
+        let applied = {
+            let mut level = level.bind_mut();
+            level.ensure_layers();
+            match level.index(pos) {
+                Some(idx) => apply(&mut *level, idx),
+                None => false,
+            }
+        };
+
+        if applied {
+            level.upcast_mut::<Resource>().emit_changed();
+        }
+        applied
+    }
+}
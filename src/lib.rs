@@ -5,9 +5,73 @@
 
 use godot::prelude::*;
 
+mod animation;
+mod attract;
 mod board;
+mod chain;
+mod chocolate;
+mod clock;
+mod combo;
+mod combo_timer;
+mod commands;
+mod control;
+mod diagnostics;
+mod edits;
+mod emphasis;
+mod engine;
+mod error;
+mod events;
+mod expiry;
+mod fog;
+mod frenzy;
+mod garden;
+mod gravity;
+mod hud;
+mod ice;
+mod input_mask;
+mod jelly;
+mod layout;
+mod level;
+mod link;
+mod locale;
 mod matching;
+mod model;
+mod multipliers;
+mod objectives;
+mod onboarding;
+mod opening;
+mod pacing;
+mod performance;
+mod pity;
+mod placement;
+mod profiles;
+mod refill;
+mod render;
+mod replay;
+mod results;
+mod rewind;
+mod rng;
+mod roulette;
+mod rotation_stats;
+mod rules;
+mod sandbox;
+mod seeker;
+mod scoring;
+mod snapshot;
+mod soak;
+mod solver;
+mod state;
+mod stones;
 mod symbols;
+mod teaching;
+mod theme;
+mod ticker;
+mod tuning;
+mod turn;
+mod tutorial;
+mod unlocks;
+mod view;
+mod view3d;
 
 struct RevolvingMatch3Extension;
 
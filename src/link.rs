@@ -0,0 +1,88 @@
+//! Link input - dragging through chains of same-colored neighbors
+//!
+//! In `InputMode::Link` the player presses on a symbol and drags through
+//! orthogonally adjacent symbols of the same type; releasing clears the path
+//! if it is at least `MIN_LINK` long. Dragging back onto the previous cell
+//! undoes the last step.
+
+use crate::model::BoardModel;
+use crate::symbols::SymbolType;
+use godot::prelude::*;
+
+/// Shortest path that clears
+pub const MIN_LINK: usize = 3;
+
+/// How the player makes moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputMode {
+    /// Swap two adjacent symbols
+    #[default]
+    Swap,
+    /// Drag through a chain of same-colored symbols
+    Link,
+}
+
+impl InputMode {
+    /// All modes, in the order of their indices
+    pub const ALL: [InputMode; 2] = [InputMode::Swap, InputMode::Link];
+
+    /// Get a mode from its index
+    pub fn from_index(index: i32) -> Option<Self> {
+        usize::try_from(index).ok().and_then(|index| Self::ALL.get(index).copied())
+    }
+}
+
+/// How a path changed when the drag reached a cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkChange {
+    /// The cell was added to the end of the path
+    Added,
+    /// The drag went back, removing the given last cell
+    Removed(Vector2i),
+    /// The cell cannot join the path
+    Unchanged,
+}
+
+/// The path being dragged, if any
+#[derive(Debug, Clone, Default)]
+pub struct LinkPath {
+    cells: Vec<Vector2i>,
+    symbol_type: Option<SymbolType>,
+}
+
+impl LinkPath {
+    /// Start a new path at a cell
+    pub fn start(&mut self, pos: Vector2i, symbol_type: SymbolType) {
+        self.cells = vec![pos];
+        self.symbol_type = Some(symbol_type);
+    }
+
+    /// Check if a path is being dragged
+    pub fn is_active(&self) -> bool {
+        self.symbol_type.is_some()
+    }
+
+    /// Extend the path to a cell holding a symbol of the given type
+    pub fn extend(&mut self, pos: Vector2i, symbol_type: SymbolType) -> LinkChange {
+        let Some(&last) = self.cells.last() else {
+            return LinkChange::Unchanged;
+        };
+
+        if self.cells.len() >= 2 && self.cells[self.cells.len() - 2] == pos {
+            self.cells.pop();
+            return LinkChange::Removed(last);
+        }
+        if self.cells.contains(&pos) || !BoardModel::is_adjacent(last, pos) || self.symbol_type != Some(symbol_type) {
+            return LinkChange::Unchanged;
+        }
+
+        self.cells.push(pos);
+        LinkChange::Added
+    }
+
+    /// End the path, returning its cells
+    pub fn take(&mut self) -> Vec<Vector2i> {
+        self.symbol_type = None;
+        std::mem::take(&mut self.cells)
+    }
+}
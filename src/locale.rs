@@ -0,0 +1,96 @@
+//! Message catalog for the player-facing text the crate produces
+//!
+//! Text is looked up by message key, e.g. `teach.rotation`, in the current
+//! locale. A key missing from a locale falls back to the locale's base
+//! language (`pt_br` to `pt`), then to the built-in English text, then to the
+//! key itself. Placeholders such as `{pos}` are filled from named arguments.
+
+use std::collections::BTreeMap;
+
+/// Locale the built-in text is written in
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Built-in English text
+const ENGLISH: &[(&str, &str)] = &[
+    ("error.out_of_bounds", "Position {pos} is outside the board."),
+    ("error.empty_cell", "There is no symbol at {pos}."),
+    ("error.cell_frozen", "The symbol at {pos} is frozen in ice."),
+    ("error.cell_blocked", "The cell at {pos} is blocked and has no room for a symbol."),
+    ("error.cell_disabled", "The cell at {pos} is not part of the board."),
+    ("error.view_mismatch", "The board was out of sync at {pos} and has been repaired."),
+    ("error.no_active_command", "An animation finished with nothing waiting for it."),
+    ("swap.not_adjacent", "Only neighboring symbols can be swapped."),
+    ("swap.no_match", "That swap would not make a match."),
+    ("swap.cell_locked", "That cell can't be moved right now."),
+    ("swap.board_busy", "Wait for the board to settle."),
+    ("swap.swaps_disabled", "Symbols can't be swapped in this mode."),
+    ("teach.rotation", "Every few seconds all symbols turn to their next face. Plan for the turn!"),
+    ("teach.blockers", "Blockers never match. Make a match next to one to break it."),
+    ("teach.specials", "Long matches leave special symbols behind. Match or swap one to set it off."),
+    ("rotation.rotate_faces", "Next turn: every symbol rotates."),
+    ("rotation.shuffle_row", "Next turn: a row gets shuffled."),
+    ("rotation.freeze_column", "Next turn: a column freezes."),
+    ("rotation.spawn_special", "Next turn: a symbol turns special."),
+    ("frenzy.upcoming", "Frenzy in {seconds}!"),
+    ("frenzy.started", "Frenzy! Scores x{multiplier}"),
+    ("frenzy.ended", "Frenzy over."),
+];
+
+/// Normalize a locale code, so `pt-BR` and `pt_BR` both become `pt_br`
+fn normalize(code: &str) -> String {
+    code.trim().replace('-', "_").to_lowercase()
+}
+
+/// The current locale and the text registered for each locale
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    locale: String,
+    messages: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        let english = ENGLISH.iter().map(|(key, text)| (key.to_string(), text.to_string())).collect();
+        Self {
+            locale: DEFAULT_LOCALE.to_string(),
+            messages: BTreeMap::from([(DEFAULT_LOCALE.to_string(), english)]),
+        }
+    }
+}
+
+impl Catalog {
+    /// Get the current locale code
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Switch locale; text missing from it falls back as described above
+    pub fn set_locale(&mut self, code: &str) {
+        let code = normalize(code);
+        self.locale = if code.is_empty() { DEFAULT_LOCALE.to_string() } else { code };
+    }
+
+    /// Check if any text was registered for a locale or its base language
+    pub fn has_locale(&self, code: &str) -> bool {
+        let code = normalize(code);
+        let language = code.split('_').next().unwrap_or_default();
+        self.messages.contains_key(&code) || self.messages.contains_key(language)
+    }
+
+    /// Register text for a locale, replacing any earlier text for the same keys
+    pub fn add_messages(&mut self, code: &str, messages: impl IntoIterator<Item = (String, String)>) {
+        self.messages.entry(normalize(code)).or_default().extend(messages);
+    }
+
+    /// Get the text for a key in the current locale, filling in `{name}` placeholders
+    pub fn text(&self, key: &str, args: &[(String, String)]) -> String {
+        let language = self.locale.split('_').next().unwrap_or_default();
+        let template = [self.locale.as_str(), language, DEFAULT_LOCALE]
+            .into_iter()
+            .find_map(|code| self.messages.get(code).and_then(|messages| messages.get(key)))
+            .map_or(key, String::as_str);
+
+        args.iter()
+            .fold(template.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+    }
+}
@@ -1,15 +1,58 @@
 //! Match detection algorithms for the match-3 game
 
-use crate::symbols::Grid;
+use crate::symbols::{Grid, Symbol};
 use godot::prelude::*;
 
+/// The shape a match's cells make
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchShape {
+    /// A single straight run
+    #[default]
+    Line,
+    /// Two runs meeting at their ends
+    L,
+    /// The end of one run meeting the middle of another
+    T,
+    /// Two runs crossing through their middles
+    Cross,
+}
+
+impl MatchShape {
+    /// Get the stronger of two shapes: a cross beats a T, which beats an L, which beats a line
+    fn max_by_strength(self, other: MatchShape) -> MatchShape {
+        let strength = |shape: MatchShape| match shape {
+            MatchShape::Line => 0,
+            MatchShape::L => 1,
+            MatchShape::T => 2,
+            MatchShape::Cross => 3,
+        };
+        if strength(other) > strength(self) {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// Get the lowercase name of this shape
+    pub fn name(&self) -> &'static str {
+        match self {
+            MatchShape::Line => "line",
+            MatchShape::L => "l",
+            MatchShape::T => "t",
+            MatchShape::Cross => "cross",
+        }
+    }
+}
+
 /// A match of 3 or more symbols
 #[derive(Debug, Clone)]
 pub struct Match {
     /// Positions of symbols in this match
     pub positions: Vec<Vector2i>,
-    /// Whether this is a horizontal match
+    /// Whether this is a horizontal line; false for every other shape
     pub horizontal: bool,
+    /// The shape of the match; runs crossing each other are merged into one match
+    pub shape: MatchShape,
 }
 
 impl Match {
@@ -43,41 +86,16 @@ impl MatchFinder {
         let mut matches = Vec::new();
 
         for y in 0..grid.height {
-            let mut x = 0;
-            while x < grid.width {
-                if let Some(symbol) = grid.get(x, y) {
-                    let symbol_type = symbol.current_type();
-                    let mut match_len = 1;
-
-                    // Count consecutive symbols of the same type
-                    while x + match_len < grid.width {
-                        if let Some(next) = grid.get(x + match_len, y) {
-                            if next.current_type() == symbol_type {
-                                match_len += 1;
-                            } else {
-                                break;
-                            }
-                        } else {
-                            break;
-                        }
-                    }
+            for (x, match_len) in Self::find_runs(grid.width, |x| grid.matchable(x, y)) {
+                let positions: Vec<Vector2i> = (0..match_len)
+                    .map(|i| Vector2i::new((x + i) as i32, y as i32))
+                    .collect();
 
-                    // If we found a match of 3+
-                    if match_len >= 3 {
-                        let positions: Vec<Vector2i> = (0..match_len)
-                            .map(|i| Vector2i::new((x + i) as i32, y as i32))
-                            .collect();
-
-                        matches.push(Match {
-                            positions,
-                            horizontal: true,
-                        });
-                    }
-
-                    x += match_len;
-                } else {
-                    x += 1;
-                }
+                matches.push(Match {
+                    positions,
+                    horizontal: true,
+                    shape: MatchShape::Line,
+                });
             }
         }
 
@@ -89,52 +107,119 @@ impl MatchFinder {
         let mut matches = Vec::new();
 
         for x in 0..grid.width {
-            let mut y = 0;
-            while y < grid.height {
-                if let Some(symbol) = grid.get(x, y) {
-                    let symbol_type = symbol.current_type();
-                    let mut match_len = 1;
-
-                    // Count consecutive symbols of the same type
-                    while y + match_len < grid.height {
-                        if let Some(next) = grid.get(x, y + match_len) {
-                            if next.current_type() == symbol_type {
-                                match_len += 1;
-                            } else {
-                                break;
-                            }
-                        } else {
-                            break;
-                        }
-                    }
+            for (y, match_len) in Self::find_runs(grid.height, |y| grid.matchable(x, y)) {
+                let positions: Vec<Vector2i> = (0..match_len)
+                    .map(|i| Vector2i::new(x as i32, (y + i) as i32))
+                    .collect();
 
-                    // If we found a match of 3+
-                    if match_len >= 3 {
-                        let positions: Vec<Vector2i> = (0..match_len)
-                            .map(|i| Vector2i::new(x as i32, (y + i) as i32))
-                            .collect();
+                matches.push(Match {
+                    positions,
+                    horizontal: false,
+                    shape: MatchShape::Line,
+                });
+            }
+        }
 
-                        matches.push(Match {
-                            positions,
-                            horizontal: false,
-                        });
-                    }
+        matches
+    }
 
-                    y += match_len;
-                } else {
-                    y += 1;
+    /// Find the runs of 3+ along a line of `len` cells, as (start, length)
+    /// The symbols in a run share a type, except wilds, which fit any; wilds between two colors
+    /// extend the runs on both sides of them. A run needs at least one symbol that isn't wild
+    fn find_runs<'a>(len: usize, cell: impl Fn(usize) -> Option<&'a Symbol>) -> Vec<(usize, usize)> {
+        let mut runs = Vec::new();
+        let mut start = 0;
+        while start < len {
+            let mut run_type = None;
+            let mut end = start;
+            // Where the wilds closing the run begin; the next run can start there
+            let mut trailing_wilds = start;
+            while let Some(symbol) = (end < len).then(|| cell(end)).flatten() {
+                if !symbol.wild {
+                    if run_type.is_some_and(|run_type| run_type != symbol.current_type()) {
+                        break;
+                    }
+                    run_type = Some(symbol.current_type());
+                    trailing_wilds = end + 1;
                 }
+                end += 1;
             }
-        }
 
-        matches
+            if end - start >= 3 && run_type.is_some() {
+                runs.push((start, end - start));
+            }
+            start = if trailing_wilds > start && trailing_wilds < end {
+                trailing_wilds
+            } else {
+                end.max(start + 1)
+            };
+        }
+        runs
     }
 
-    /// Find all matches (horizontal and vertical)
+    /// Find all matches: horizontal and vertical runs, with runs that cross merged into one
+    /// L, T or cross
     pub fn find_all(grid: &Grid) -> Vec<Match> {
-        let mut matches = Self::find_horizontal(grid);
-        matches.extend(Self::find_vertical(grid));
-        matches
+        let mut runs = Self::find_horizontal(grid);
+        runs.extend(Self::find_vertical(grid));
+        Self::merge_shapes(runs)
+    }
+
+    /// Merge every group of runs crossing each other into one match; a group of more than two runs
+    /// takes the shape of its strongest crossing. Runs along the same line that share a wild stay
+    /// separate lines, as they hold different colors
+    pub fn merge_shapes(runs: Vec<Match>) -> Vec<Match> {
+        // Each group keeps the runs it was built from, to tell crossings from runs along the same line
+        let mut merged: Vec<(Match, Vec<Match>)> = Vec::new();
+        for run in runs {
+            let mut group = run.clone();
+            let mut parts = vec![run];
+            // A run can bridge several groups (a column through two rows), so fold in every one it crosses
+            let mut idx = 0;
+            while idx < merged.len() {
+                let meets = |crossing: bool| {
+                    merged[idx].1.iter().any(|other| {
+                        parts.iter().any(|part| {
+                            (part.horizontal != other.horizontal) == crossing
+                                && part.positions.iter().any(|pos| other.positions.contains(pos))
+                        })
+                    })
+                };
+                if !meets(true) || meets(false) {
+                    idx += 1;
+                    continue;
+                }
+                let (mut other, other_runs) = merged.remove(idx);
+                let shape = Self::crossing_shape(&other, &group).max_by_strength(group.shape);
+                other.shape = other.shape.max_by_strength(shape);
+                other.horizontal = false;
+                for pos in group.positions {
+                    if !other.positions.contains(&pos) {
+                        other.positions.push(pos);
+                    }
+                }
+                group = other;
+                parts.extend(other_runs);
+            }
+            merged.push((group, parts));
+        }
+        merged.into_iter().map(|(group, _)| group).collect()
+    }
+
+    /// Get the shape a run (or a group it already joined) makes with a match it crosses, by where
+    /// the shared cell sits in each
+    fn crossing_shape(group: &Match, run: &Match) -> MatchShape {
+        let Some(shared) = run.positions.iter().find(|pos| group.positions.contains(pos)) else {
+            return group.shape;
+        };
+        let at_end = |positions: &[Vector2i]| positions.first() == Some(shared) || positions.last() == Some(shared);
+        // A group that is already a shape counts its shared cell as a middle
+        let group_end = group.shape == MatchShape::Line && at_end(&group.positions);
+        match (group_end, at_end(&run.positions)) {
+            (true, true) => MatchShape::L,
+            (false, false) => MatchShape::Cross,
+            _ => MatchShape::T,
+        }
     }
 
     /// Get all unique positions that are part of any match
@@ -150,8 +235,13 @@ impl MatchFinder {
         positions
     }
 
-    /// Check if swapping two positions would create a match
+    /// Check if swapping two positions would create a match; wilds match any color, and
+    /// swapping two wilds changes nothing. Frozen symbols can't be swapped
     pub fn would_create_match(grid: &Grid, pos1: Vector2i, pos2: Vector2i) -> bool {
+        if [pos1, pos2].iter().any(|pos| grid.is_frozen(pos.x as usize, pos.y as usize)) {
+            return false;
+        }
+
         // Create a temporary grid with swapped positions
         let mut temp_grid = grid.clone();
 
@@ -159,6 +249,10 @@ impl MatchFinder {
         let symbol2 = temp_grid.take(pos2.x as usize, pos2.y as usize);
 
         if let (Some(mut s1), Some(mut s2)) = (symbol1, symbol2) {
+            if s1.blocker || s2.blocker || (s1.wild && s2.wild) {
+                return false;
+            }
+
             // Update grid positions
             s1.grid_pos = pos2;
             s2.grid_pos = pos1;
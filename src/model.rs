@@ -0,0 +1,1206 @@
+//! Board model - grid, rules, scoring and randomness, independent of any scene
+//!
+//! The model never touches nodes or tweens. Each step of a move (swap, clear,
+//! gravity, refill, rotation) is a separate call so a view can animate between
+//! them, and notifications are queued as `ModelEvent`s for the owner to drain.
+
+use crate::chain::{Chain, ChainCause, ChainRecorder, ChainSpecial, StepSpecials};
+use crate::chocolate;
+use crate::combo::{ComboEffect, ComboMatrix, SpecialKind};
+use crate::combo_timer::ComboTimer;
+use crate::edits::{BoardEdit, Special};
+use crate::error::{BoardError, SwapRejection};
+use crate::expiry;
+use crate::gravity::{self, GravityWell};
+use crate::ice;
+use crate::jelly::JellyLayer;
+use crate::matching::{Match, MatchFinder, MatchShape};
+use crate::multipliers::CellMultipliers;
+use crate::onboarding::{self, Onboarding};
+use crate::pity::{self, PityMeter};
+use crate::refill::{self, RefillPolicy, SpawnGuarantee, SpawnQueue};
+use crate::rng;
+use crate::rules::{ClassicRevolvingRules, Ruleset};
+use crate::scoring::{self, ScoreBreakdown};
+use crate::seeker;
+use crate::stones;
+use crate::symbols::{self, CellOverlay, Grid, Symbol, SymbolKind, SymbolTags, SymbolType};
+use godot::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+/// Notifications produced by the model
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModelEvent {
+    /// Matches were found and scored
+    MatchFound { count: i32 },
+    /// The score changed
+    ScoreChanged(i32),
+    /// A symbol was removed by a match, with the tags it carried
+    SymbolCleared {
+        pos: Vector2i,
+        symbol_type: SymbolType,
+        tags: SymbolTags,
+    },
+    /// A cascade ended; what set it off and what each step matched and cleared
+    ChainResolved(Chain),
+    /// A match used up the score multiplier on a cell
+    MultiplierConsumed(Vector2i),
+    /// A symbol sat unmatched too long and turned into a blocker
+    SymbolExpired(Vector2i),
+    /// An L, T or cross shaped match left a seeker behind
+    SeekerCreated(Vector2i),
+    /// A cleared seeker flew to another cell and cleared it
+    SeekerLaunched { from: Vector2i, to: Vector2i },
+    /// A four-long match left a striped symbol behind
+    StripedCreated { pos: Vector2i, kind: SymbolKind },
+    /// A cleared striped symbol cleared its row or column
+    StripeFired { pos: Vector2i, kind: SymbolKind },
+    /// A match of five or more left a color bomb behind
+    ColorBombCreated(Vector2i),
+    /// A swapped color bomb cleared every symbol of a type
+    ColorBombFired { pos: Vector2i, symbol_type: SymbolType },
+    /// Two specials swapped into each other went off together, centered on `at`, clearing `cells`
+    ComboTriggered {
+        at: Vector2i,
+        effect: ComboEffect,
+        cells: Vec<Vector2i>,
+    },
+    /// A refill placed a symbol setting up a big match, after too many turns without one
+    PityNudge(Vector2i),
+    /// A match next to a stone took a hit point off it, leaving `hp`
+    StoneHit { pos: Vector2i, hp: u32 },
+    /// A stone lost its last hit point, emptying its cell
+    StoneBroken(Vector2i),
+    /// A match through a frozen cell cracked a layer off its ice, leaving `layers`
+    IceCracked { pos: Vector2i, layers: u32 },
+    /// A frozen cell's ice broke, freeing its symbol
+    IceBroken(Vector2i),
+    /// A match cleared the jelly from a cell, leaving `remaining` jelly cells
+    JellyCleared { pos: Vector2i, remaining: i32 },
+    /// A match next to chocolate ate it, emptying its cell
+    ChocolateEaten(Vector2i),
+    /// Chocolate grew into a cell, swallowing any symbol there
+    ChocolateSpread(Vector2i),
+    /// An ingredient left the board through the bottom row, leaving `remaining` ingredients on the board
+    IngredientCollected { pos: Vector2i, remaining: i32 },
+}
+
+/// A symbol moved down by gravity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fall {
+    pub from: Vector2i,
+    pub to: Vector2i,
+    /// Cells where the path changes direction before `to`; empty for a straight fall
+    pub bends: Vec<Vector2i>,
+}
+
+/// A symbol created by refill
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spawn {
+    pub pos: Vector2i,
+    pub symbol_type: SymbolType,
+}
+
+/// The logical state of a board
+#[derive(Debug, Clone)]
+pub struct BoardModel {
+    grid: Grid,
+    rules: Arc<dyn Ruleset>,
+    combos: ComboMatrix,
+    rng: StdRng,
+    seed: i64,
+    refill_policy: RefillPolicy,
+    spawn_queue: SpawnQueue,
+    guarantee: Option<SpawnGuarantee>,
+    held: Option<Symbol>,
+    pending_combos: Vec<(ComboEffect, Vector2i, Vector2i)>,
+    pending_bombs: Vec<(Vector2i, SymbolType)>,
+    favored: Option<(SymbolType, f64)>,
+    wells: Vec<GravityWell>,
+    overlays: Vec<(Vector2i, CellOverlay)>,
+    holes: Vec<Vector2i>,
+    multipliers: CellMultipliers,
+    jelly: JellyLayer,
+    expiry: u32,
+    objective: Option<SymbolType>,
+    score: i32,
+    breakdown: ScoreBreakdown,
+    combo: i32,
+    combo_timer: Option<ComboTimer>,
+    cascade_depth: i32,
+    scoring: bool,
+    score_multiplier: i32,
+    chain: ChainRecorder,
+    pity: PityMeter,
+    events: Vec<ModelEvent>,
+}
+
+impl BoardModel {
+    /// Create an empty model with the classic rules, seeded from entropy
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_rules(width, height, Arc::new(ClassicRevolvingRules))
+    }
+
+    /// Create an empty model playing a given variant, seeded from entropy
+    pub fn with_rules(width: usize, height: usize, rules: Arc<dyn Ruleset>) -> Self {
+        Self {
+            grid: Grid::new(width, height),
+            combos: rules.special_combos(),
+            combo_timer: ComboTimer::for_mode(rules.combo_mode()),
+            rules,
+            rng: rng::from_entropy(),
+            seed: -1,
+            refill_policy: RefillPolicy::default(),
+            spawn_queue: SpawnQueue::default(),
+            guarantee: None,
+            held: None,
+            pending_combos: Vec::new(),
+            pending_bombs: Vec::new(),
+            favored: None,
+            wells: Vec::new(),
+            overlays: Vec::new(),
+            holes: Vec::new(),
+            multipliers: CellMultipliers::default(),
+            jelly: JellyLayer::default(),
+            expiry: 0,
+            objective: None,
+            score: 0,
+            breakdown: ScoreBreakdown::default(),
+            combo: 1,
+            cascade_depth: 0,
+            scoring: true,
+            score_multiplier: 1,
+            chain: ChainRecorder::default(),
+            pity: PityMeter::default(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Get the grid
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// Check if the symbol at a position expired into a blocker
+    pub fn is_blocker(&self, pos: Vector2i) -> bool {
+        self.grid.get(pos.x as usize, pos.y as usize).is_some_and(|symbol| symbol.blocker)
+    }
+
+    /// Get the current type of the symbol at a position, if any
+    pub fn symbol_type_at(&self, pos: Vector2i) -> Option<SymbolType> {
+        let idx = self.grid.checked_index(pos).ok()?;
+        self.grid.cells[idx].as_ref().map(|symbol| symbol.current_type())
+    }
+
+    /// Get the active ruleset
+    pub fn rules(&self) -> &dyn Ruleset {
+        self.rules.as_ref()
+    }
+
+    /// Switch to another variant's rules; the grid is kept
+    /// The special combos are reset to the ones the new rules define
+    pub fn set_rules(&mut self, rules: Arc<dyn Ruleset>) {
+        self.combos = rules.special_combos();
+        self.combo_timer = ComboTimer::for_mode(rules.combo_mode());
+        self.rules = rules;
+        self.reset_spawn_queue();
+    }
+
+    /// Set or remove what a pair of specials does when swapped into each other, until the rules change
+    pub fn set_special_combo(&mut self, a: SpecialKind, b: SpecialKind, effect: Option<ComboEffect>) {
+        self.combos.set(a, b, effect);
+    }
+
+    /// Set the turns without a big match before a refill sets one up (0 turns pity off)
+    pub fn set_pity_threshold(&mut self, threshold: u32) {
+        self.pity.set_threshold(threshold);
+    }
+
+    /// Get the turns since the last big match or pity nudge
+    pub fn pity_turns(&self) -> u32 {
+        self.pity.turns()
+    }
+
+    /// Get the score
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+
+    /// Get the points scored per source
+    pub fn breakdown(&self) -> &ScoreBreakdown {
+        &self.breakdown
+    }
+
+    /// Get the combo multiplier applied to the next match
+    pub fn combo(&self) -> i32 {
+        self.combo
+    }
+
+    /// Get the share of the combo window left, if the rules time combos
+    pub fn combo_window(&self) -> Option<f64> {
+        self.combo_timer.map(|timer| timer.fraction())
+    }
+
+    /// Drain the combo window by `delta` seconds between moves, dropping the combo when it closes
+    /// Returns the share of the window left, if the rules time combos and the window was open
+    pub fn tick_combo_window(&mut self, delta: f64) -> Option<f64> {
+        if self.cascade_depth > 0 {
+            return None;
+        }
+        let timer = self.combo_timer.as_mut().filter(|timer| timer.is_running())?;
+        if timer.advance(delta) {
+            self.combo = 1;
+        }
+        Some(timer.fraction())
+    }
+
+    /// Get the number of match resolutions in the current cascade
+    pub fn cascade_depth(&self) -> i32 {
+        self.cascade_depth
+    }
+
+    /// Get the last seed set (-1 when seeded from entropy)
+    pub fn seed(&self) -> i64 {
+        self.seed
+    }
+
+    /// Reseed the random source
+    pub fn set_seed(&mut self, seed: i64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed as u64);
+        self.reset_spawn_queue();
+    }
+
+    /// Set the gravity wells that bend fall paths
+    pub fn set_gravity_wells(&mut self, wells: Vec<GravityWell>) {
+        self.wells = wells;
+    }
+
+    /// Set the overlays, such as stones and ice, placed whenever the board is rebuilt
+    pub fn set_overlays(&mut self, overlays: Vec<(Vector2i, CellOverlay)>) {
+        self.overlays = overlays;
+    }
+
+    /// Set the cells left out of the board as holes whenever it is rebuilt
+    pub fn set_holes(&mut self, holes: Vec<Vector2i>) {
+        self.holes = holes;
+    }
+
+    /// Get the score multipliers painted on cells
+    pub fn cell_multipliers(&self) -> &CellMultipliers {
+        &self.multipliers
+    }
+
+    /// Set the score multipliers painted on cells
+    pub fn set_cell_multipliers(&mut self, multipliers: CellMultipliers) {
+        self.multipliers = multipliers;
+    }
+
+    /// Get the jelly left on cells
+    pub fn jelly(&self) -> &JellyLayer {
+        &self.jelly
+    }
+
+    /// Set the jelly painted on cells
+    pub fn set_jelly(&mut self, jelly: JellyLayer) {
+        self.jelly = jelly;
+    }
+
+    /// Get the rotations a symbol survives unmatched before it turns into a blocker (0 for never)
+    pub fn expiry(&self) -> u32 {
+        self.expiry
+    }
+
+    /// Set the rotations a symbol survives unmatched before it turns into a blocker (0 for never)
+    pub fn set_expiry(&mut self, rotations: u32) {
+        self.expiry = rotations;
+    }
+
+    /// Set the symbol type the objectives most need, which seekers aim for
+    pub fn set_objective(&mut self, objective: Option<SymbolType>) {
+        self.objective = objective;
+    }
+
+    /// Make refill spawn a symbol type with the given probability, or stop favoring one
+    pub fn set_favored_symbol(&mut self, favored: Option<(SymbolType, f64)>) {
+        self.favored = favored;
+    }
+
+    /// Put a floor on how often refill spawns a symbol type, or remove it
+    /// Symbols already queued keep their type
+    pub fn set_spawn_guarantee(&mut self, guarantee: Option<SpawnGuarantee>) {
+        self.guarantee = guarantee;
+    }
+
+    /// Choose how refill picks symbol types
+    pub fn set_refill_policy(&mut self, policy: RefillPolicy) {
+        self.refill_policy = policy;
+        self.reset_spawn_queue();
+    }
+
+    /// Get the types of the next `count` symbols a column will spawn, in spawn order
+    pub fn spawn_preview(&mut self, column: usize, count: usize) -> Vec<SymbolType> {
+        let count = count.min(refill::MAX_PREVIEW);
+        self.queue_spawns(column, count);
+        self.spawn_queue
+            .column_mut(column)
+            .map(|queue| queue.upcoming.iter().take(count).map(Symbol::current_type).collect())
+            .unwrap_or_default()
+    }
+
+    /// Drop every queued spawn and reseed the column generators from the model's
+    fn reset_spawn_queue(&mut self) {
+        self.spawn_queue.reset(self.grid.width, &mut self.rng);
+    }
+
+    /// Make sure a column has at least `count` spawns queued
+    /// A frenzy's favored type and the spawn guarantee are decided here, so queued symbols keep their type
+    fn queue_spawns(&mut self, column: usize, count: usize) {
+        if self.spawn_queue.width() != self.grid.width {
+            self.reset_spawn_queue();
+        }
+        let Some(queue) = self.spawn_queue.column_mut(column) else {
+            return;
+        };
+        let pos = Vector2i::new(column as i32, 0);
+        while queue.upcoming.len() < count {
+            let favored = self
+                .favored
+                .filter(|(_, chance)| queue.rng.gen_bool(chance.clamp(0.0, 1.0)));
+            let symbol = match (favored, self.refill_policy) {
+                (Some((symbol_type, _)), _) => Symbol::with_type(pos, symbol_type),
+                (None, RefillPolicy::Random) => self.rules.spawn_symbol(pos, &mut queue.rng),
+                (None, RefillPolicy::Bag) => Symbol::with_type(pos, queue.bag.draw(&mut queue.rng)),
+            };
+            queue.push(symbol, self.guarantee);
+        }
+    }
+
+    /// Take all queued events
+    pub fn drain_events(&mut self) -> Vec<ModelEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Replace the grid with a random fill of the given size plus preplaced symbols
+    pub fn rebuild(&mut self, width: usize, height: usize, preplaced: &[(usize, usize, SymbolType)]) {
+        self.grid = Grid::new(width, height);
+        for &pos in &self.holes {
+            let _ = self.grid.set_enabled(pos, false);
+        }
+        self.grid.fill_random(&mut self.rng);
+
+        for &(x, y, symbol_type) in preplaced {
+            self.grid.set(x, y, Some(Symbol::with_type(Vector2i::new(x as i32, y as i32), symbol_type)));
+        }
+        for &(pos, overlay) in &self.overlays {
+            let _ = self.grid.set_overlay(pos, Some(overlay));
+        }
+        self.held = None;
+        self.pity.reset();
+        self.reset_spawn_queue();
+    }
+
+    /// Replace the grid as-is
+    pub fn load_grid(&mut self, grid: Grid) {
+        self.grid = grid;
+        self.held = None;
+        self.reset_spawn_queue();
+    }
+
+    /// Replace the grid with a first-play onboarding board of the current size and holes
+    pub fn generate_onboarding(&mut self) -> Option<Onboarding> {
+        let onboarding = onboarding::generate(self.grid.width, self.grid.height, &self.holes, &mut self.rng)?;
+        self.grid = onboarding.grid.clone();
+        self.held = None;
+        self.reset_spawn_queue();
+        Some(onboarding)
+    }
+
+    /// Add points from a source, unless scoring is disabled
+    pub fn add_score(&mut self, amount: i32, source: &str) {
+        if !self.scoring {
+            return;
+        }
+        self.score += amount;
+        self.breakdown.add(source, amount);
+        self.events.push(ModelEvent::ScoreChanged(self.score));
+    }
+
+    /// Go back to an earlier state of this model, such as a rewind snapshot
+    /// Undrained events and the settings the board and modifiers own (scoring, multiplier,
+    /// favored type and expiry) are kept
+    pub fn restore(&mut self, earlier: BoardModel) {
+        let current = std::mem::replace(self, earlier);
+        self.events = current.events;
+        self.scoring = current.scoring;
+        self.score_multiplier = current.score_multiplier;
+        self.favored = current.favored;
+        self.expiry = current.expiry;
+        self.events.push(ModelEvent::ScoreChanged(self.score));
+    }
+
+    /// Reset score, breakdown and combo
+    pub fn reset_scoring(&mut self) {
+        self.score = 0;
+        self.breakdown.clear();
+        self.combo = 1;
+        if let Some(timer) = self.combo_timer.as_mut() {
+            timer.reset();
+        }
+        self.cascade_depth = 0;
+        self.events.push(ModelEvent::ScoreChanged(0));
+    }
+
+    /// Multiply the points of every match from now on
+    pub fn set_score_multiplier(&mut self, multiplier: i32) {
+        self.score_multiplier = multiplier;
+    }
+
+    /// Enable or disable scoring; matches still clear while disabled
+    pub fn set_scoring(&mut self, enabled: bool) {
+        self.scoring = enabled;
+    }
+
+    /// Replace the symbol at a position
+    pub fn set_cell(&mut self, pos: Vector2i, symbol_type: SymbolType) -> Result<(), BoardError> {
+        self.grid.try_set(pos, Some(Symbol::with_type(pos, symbol_type)))
+    }
+
+    /// Turn the symbol at a position into a seeker
+    pub fn set_seeker(&mut self, pos: Vector2i) -> Result<(), BoardError> {
+        let idx = self.grid.checked_index(pos)?;
+        let symbol = self.grid.cells[idx].as_mut().ok_or(BoardError::EmptyCell(pos))?;
+        symbol.seeker = true;
+        Ok(())
+    }
+
+    /// Make the symbol at a position wild, so it matches any type
+    pub fn set_wild(&mut self, pos: Vector2i) -> Result<(), BoardError> {
+        let idx = self.grid.checked_index(pos)?;
+        let symbol = self.grid.cells[idx].as_mut().ok_or(BoardError::EmptyCell(pos))?;
+        symbol.wild = true;
+        Ok(())
+    }
+
+    /// Get the tags of the symbol at a position
+    pub fn tags(&self, pos: Vector2i) -> Result<&SymbolTags, BoardError> {
+        let idx = self.grid.checked_index(pos)?;
+        let symbol = self.grid.cells[idx].as_ref().ok_or(BoardError::EmptyCell(pos))?;
+        Ok(&symbol.tags)
+    }
+
+    /// Set a tag on the symbol at a position, or remove it when `value` is None
+    pub fn set_tag(&mut self, pos: Vector2i, key: &str, value: Option<String>) -> Result<(), BoardError> {
+        let idx = self.grid.checked_index(pos)?;
+        let symbol = self.grid.cells[idx].as_mut().ok_or(BoardError::EmptyCell(pos))?;
+        match value {
+            Some(value) => symbol.tags.insert(key.to_string(), value),
+            None => symbol.tags.remove(key),
+        };
+        Ok(())
+    }
+
+    /// Give the symbol at a position a kind, e.g. make it striped
+    pub fn set_kind(&mut self, pos: Vector2i, kind: SymbolKind) -> Result<(), BoardError> {
+        let idx = self.grid.checked_index(pos)?;
+        let symbol = self.grid.cells[idx].as_mut().ok_or(BoardError::EmptyCell(pos))?;
+        symbol.kind = kind;
+        Ok(())
+    }
+
+    /// Apply a batch of scripted edits, all or nothing, returning the cells cleared
+    /// Cleared symbols are announced but not scored; the first edit that fails leaves the board as it was
+    pub fn apply_edits(&mut self, edits: &[BoardEdit]) -> Result<Vec<Vector2i>, BoardError> {
+        let mut edited = self.clone();
+        let mut cleared = Vec::new();
+        for edit in edits {
+            match edit {
+                BoardEdit::Set { pos, symbol_type } => {
+                    edited.grid.checked_index(*pos)?;
+                    if edited.grid.is_filled(pos.x as usize, pos.y as usize) {
+                        return Err(BoardError::CellBlocked(*pos));
+                    }
+                    edited.set_cell(*pos, *symbol_type)?;
+                    cleared.retain(|cell| cell != pos);
+                }
+                BoardEdit::Special { pos, special } => match special {
+                    Special::Seeker => edited.set_seeker(*pos)?,
+                    Special::Wild => edited.set_wild(*pos)?,
+                    Special::Kind(kind) => edited.set_kind(*pos, *kind)?,
+                },
+                BoardEdit::Obstacle { pos, overlay } => {
+                    let fills = overlay.is_some_and(|overlay| overlay.fills_cell());
+                    if fills && edited.grid.get(pos.x as usize, pos.y as usize).is_some() {
+                        edited.take_symbols(&[*pos]);
+                        cleared.push(*pos);
+                    }
+                    edited.grid.set_overlay(*pos, *overlay)?;
+                }
+                BoardEdit::Clear(cells) => {
+                    for pos in cells {
+                        edited.grid.checked_index(*pos)?;
+                    }
+                    let taken: Vec<Vector2i> = cells
+                        .iter()
+                        .copied()
+                        .filter(|pos| edited.grid.get(pos.x as usize, pos.y as usize).is_some())
+                        .collect();
+                    edited.take_symbols(&taken);
+                    cleared.extend(taken);
+                }
+            }
+        }
+        *self = edited;
+        Ok(cleared)
+    }
+
+    /// Check if two positions are adjacent
+    pub fn is_adjacent(pos1: Vector2i, pos2: Vector2i) -> bool {
+        let dx = (pos1.x - pos2.x).abs();
+        let dy = (pos1.y - pos2.y).abs();
+        (dx == 1 && dy == 0) || (dx == 0 && dy == 1)
+    }
+
+    /// Check if swapping two positions is a legal move, and why not if it isn't
+    pub fn check_swap(&self, pos1: Vector2i, pos2: Vector2i) -> Result<(), SwapRejection> {
+        self.check_move(pos1, pos2)?;
+        let combo = self.combos.effect_at(&self.grid, pos1, pos2);
+        let bomb = self.color_bomb_swap(pos1, pos2);
+        if combo.is_none() && bomb.is_none() && !self.rules.would_create_match(&self.grid, pos1, pos2) {
+            return Err(SwapRejection::NoMatch);
+        }
+        Ok(())
+    }
+
+    /// Check if two positions could be swapped at all, whether or not the swap would match
+    fn check_move(&self, pos1: Vector2i, pos2: Vector2i) -> Result<(), SwapRejection> {
+        if !self.rules.swaps() {
+            return Err(SwapRejection::SwapsDisabled);
+        }
+        if !Self::is_adjacent(pos1, pos2) {
+            return Err(SwapRejection::NotAdjacent);
+        }
+        let locked = |pos: &Vector2i| {
+            let (x, y) = (pos.x as usize, pos.y as usize);
+            self.is_blocker(*pos) || self.grid.is_filled(x, y) || self.grid.is_frozen(x, y)
+        };
+        if let Some(pos) = [pos1, pos2].into_iter().find(locked) {
+            return Err(SwapRejection::CellLocked(pos));
+        }
+        Ok(())
+    }
+
+    /// Check if swapping two positions is a legal move
+    pub fn can_swap(&self, pos1: Vector2i, pos2: Vector2i) -> bool {
+        self.check_swap(pos1, pos2).is_ok()
+    }
+
+    /// Swap two symbols without checking for a match
+    /// Starts a new chain for the cascade that follows; two specials that combine, or a color bomb and
+    /// the symbol it was swapped with, go off with its first resolution
+    pub fn swap(&mut self, pos1: Vector2i, pos2: Vector2i) -> Result<(), BoardError> {
+        self.grid.swap(pos1, pos2)?;
+        self.pending_combos.clear();
+        self.pending_bombs.clear();
+        self.arm_specials(pos1, pos2);
+        self.chain.begin(ChainCause::Swap(pos1, pos2));
+        Ok(())
+    }
+
+    /// Arm the combo or color bomb two just swapped cells set off with the next resolution, if any
+    fn arm_specials(&mut self, pos1: Vector2i, pos2: Vector2i) {
+        if let Some(effect) = self.combos.effect_at(&self.grid, pos1, pos2) {
+            self.pending_combos.push((effect, pos2, pos1));
+        } else if let Some(bomb) = self.color_bomb_swap(pos1, pos2) {
+            self.pending_bombs.push(bomb);
+        }
+    }
+
+    /// Get the color bomb among two cells and the type it would clear, if one is swapped with a matchable symbol
+    fn color_bomb_swap(&self, pos1: Vector2i, pos2: Vector2i) -> Option<(Vector2i, SymbolType)> {
+        let is_bomb = |pos: Vector2i| {
+            self.grid.get(pos.x as usize, pos.y as usize).is_some_and(|symbol| symbol.kind == SymbolKind::ColorBomb)
+        };
+        [(pos1, pos2), (pos2, pos1)].into_iter().find_map(|(bomb, other)| {
+            let target = self.grid.matchable(other.x as usize, other.y as usize)?;
+            is_bomb(bomb).then(|| (bomb, target.current_type()))
+        })
+    }
+
+    /// Get a cell's mirror image across the board's vertical center line
+    pub fn mirror(&self, pos: Vector2i) -> Vector2i {
+        Vector2i::new(self.grid.width as i32 - 1 - pos.x, pos.y)
+    }
+
+    /// Repeat a swap on the mirrored cells, as part of the current chain
+    /// The mirrored swap is checked like any other but needn't make a match of its own; specials it
+    /// combines, or a color bomb it swaps, go off with the first resolution like the swap's own
+    /// Returns the mirrored cells, or None when the swap is its own mirror image
+    pub fn swap_mirrored(
+        &mut self,
+        pos1: Vector2i,
+        pos2: Vector2i,
+    ) -> Result<Option<(Vector2i, Vector2i)>, SwapRejection> {
+        let (mirror1, mirror2) = (self.mirror(pos1), self.mirror(pos2));
+        if (mirror1 == pos1 && mirror2 == pos2) || (mirror1 == pos2 && mirror2 == pos1) {
+            return Ok(None);
+        }
+        self.check_move(mirror1, mirror2)?;
+        self.grid
+            .swap(mirror1, mirror2)
+            .map_err(|err| SwapRejection::CellLocked(err.position().unwrap_or(mirror1)))?;
+        self.arm_specials(mirror1, mirror2);
+        self.chain.record_moves([mirror1, mirror2]);
+        Ok(Some((mirror1, mirror2)))
+    }
+
+    /// Check if the grid currently has any match
+    pub fn has_matches(&self) -> bool {
+        !self.pending_combos.is_empty()
+            || !self.pending_bombs.is_empty()
+            || !self.rules.find_matches(&self.grid).is_empty()
+    }
+
+    /// End a cascade chain, resetting the depth and, unless the rules time combos, the combo multiplier
+    pub fn end_cascade(&mut self) {
+        if self.combo_timer.is_none() {
+            self.combo = 1;
+        }
+        self.cascade_depth = 0;
+        let cause = self.chain.cause();
+        let chain = self.chain.finish();
+        if let Some(cause) = cause {
+            self.pity.record(cause, chain.as_ref());
+        }
+        if let Some(chain) = chain {
+            self.events.push(ModelEvent::ChainResolved(chain));
+        }
+    }
+
+    /// Score and remove all current matches, returning the cleared positions
+    /// Each call in the same cascade raises the combo multiplier
+    pub fn resolve_matches(&mut self) -> Vec<Vector2i> {
+        let matches = self.rules.find_matches(&self.grid);
+        let first_event = self.events.len();
+        let mut blasts = self.take_combo_blasts();
+        blasts.extend(self.take_color_bombs());
+        if matches.is_empty() && blasts.is_empty() {
+            return Vec::new();
+        }
+
+        // Only the first resolution after a swap can leave a special in a swapped cell
+        let swapped = match self.chain.cause() {
+            Some(ChainCause::Swap(pos1, pos2)) if self.cascade_depth == 0 => vec![pos2, pos1],
+            _ => Vec::new(),
+        };
+
+        // Combo blasts and color bombs each score as one more match
+        let mut positions = MatchFinder::get_matched_positions(&matches);
+        let mut scored = matches.clone();
+        for blast in blasts {
+            for pos in &blast.positions {
+                if !positions.contains(pos) {
+                    positions.push(*pos);
+                }
+            }
+            scored.push(blast);
+        }
+        self.score_matches(&scored);
+        self.fire_specials(&mut positions);
+        for m in &matches {
+            self.leave_special(m, &swapped, &mut positions);
+        }
+
+        let chain_matches = matches
+            .into_iter()
+            .filter_map(|m| match_type(&self.grid, &m).map(|symbol_type| (m.positions, symbol_type)))
+            .collect();
+        positions.extend(expiry::adjacent_blockers(&self.grid, &positions));
+        self.spare_ingredients(&mut positions);
+        self.hit_stones(&positions);
+        self.eat_chocolate(&positions);
+        self.crack_ice(&mut positions);
+        self.clear_jelly(&positions);
+        let specials = special_events(&self.events[first_event..]);
+        self.chain.record_step(chain_matches, positions.clone(), specials);
+
+        self.take_symbols(&positions);
+        positions
+    }
+
+    /// Keep the ingredients among the cells about to be cleared on the board; they only leave through the bottom row
+    fn spare_ingredients(&self, cleared: &mut Vec<Vector2i>) {
+        cleared.retain(|pos| !self.is_ingredient(*pos));
+    }
+
+    /// Check if a cell holds an ingredient
+    fn is_ingredient(&self, pos: Vector2i) -> bool {
+        let symbol = self.grid.get(pos.x as usize, pos.y as usize);
+        symbol.is_some_and(|symbol| symbol.kind == SymbolKind::Ingredient)
+    }
+
+    /// Get the ingredients in the lowest playable cell of their column, ready to leave the board;
+    /// ice holds them back until it breaks
+    fn exiting_ingredients(&self) -> Vec<Vector2i> {
+        (0..self.grid.width)
+            .filter_map(|x| (0..self.grid.height).rev().find(|y| self.grid.is_enabled(x, *y)).map(|y| (x, y)))
+            .map(|(x, y)| Vector2i::new(x as i32, y as i32))
+            .filter(|pos| self.is_ingredient(*pos) && !self.grid.is_frozen(pos.x as usize, pos.y as usize))
+            .collect()
+    }
+
+    /// Check if any ingredient is waiting to leave the board, so the board must settle again
+    pub fn has_exiting_ingredients(&self) -> bool {
+        !self.exiting_ingredients().is_empty()
+    }
+
+    /// Count the ingredients on the board
+    pub fn ingredients_left(&self) -> usize {
+        self.grid.cells.iter().flatten().filter(|symbol| symbol.kind == SymbolKind::Ingredient).count()
+    }
+
+    /// Chip the stones next to the cleared cells, reporting each hit and break
+    fn hit_stones(&mut self, cleared: &[Vector2i]) {
+        for (pos, hp) in stones::hit_adjacent(&mut self.grid, cleared) {
+            self.events.push(if hp > 0 { ModelEvent::StoneHit { pos, hp } } else { ModelEvent::StoneBroken(pos) });
+        }
+    }
+
+    /// Eat the chocolate next to the cleared cells, reporting each cell
+    fn eat_chocolate(&mut self, cleared: &[Vector2i]) {
+        for pos in chocolate::eat_adjacent(&mut self.grid, cleared) {
+            self.events.push(ModelEvent::ChocolateEaten(pos));
+        }
+    }
+
+    /// Grow chocolate into a neighboring cell, after a move that ate none
+    /// Returns the cell it took, if it had room to grow
+    pub fn spread_chocolate(&mut self) -> Option<Vector2i> {
+        let pos = chocolate::spread(&mut self.grid, &mut self.rng)?;
+        self.events.push(ModelEvent::ChocolateSpread(pos));
+        Some(pos)
+    }
+
+    /// Crack the ice over any cleared cells, leaving their symbols in place, reporting each crack and break
+    fn crack_ice(&mut self, cleared: &mut Vec<Vector2i>) {
+        for (pos, layers) in ice::crack(&mut self.grid, cleared) {
+            self.events.push(if layers > 0 {
+                ModelEvent::IceCracked { pos, layers }
+            } else {
+                ModelEvent::IceBroken(pos)
+            });
+        }
+    }
+
+    /// Clear the jelly under the cleared cells, reporting each cell with what is left
+    fn clear_jelly(&mut self, cleared: &[Vector2i]) {
+        let emptied = self.jelly.clear(cleared);
+        let mut remaining = (self.jelly.remaining() + emptied.len()) as i32;
+        for pos in emptied {
+            remaining -= 1;
+            self.events.push(ModelEvent::JellyCleared { pos, remaining });
+        }
+    }
+
+    /// Take the cells of the combos set off by the last swap and its mirror image, if any
+    fn take_combo_blasts(&mut self) -> Vec<Match> {
+        std::mem::take(&mut self.pending_combos)
+            .into_iter()
+            .map(|(effect, at, other)| self.combo_blast(effect, at, other))
+            .collect()
+    }
+
+    /// Fire one armed combo, returning the cells it clears
+    /// A convert-and-fire combo turns the plain symbols it takes striped, alternating rows and columns
+    fn combo_blast(&mut self, effect: ComboEffect, at: Vector2i, other: Vector2i) -> Match {
+        let positions = effect.cells(&self.grid, at, other);
+        if effect == ComboEffect::ConvertAndFire {
+            for (i, pos) in positions.iter().enumerate() {
+                let Some(symbol) = self.grid.get_mut(pos.x as usize, pos.y as usize) else {
+                    continue;
+                };
+                if !symbol.seeker && symbol.kind == SymbolKind::Plain {
+                    symbol.kind = if i % 2 == 0 { SymbolKind::StripedRow } else { SymbolKind::StripedColumn };
+                }
+            }
+        }
+        self.events.push(ModelEvent::ComboTriggered {
+            at,
+            effect,
+            cells: positions.clone(),
+        });
+        Match {
+            positions,
+            horizontal: false,
+            shape: MatchShape::Line,
+        }
+    }
+
+    /// Take the cells each color bomb swapped on the last move clears: itself and every symbol of the
+    /// type it was swapped with
+    fn take_color_bombs(&mut self) -> Vec<Match> {
+        std::mem::take(&mut self.pending_bombs)
+            .into_iter()
+            .map(|(at, symbol_type)| {
+                self.events.push(ModelEvent::ColorBombFired { pos: at, symbol_type });
+                let mut positions = vec![at];
+                positions.extend(self.grid.cells_of_type(symbol_type));
+                Match {
+                    positions,
+                    horizontal: false,
+                    shape: MatchShape::Line,
+                }
+            })
+            .collect()
+    }
+
+    /// Keep one cell of a match as the special it earns, taking it off the cleared cells
+    /// L, T and cross shapes leave a seeker; straight matches of four a striped symbol and of five or more a
+    /// color bomb. The special goes in the swapped cell if the match has one, otherwise in its first
+    fn leave_special(&mut self, m: &Match, swapped: &[Vector2i], positions: &mut Vec<Vector2i>) {
+        let seeker = m.shape != MatchShape::Line;
+        let kind = if seeker {
+            SymbolKind::Plain
+        } else if m.len() >= symbols::COLOR_BOMB_MATCH_LEN {
+            SymbolKind::ColorBomb
+        } else if m.len() == symbols::STRIPE_MATCH_LEN {
+            SymbolKind::striped_for(m.horizontal)
+        } else {
+            return;
+        };
+
+        let pos = swapped.iter().copied().find(|pos| m.positions.contains(pos)).unwrap_or(m.positions[0]);
+        let Some(symbol) = self.grid.get_mut(pos.x as usize, pos.y as usize) else {
+            return;
+        };
+        if symbol.seeker || symbol.wild || symbol.kind != SymbolKind::Plain {
+            return;
+        }
+        symbol.seeker = seeker;
+        symbol.kind = kind;
+        positions.retain(|cleared| *cleared != pos);
+        self.events.push(match kind {
+            _ if seeker => ModelEvent::SeekerCreated(pos),
+            SymbolKind::ColorBomb => ModelEvent::ColorBombCreated(pos),
+            _ => ModelEvent::StripedCreated { pos, kind },
+        });
+    }
+
+    /// Add what every special among the cells being cleared clears: each seeker's target and each
+    /// striped symbol's row or column, including specials hit by other specials
+    fn fire_specials(&mut self, positions: &mut Vec<Vector2i>) {
+        let mut i = 0;
+        while i < positions.len() {
+            let from = positions[i];
+            i += 1;
+            let Some(symbol) = self.grid.get(from.x as usize, from.y as usize) else {
+                continue;
+            };
+            let (kind, seeker) = (symbol.kind, symbol.seeker);
+            if kind.is_striped() {
+                for pos in kind.cleared_cells(&self.grid, from) {
+                    if !positions.contains(&pos) {
+                        positions.push(pos);
+                    }
+                }
+                self.events.push(ModelEvent::StripeFired { pos: from, kind });
+            }
+            if !seeker {
+                continue;
+            }
+            let target = seeker::pick_target(&self.grid, from, positions, self.objective, &self.multipliers);
+            if let Some(to) = target {
+                positions.push(to);
+                self.events.push(ModelEvent::SeekerLaunched { from, to });
+            }
+        }
+    }
+
+    /// Score and remove a linked path of same-typed cells as a single match
+    /// Starts a new chain for the cascade that follows
+    pub fn clear_link(&mut self, cells: &[Vector2i]) -> Vec<Vector2i> {
+        let link = Match {
+            positions: cells.to_vec(),
+            horizontal: false,
+            shape: MatchShape::Line,
+        };
+        let Some(symbol_type) = match_type(&self.grid, &link) else {
+            return Vec::new();
+        };
+
+        self.chain.begin(ChainCause::Link);
+        self.score_matches(std::slice::from_ref(&link));
+
+        let mut cleared = cells.to_vec();
+        cleared.extend(expiry::adjacent_blockers(&self.grid, cells));
+        self.spare_ingredients(&mut cleared);
+        self.hit_stones(&cleared);
+        self.eat_chocolate(&cleared);
+        self.crack_ice(&mut cleared);
+        self.clear_jelly(&cleared);
+        self.chain
+            .record_step(vec![(link.positions, symbol_type)], cleared.clone(), StepSpecials::default());
+
+        self.take_symbols(&cleared);
+        cleared
+    }
+
+    /// Score matches at the current combo, then raise the combo for the next resolution
+    fn score_matches(&mut self, matches: &[Match]) {
+        self.events.push(ModelEvent::MatchFound { count: matches.len() as i32 });
+        let match_score: i32 = matches
+            .iter()
+            .map(|m| self.rules.score_match(m, self.combo) * self.multipliers.best(&m.positions))
+            .sum();
+        self.add_score(match_score * self.score_multiplier, scoring::SOURCE_MATCH);
+
+        for m in matches {
+            for pos in self.multipliers.consume(&m.positions) {
+                self.events.push(ModelEvent::MultiplierConsumed(pos));
+            }
+        }
+
+        // Increment combo for cascades, and keep a timed combo going
+        self.combo += 1;
+        if let Some(timer) = self.combo_timer.as_mut() {
+            timer.refill();
+        }
+        self.cascade_depth += 1;
+    }
+
+    /// Clear every symbol in a row for `points` each, returning the cleared positions
+    pub fn sweep_row(&mut self, row: usize, points: i32) -> Vec<Vector2i> {
+        let cleared: Vec<Vector2i> = (0..self.grid.width)
+            .filter(|x| self.grid.get(*x, row).is_some())
+            .map(|x| Vector2i::new(x as i32, row as i32))
+            .collect();
+        self.take_symbols(&cleared);
+        self.add_score(points * cleared.len() as i32, scoring::SOURCE_SWEEP);
+        cleared
+    }
+
+    /// Remove the symbols at the given positions
+    fn take_symbols(&mut self, positions: &[Vector2i]) {
+        for pos in positions {
+            if let Some(symbol) = self.grid.take(pos.x as usize, pos.y as usize) {
+                self.events.push(ModelEvent::SymbolCleared {
+                    pos: *pos,
+                    symbol_type: symbol.current_type(),
+                    tags: symbol.tags,
+                });
+            }
+        }
+    }
+
+    /// Take the ingredients in the bottom row off the board, then drop symbols into empty cells below them
+    pub fn apply_gravity(&mut self) -> Vec<Fall> {
+        let exiting = self.exiting_ingredients();
+        let mut remaining = self.ingredients_left() as i32;
+        for pos in exiting {
+            self.grid.take(pos.x as usize, pos.y as usize);
+            remaining -= 1;
+            self.events.push(ModelEvent::IngredientCollected { pos, remaining });
+        }
+
+        let falls = gravity::settle(&mut self.grid, &self.wells);
+        self.chain.record_moves(falls.iter().map(|fall| fall.to));
+        falls
+    }
+
+    /// Fill every empty playable cell not taken by a stone or chocolate with a new random symbol
+    pub fn refill(&mut self) -> Vec<Spawn> {
+        let mut spawns = Vec::new();
+        let mut nudging = self.pity.is_due() && self.rules.swaps();
+
+        for x in 0..self.grid.width {
+            for y in 0..self.grid.height {
+                if self.grid.is_enabled(x, y) && self.grid.get(x, y).is_none() && !self.grid.is_filled(x, y) {
+                    let pos = Vector2i::new(x as i32, y as i32);
+                    self.queue_spawns(x, 1);
+                    let next = self.spawn_queue.column_mut(x).and_then(|queue| queue.upcoming.pop_front());
+                    let Some(mut symbol) = next else {
+                        continue;
+                    };
+                    // A nudge replaces the queued symbol, which is still used up so later spawns keep to the preview
+                    let setup = nudging.then(|| pity::setup_type(&self.grid, self.rules.as_ref(), pos)).flatten();
+                    if let Some(symbol_type) = setup {
+                        symbol = Symbol::with_type(pos, symbol_type);
+                        nudging = false;
+                        self.pity.reset();
+                        self.events.push(ModelEvent::PityNudge(pos));
+                    }
+                    symbol.grid_pos = pos;
+                    let symbol_type = symbol.current_type();
+                    self.grid.set(x, y, Some(symbol));
+                    spawns.push(Spawn { pos, symbol_type });
+                }
+            }
+        }
+
+        self.chain.record_moves(spawns.iter().map(|spawn| spawn.pos));
+        spawns
+    }
+
+    /// Turn the whole grid a quarter turn, moving every symbol to its new cell; the grid must be square
+    /// The board keeps its shape, like jelly and multipliers under it: holes stay where they are.
+    /// A symbol or overlay turned into a hole moves to the nearest cell a hole turned out of, so the
+    /// spin loses nothing
+    /// Starts a new chain for the cascade that follows
+    pub fn spin(&mut self, clockwise: bool) {
+        let mut spun = self.grid.rotated(clockwise);
+        let mut stranded = Vec::new();
+        let mut vacated = Vec::new();
+        for y in 0..spun.height {
+            for x in 0..spun.width {
+                let pos = Vector2i::new(x as i32, y as i32);
+                match (self.grid.is_enabled(x, y), spun.is_enabled(x, y)) {
+                    (false, true) => stranded.push((pos, spun.take(x, y), spun.overlay(x, y))),
+                    (true, false) => vacated.push(pos),
+                    _ => {}
+                }
+                let _ = spun.set_enabled(pos, self.grid.is_enabled(x, y));
+            }
+        }
+
+        // The turn keeps the number of holes, so every stranded cell has an empty one to go to
+        for (from, symbol, overlay) in stranded {
+            let distance = |to: &Vector2i| (to.x - from.x).abs() + (to.y - from.y).abs();
+            let Some(nearest) = (0..vacated.len()).min_by_key(|&idx| distance(&vacated[idx])) else {
+                break;
+            };
+            let to = vacated.remove(nearest);
+            if let Some(overlay) = overlay {
+                let _ = spun.set_overlay(to, Some(overlay));
+            }
+            if let Some(mut symbol) = symbol {
+                symbol.grid_pos = to;
+                spun.set(to.x as usize, to.y as usize, Some(symbol));
+            }
+        }
+        self.grid = spun;
+        self.chain.begin(ChainCause::Spin);
+    }
+
+    /// Put the held symbol in place of the one at a position, holding that one instead;
+    /// with nothing held the cell is left empty for gravity and refill. Any cell with a symbol
+    /// will do, since a settled board has no empty cells to stay next to
+    /// Starts a new chain for the cascade that follows
+    pub fn exchange_held(&mut self, pos: Vector2i) -> Result<(), BoardError> {
+        let idx = self.grid.checked_index(pos)?;
+        if self.grid.is_frozen(pos.x as usize, pos.y as usize) {
+            return Err(BoardError::CellFrozen(pos));
+        }
+        let taken = self.grid.cells[idx].take().ok_or(BoardError::EmptyCell(pos))?;
+        self.grid.cells[idx] = self.held.take().map(|mut held| {
+            held.grid_pos = pos;
+            held
+        });
+        self.held = Some(taken);
+        self.chain.begin(ChainCause::Hold(pos));
+        Ok(())
+    }
+
+    /// Get the current type of the held symbol, if any
+    pub fn held_type(&self) -> Option<SymbolType> {
+        self.held.as_ref().map(Symbol::current_type)
+    }
+
+    /// Turn the symbol at a position to its next face, or back to its previous one if not clockwise
+    /// Starts a new chain for the cascade that follows
+    pub fn rotate_cell(&mut self, pos: Vector2i, clockwise: bool) -> Result<(), BoardError> {
+        let idx = self.grid.checked_index(pos)?;
+        let symbol = self.grid.cells[idx].as_mut().ok_or(BoardError::EmptyCell(pos))?;
+        if clockwise {
+            symbol.rotate();
+        } else {
+            symbol.rotate_back();
+        }
+        self.chain.begin(ChainCause::Tap(pos));
+        Ok(())
+    }
+
+    /// Rotate every symbol as the rules dictate
+    /// Starts a new chain for the cascade that follows
+    pub fn rotate_all(&mut self) {
+        self.rules.rotate(&mut self.grid);
+        self.chain.begin(ChainCause::Rotation);
+    }
+
+    /// Age every symbol by one rotation cycle, if expiry is on
+    pub fn age_symbols(&mut self) {
+        if self.expiry == 0 {
+            return;
+        }
+        for pos in expiry::age(&mut self.grid, self.expiry) {
+            self.events.push(ModelEvent::SymbolExpired(pos));
+        }
+    }
+
+    /// Shuffle the symbols of one row in place
+    /// Starts a new chain for the cascade that follows
+    pub fn shuffle_row(&mut self, row: usize) {
+        let y = row as i32;
+        for x in (1..self.grid.width).rev() {
+            let other = self.rng.gen_range(0..=x);
+            // Holes and other empty cells stay where they are
+            let _ = self.grid.swap(Vector2i::new(x as i32, y), Vector2i::new(other as i32, y));
+        }
+        self.chain.begin(ChainCause::Rotation);
+    }
+
+    /// Pick a row at random from the seeded source
+    pub fn random_row(&mut self) -> usize {
+        self.rng.gen_range(0..self.grid.height.max(1))
+    }
+
+    /// Find the matches the next rotation will create, with their symbol type, leaving the grid unchanged
+    pub fn post_rotation_matches(&self) -> Vec<(Match, SymbolType)> {
+        let mut grid = self.grid.clone();
+        self.rules.rotate(&mut grid);
+        self.rules
+            .find_matches(&grid)
+            .into_iter()
+            .filter_map(|m| match_type(&grid, &m).map(|symbol_type| (m, symbol_type)))
+            .collect()
+    }
+
+    /// Find a move that would create a match; a tap is returned with both cells the same
+    pub fn find_hint(&self) -> Option<(Vector2i, Vector2i)> {
+        if self.rules.swaps() {
+            if let Some(found) = self.rules.find_possible_move(&self.grid) {
+                return Some(found);
+            }
+        }
+        if self.rules.taps() {
+            return self.rules.find_possible_tap(&self.grid).map(|pos| (pos, pos));
+        }
+        None
+    }
+}
+
+/// Sort model events into the specials they show being created and set off
+pub fn special_events(events: &[ModelEvent]) -> StepSpecials {
+    let mut specials = StepSpecials::default();
+    for event in events {
+        match *event {
+            ModelEvent::SeekerCreated(pos) => specials.created.push((pos, ChainSpecial::Seeker)),
+            ModelEvent::StripedCreated { pos, kind } => specials.created.push((pos, ChainSpecial::Striped(kind))),
+            ModelEvent::ColorBombCreated(pos) => specials.created.push((pos, ChainSpecial::ColorBomb)),
+            ModelEvent::SeekerLaunched { from, .. } => specials.fired.push((from, ChainSpecial::Seeker)),
+            ModelEvent::StripeFired { pos, kind } => specials.fired.push((pos, ChainSpecial::Striped(kind))),
+            ModelEvent::ColorBombFired { pos, .. } => specials.fired.push((pos, ChainSpecial::ColorBomb)),
+            ModelEvent::ComboTriggered { at, effect, .. } => specials.fired.push((at, ChainSpecial::Combo(effect))),
+            _ => {}
+        }
+    }
+    specials
+}
+
+/// Get the symbol type of a match from its first cell that isn't wild, or its first cell if all are
+fn match_type(grid: &Grid, m: &Match) -> Option<SymbolType> {
+    let symbols: Vec<&Symbol> = m.positions.iter().filter_map(|pos| grid.get(pos.x as usize, pos.y as usize)).collect();
+    let symbol = symbols.iter().find(|symbol| !symbol.wild).or(symbols.first())?;
+    Some(symbol.current_type())
+}
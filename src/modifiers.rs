@@ -0,0 +1,281 @@
+//! Level modifiers - named rules stacked on top of the base game
+//!
+//! A level lists the modifiers it plays with (frenzy, expiry, roulette,
+//! mirror, fog) and the board stacks them. Each implements `Modifier` and reacts
+//! to the game through lifecycle hooks: a move starting a turn, a resolution
+//! clearing cells, a rotation firing and game time passing. Hooks run in
+//! stack order; they change the model directly and report anything the board
+//! should show as `ModifierEvent`s. A new modifier is one more `Modifier`
+//! type and a name in `create`.
+
+use crate::commands::BoardCommand;
+use crate::error::SwapRejection;
+use crate::expiry::ExpiryModifier;
+use crate::fog::FogModifier;
+use crate::frenzy::FrenzyModifier;
+use crate::model::BoardModel;
+use crate::roulette::{Roulette, RouletteEffect, RouletteKind};
+use crate::symbols::SymbolType;
+use godot::prelude::*;
+use std::fmt;
+
+/// Names of the built-in modifiers, in the order they are documented
+pub const NAMES: [&str; 5] = ["frenzy", "expiry", "roulette", "mirror", "fog"];
+
+/// Board settings the modifiers read, taken fresh for every hook
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ModifierTuning {
+    pub frenzy_interval: f64,
+    pub frenzy_duration: f64,
+    pub frenzy_warning: f64,
+    pub frenzy_multiplier: i32,
+    /// Symbol type refills favor during a frenzy
+    pub favored: Option<SymbolType>,
+    /// Cells around activity the fog clears from, along either axis
+    pub fog_radius: i32,
+    /// Moves a cell stays clear of fog after activity near it
+    pub fog_moves: i32,
+}
+
+/// Something a modifier did that the board shows or announces
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModifierEvent {
+    /// A frenzy starts in `seconds`
+    FrenzyUpcoming { seconds: f64 },
+    /// A frenzy started
+    FrenzyStarted { multiplier: i32, duration: f64 },
+    /// The frenzy ended
+    FrenzyEnded,
+    /// A swap was repeated on these mirrored cells
+    SwapMirrored(Vector2i, Vector2i),
+    /// A swap's mirror image was not made, and why
+    MirrorSkipped(SwapRejection),
+    /// The next rotation will have this effect
+    RotationAnnounced(RouletteKind),
+    /// The fog cleared from a cell
+    CellRevealed(Vector2i),
+    /// The fog covered a cell again
+    CellFogged(Vector2i),
+}
+
+/// What a hook may touch
+pub struct ModifierContext<'a> {
+    pub model: &'a mut BoardModel,
+    pub tuning: ModifierTuning,
+    /// Cells the board won't let a move repeated by a modifier touch: locked, masked or outside the tutorial step
+    pub locked: Vec<Vector2i>,
+    events: Vec<ModifierEvent>,
+}
+
+impl<'a> ModifierContext<'a> {
+    /// Create a context for running hooks on a model
+    pub fn new(model: &'a mut BoardModel, tuning: ModifierTuning) -> Self {
+        Self {
+            model,
+            tuning,
+            locked: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Report something for the board to show
+    pub fn emit(&mut self, event: ModifierEvent) {
+        self.events.push(event);
+    }
+
+    /// Take the events the hooks reported
+    pub fn into_events(self) -> Vec<ModifierEvent> {
+        self.events
+    }
+}
+
+/// A rule a level stacks on top of the base game; every hook defaults to doing nothing
+pub trait Modifier: fmt::Debug {
+    /// Get the name levels list this modifier by
+    fn name(&self) -> &'static str;
+
+    /// Called when the modifier joins the stack
+    fn on_attach(&mut self, _ctx: &mut ModifierContext) {}
+
+    /// Called when the modifier leaves the stack; undo anything still in force
+    fn on_detach(&mut self, _ctx: &mut ModifierContext) {}
+
+    /// Called with the player's move (Swap, Tap, ClearLink or Hold) once the model has made it
+    fn on_turn_start(&mut self, _ctx: &mut ModifierContext, _command: BoardCommand) {}
+
+    /// Called after a resolution cleared cells, whatever set it off
+    fn on_match(&mut self, _ctx: &mut ModifierContext, _cleared: &[Vector2i]) {}
+
+    /// Called as the rotation timer fires; return an effect to replace the plain rotation
+    fn pick_rotation(&mut self, _ctx: &mut ModifierContext) -> Option<RouletteEffect> {
+        None
+    }
+
+    /// Called once a rotation's effect has been applied
+    fn on_rotation(&mut self, _ctx: &mut ModifierContext, _effect: RouletteEffect) {}
+
+    /// Called every frame with the game time that passed
+    fn on_tick(&mut self, _ctx: &mut ModifierContext, _delta: f64) {}
+
+    /// Check if the modifier stops the player moving a cell
+    fn locks(&self, _pos: Vector2i) -> bool {
+        false
+    }
+
+    /// Check if the modifier hides a cell's symbol from the player
+    fn hides(&self, _pos: Vector2i) -> bool {
+        false
+    }
+
+    /// Get the effect the next rotation will have, if the modifier decides it
+    fn upcoming_rotation(&self) -> Option<RouletteKind> {
+        None
+    }
+
+    /// Check if the modifier is boosting the score right now
+    fn boosting(&self) -> bool {
+        false
+    }
+}
+
+/// Every swap is repeated on its mirror image across the board's vertical center line,
+/// resolving both in one cascade
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MirrorModifier;
+
+impl Modifier for MirrorModifier {
+    fn name(&self) -> &'static str {
+        "mirror"
+    }
+
+    fn on_turn_start(&mut self, ctx: &mut ModifierContext, command: BoardCommand) {
+        let BoardCommand::Swap(pos1, pos2) = command else {
+            return;
+        };
+        let mirrored = [ctx.model.mirror(pos1), ctx.model.mirror(pos2)];
+        if let Some(pos) = mirrored.into_iter().find(|pos| ctx.locked.contains(pos)) {
+            ctx.emit(ModifierEvent::MirrorSkipped(SwapRejection::CellLocked(pos)));
+            return;
+        }
+        match ctx.model.swap_mirrored(pos1, pos2) {
+            Ok(Some((mirror1, mirror2))) => ctx.emit(ModifierEvent::SwapMirrored(mirror1, mirror2)),
+            Ok(None) => {}
+            Err(reason) => ctx.emit(ModifierEvent::MirrorSkipped(reason)),
+        }
+    }
+}
+
+/// Create a built-in modifier by name; `seed` seeds any randomness and `expiry_rotations` is the expiry age
+pub fn create(name: &str, seed: i64, expiry_rotations: u32) -> Option<Box<dyn Modifier>> {
+    match name {
+        "frenzy" => Some(Box::new(FrenzyModifier::default())),
+        "expiry" => Some(Box::new(ExpiryModifier::new(expiry_rotations))),
+        "roulette" => Some(Box::new(Roulette::new(seed))),
+        "mirror" => Some(Box::new(MirrorModifier)),
+        "fog" => Some(Box::new(FogModifier::default())),
+        _ => None,
+    }
+}
+
+/// The modifiers in play, in the order their hooks run
+#[derive(Debug, Default)]
+pub struct ModifierStack {
+    modifiers: Vec<Box<dyn Modifier>>,
+}
+
+impl ModifierStack {
+    /// Get the names of the stacked modifiers, in order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.modifiers.iter().map(|modifier| modifier.name()).collect()
+    }
+
+    /// Check if a modifier is stacked
+    pub fn contains(&self, name: &str) -> bool {
+        self.modifiers.iter().any(|modifier| modifier.name() == name)
+    }
+
+    /// Stack a modifier on top, returning false if one of the same name is already in play
+    pub fn add(&mut self, mut modifier: Box<dyn Modifier>, ctx: &mut ModifierContext) -> bool {
+        if self.contains(modifier.name()) {
+            return false;
+        }
+        modifier.on_attach(ctx);
+        self.modifiers.push(modifier);
+        true
+    }
+
+    /// Take a modifier out of play, returning false if it was not stacked
+    pub fn remove(&mut self, name: &str, ctx: &mut ModifierContext) -> bool {
+        let Some(idx) = self.modifiers.iter().position(|modifier| modifier.name() == name) else {
+            return false;
+        };
+        self.modifiers.remove(idx).on_detach(ctx);
+        true
+    }
+
+    /// Take every modifier out of play
+    pub fn clear(&mut self, ctx: &mut ModifierContext) {
+        for mut modifier in self.modifiers.drain(..) {
+            modifier.on_detach(ctx);
+        }
+    }
+
+    /// Run every `on_turn_start` hook
+    pub fn on_turn_start(&mut self, ctx: &mut ModifierContext, command: BoardCommand) {
+        for modifier in &mut self.modifiers {
+            modifier.on_turn_start(ctx, command);
+        }
+    }
+
+    /// Run every `on_match` hook
+    pub fn on_match(&mut self, ctx: &mut ModifierContext, cleared: &[Vector2i]) {
+        for modifier in &mut self.modifiers {
+            modifier.on_match(ctx, cleared);
+        }
+    }
+
+    /// Ask every modifier for a replacement effect; the last one stacked wins, a plain rotation if none
+    pub fn pick_rotation(&mut self, ctx: &mut ModifierContext) -> RouletteEffect {
+        let mut effect = RouletteEffect::RotateFaces;
+        for modifier in &mut self.modifiers {
+            if let Some(picked) = modifier.pick_rotation(ctx) {
+                effect = picked;
+            }
+        }
+        effect
+    }
+
+    /// Run every `on_rotation` hook
+    pub fn on_rotation(&mut self, ctx: &mut ModifierContext, effect: RouletteEffect) {
+        for modifier in &mut self.modifiers {
+            modifier.on_rotation(ctx, effect);
+        }
+    }
+
+    /// Run every `on_tick` hook
+    pub fn on_tick(&mut self, ctx: &mut ModifierContext, delta: f64) {
+        for modifier in &mut self.modifiers {
+            modifier.on_tick(ctx, delta);
+        }
+    }
+
+    /// Check if any modifier stops the player moving a cell
+    pub fn locks(&self, pos: Vector2i) -> bool {
+        self.modifiers.iter().any(|modifier| modifier.locks(pos))
+    }
+
+    /// Check if any modifier hides a cell's symbol from the player
+    pub fn hides(&self, pos: Vector2i) -> bool {
+        self.modifiers.iter().any(|modifier| modifier.hides(pos))
+    }
+
+    /// Get the effect the next rotation will have, if a modifier decides it
+    pub fn upcoming_rotation(&self) -> Option<RouletteKind> {
+        self.modifiers.iter().rev().find_map(|modifier| modifier.upcoming_rotation())
+    }
+
+    /// Check if any modifier is boosting the score right now
+    pub fn boosting(&self) -> bool {
+        self.modifiers.iter().any(|modifier| modifier.boosting())
+    }
+}
@@ -0,0 +1,87 @@
+//! Cell score multipliers - level cells that multiply the score of matches over them
+//!
+//! A match scores at the highest multiplier among its cells. Depending on the
+//! level, a multiplier either stays for the whole level or is used up by the
+//! first match over it.
+
+use godot::prelude::*;
+
+/// Score multipliers per cell, as painted on the level
+#[derive(Debug, Clone, Default)]
+pub struct CellMultipliers {
+    width: usize,
+    /// Multiplier per cell; 0 or 1 for none
+    factors: Vec<i32>,
+    /// Whether a multiplier is used up by the first match over it
+    consumable: bool,
+}
+
+impl CellMultipliers {
+    /// Create multipliers for a board `width` cells wide from one factor per cell
+    pub fn new(width: usize, factors: Vec<i32>, consumable: bool) -> Self {
+        Self {
+            width,
+            factors,
+            consumable,
+        }
+    }
+
+    /// Get the multiplier at a cell (1 for none)
+    pub fn factor(&self, pos: Vector2i) -> i32 {
+        self.index(pos)
+            .and_then(|idx| self.factors.get(idx))
+            .map_or(1, |factor| (*factor).max(1))
+    }
+
+    /// Get the multiplier for a match over the given cells
+    pub fn best(&self, positions: &[Vector2i]) -> i32 {
+        positions.iter().map(|pos| self.factor(*pos)).max().unwrap_or(1)
+    }
+
+    /// Use up the multipliers under the given cells, if they are consumable
+    /// Returns the cells that lost their multiplier
+    pub fn consume(&mut self, positions: &[Vector2i]) -> Vec<Vector2i> {
+        if !self.consumable {
+            return Vec::new();
+        }
+        positions
+            .iter()
+            .copied()
+            .filter(|pos| {
+                let Some(factor) = self.index(*pos).and_then(|idx| self.factors.get_mut(idx)) else {
+                    return false;
+                };
+                let used = *factor > 1;
+                *factor = 0;
+                used
+            })
+            .collect()
+    }
+
+    /// Iterate over the cells with a multiplier, with their factor
+    pub fn cells(&self) -> impl Iterator<Item = (Vector2i, i32)> + '_ {
+        let width = self.width.max(1);
+        self.factors
+            .iter()
+            .enumerate()
+            .filter(|(_, factor)| **factor > 1)
+            .map(move |(idx, factor)| (Vector2i::new((idx % width) as i32, (idx / width) as i32), *factor))
+    }
+
+    /// Get the cell index for a position
+    fn index(&self, pos: Vector2i) -> Option<usize> {
+        if pos.x < 0 || pos.y < 0 || pos.x as usize >= self.width {
+            return None;
+        }
+        Some(pos.y as usize * self.width + pos.x as usize)
+    }
+}
+
+/// Get the color of the tile shown under a cell with a multiplier
+pub fn tile_color(factor: i32) -> Color {
+    if factor >= 3 {
+        Color::from_rgba(0.75, 0.3, 0.15, 1.0)
+    } else {
+        Color::from_rgba(0.7, 0.6, 0.2, 1.0)
+    }
+}
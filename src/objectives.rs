@@ -0,0 +1,153 @@
+//! Weighted objectives - several level goals adding up to one completion percentage
+//!
+//! A level can ask for a mix of goals: a score to reach, symbols of a type to
+//! collect, stones or ice to break, jelly to clear, ingredients to bring down.
+//! Each goal counts toward the level's completion in proportion to its weight,
+//! so a level can lean on one goal and treat another as a side task. The board
+//! announces each of `THRESHOLDS` as the completion crosses it, for mid-level
+//! celebrations.
+
+use crate::model::ModelEvent;
+use crate::symbols::SymbolType;
+
+/// Completion percentages announced as they are crossed
+pub const THRESHOLDS: [i32; 3] = [25, 50, 75];
+
+/// What an objective counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectiveKind {
+    /// Points scored
+    Score,
+    /// Symbols of a type cleared
+    Collect(SymbolType),
+    /// Stones broken
+    Stones,
+    /// Ice broken
+    Ice,
+    /// Jelly cells cleared
+    Jelly,
+    /// Ingredients brought out through the bottom row
+    Ingredients,
+}
+
+impl ObjectiveKind {
+    /// Parse a kind from its name; a collect objective needs the type it collects
+    pub fn from_name(name: &str, symbol_type: Option<SymbolType>) -> Option<Self> {
+        match name {
+            "score" => Some(ObjectiveKind::Score),
+            "collect" => symbol_type.map(ObjectiveKind::Collect),
+            "stones" => Some(ObjectiveKind::Stones),
+            "ice" => Some(ObjectiveKind::Ice),
+            "jelly" => Some(ObjectiveKind::Jelly),
+            "ingredients" => Some(ObjectiveKind::Ingredients),
+            _ => None,
+        }
+    }
+
+    /// Get the lowercase name of this kind
+    pub fn name(&self) -> &'static str {
+        match self {
+            ObjectiveKind::Score => "score",
+            ObjectiveKind::Collect(_) => "collect",
+            ObjectiveKind::Stones => "stones",
+            ObjectiveKind::Ice => "ice",
+            ObjectiveKind::Jelly => "jelly",
+            ObjectiveKind::Ingredients => "ingredients",
+        }
+    }
+}
+
+/// One goal of a level and how far along it is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Objective {
+    pub kind: ObjectiveKind,
+    pub target: i32,
+    /// Share of the level's completion this goal carries, relative to the others
+    pub weight: f64,
+    pub progress: i32,
+}
+
+impl Objective {
+    /// Create an objective with no progress
+    pub fn new(kind: ObjectiveKind, target: i32, weight: f64) -> Self {
+        Self {
+            kind,
+            target,
+            weight: weight.max(0.0),
+            progress: 0,
+        }
+    }
+
+    /// Get the share of the target reached, from 0 to 1
+    pub fn fraction(&self) -> f64 {
+        if self.target <= 0 {
+            return 1.0;
+        }
+        (f64::from(self.progress) / f64::from(self.target)).clamp(0.0, 1.0)
+    }
+
+    /// Check if the target is reached
+    pub fn is_complete(&self) -> bool {
+        self.progress >= self.target
+    }
+}
+
+/// A level's objectives, with the thresholds already announced
+#[derive(Debug, Clone, Default)]
+pub struct ObjectiveSet {
+    objectives: Vec<Objective>,
+    crossed: usize,
+}
+
+impl ObjectiveSet {
+    /// Track some objectives from the start
+    pub fn new(objectives: Vec<Objective>) -> Self {
+        Self { objectives, crossed: 0 }
+    }
+
+    /// Get the objectives
+    pub fn objectives(&self) -> &[Objective] {
+        &self.objectives
+    }
+
+    /// Count a model event toward the objectives it advances
+    pub fn record(&mut self, event: &ModelEvent) {
+        for objective in &mut self.objectives {
+            match (objective.kind, event) {
+                (ObjectiveKind::Score, ModelEvent::ScoreChanged(score)) => objective.progress = *score,
+                (ObjectiveKind::Collect(wanted), ModelEvent::SymbolCleared { symbol_type, .. })
+                    if *symbol_type == wanted =>
+                {
+                    objective.progress += 1;
+                }
+                (ObjectiveKind::Stones, ModelEvent::StoneBroken(_))
+                | (ObjectiveKind::Ice, ModelEvent::IceBroken(_))
+                | (ObjectiveKind::Jelly, ModelEvent::JellyCleared { .. })
+                | (ObjectiveKind::Ingredients, ModelEvent::IngredientCollected { .. }) => objective.progress += 1,
+                _ => {}
+            }
+        }
+    }
+
+    /// Get the weighted completion of every objective, from 0 to 100 (0 without objectives)
+    pub fn completion_percent(&self) -> f64 {
+        let total: f64 = self.objectives.iter().map(|objective| objective.weight).sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let done: f64 = self.objectives.iter().map(|objective| objective.fraction() * objective.weight).sum();
+        done / total * 100.0
+    }
+
+    /// Take the thresholds the completion has crossed since the last call, lowest first
+    pub fn take_crossed(&mut self) -> Vec<i32> {
+        let percent = self.completion_percent();
+        let crossed: Vec<i32> = THRESHOLDS[self.crossed..]
+            .iter()
+            .copied()
+            .take_while(|threshold| percent >= f64::from(*threshold))
+            .collect();
+        self.crossed += crossed.len();
+        crossed
+    }
+}
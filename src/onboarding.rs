@@ -0,0 +1,112 @@
+//! First-play onboarding board - a scripted opening with a guaranteed cascade
+//!
+//! The generated board has one obvious swap that makes a 4-match, and the
+//! symbols falling into its gap complete a 3-match at known positions. Each
+//! candidate is checked by simulating the move on a `BoardModel`.
+
+use crate::model::BoardModel;
+use crate::symbols::{Grid, Symbol, SymbolType};
+use godot::prelude::*;
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+/// Candidate boards to try before giving up
+const MAX_ATTEMPTS: usize = 100;
+
+/// A generated onboarding board and where its scripted moves happen
+#[derive(Debug, Clone)]
+pub struct Onboarding {
+    pub grid: Grid,
+    /// The swap that makes the 4-match
+    pub swap: (Vector2i, Vector2i),
+    /// Cells of the 4-match
+    pub first_match: Vec<Vector2i>,
+    /// Cells of the follow-up cascade match
+    pub cascade: Vec<Vector2i>,
+}
+
+/// Generate an onboarding board with holes at `holes`; needs at least 6x3 cells, with none of
+/// the scripted cells a hole
+pub fn generate<R: Rng + ?Sized>(width: usize, height: usize, holes: &[Vector2i], rng: &mut R) -> Option<Onboarding> {
+    if width < 6 || height < 3 {
+        return None;
+    }
+
+    // Pattern origin: the 4-match row, with room for one row above and below
+    let origin = Vector2i::new(((width - 6) / 2) as i32, (height / 2) as i32);
+    let at = |dx: i32, dy: i32| origin + Vector2i::new(dx, dy);
+
+    // Row:   A A B A C C   with an A below the B and a C above the last A.
+    // Swapping the B down makes A A A A; the C above then drops into C C C.
+    let swap = (at(2, 0), at(2, 1));
+    let first_match = vec![at(0, 0), at(1, 0), at(2, 0), at(3, 0)];
+    let cascade = vec![at(3, 0), at(4, 0), at(5, 0)];
+
+    for _ in 0..MAX_ATTEMPTS {
+        let mut types = SymbolType::ALL;
+        types.shuffle(rng);
+        let (a, b, c) = (types[0], types[1], types[2]);
+
+        let mut grid = Grid::new(width, height);
+        for &pos in holes {
+            let _ = grid.set_enabled(pos, false);
+        }
+        grid.fill_random(rng);
+
+        let stamp = [
+            (at(0, 0), a),
+            (at(1, 0), a),
+            (at(2, 0), b),
+            (at(3, 0), a),
+            (at(2, 1), a),
+            (at(3, -1), c),
+            (at(4, 0), c),
+            (at(5, 0), c),
+        ];
+        if stamp.iter().any(|(pos, _)| !grid.is_enabled(pos.x as usize, pos.y as usize)) {
+            return None;
+        }
+        for (pos, symbol_type) in stamp {
+            grid.set(pos.x as usize, pos.y as usize, Some(Symbol::with_type(pos, symbol_type)));
+        }
+
+        if plays_out(&grid, swap, &first_match, &cascade) {
+            return Some(Onboarding {
+                grid,
+                swap,
+                first_match,
+                cascade,
+            });
+        }
+    }
+
+    None
+}
+
+/// Simulate the scripted move and check it plays out exactly as planned
+fn plays_out(grid: &Grid, swap: (Vector2i, Vector2i), first_match: &[Vector2i], cascade: &[Vector2i]) -> bool {
+    let mut model = BoardModel::new(grid.width, grid.height);
+    model.load_grid(grid.clone());
+
+    // The opening board must be quiet
+    if model.has_matches() || model.swap(swap.0, swap.1).is_err() {
+        return false;
+    }
+
+    // The swap makes exactly the planned 4-match
+    let matches = model.rules().find_matches(model.grid());
+    let [only] = &matches[..] else {
+        return false;
+    };
+    if only.len() != first_match.len() || !first_match.iter().all(|pos| only.positions.contains(pos)) {
+        return false;
+    }
+
+    // Symbols falling into the gap complete the cascade, before any refill
+    model.resolve_matches();
+    model.apply_gravity();
+    let matches = model.rules().find_matches(model.grid());
+    cascade
+        .iter()
+        .all(|pos| matches.iter().any(|m| m.positions.contains(pos)))
+}
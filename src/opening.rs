@@ -0,0 +1,93 @@
+//! Opening fairness - how playable a freshly dealt board is
+//!
+//! A report counts the opening's legal swaps, finds the one scoring most and
+//! measures how evenly the symbol types are spread. A `FairnessPolicy` sets the
+//! least a deal must offer; the board deals again until one passes or it runs
+//! out of attempts. Each deal draws from the model's seeded random source in
+//! turn, so a seed always settles on the same opening.
+
+use crate::hud;
+use crate::model::BoardModel;
+use crate::solver::{self, MoveScore};
+use crate::symbols::SymbolType;
+
+/// What a dealt board offers the player
+#[derive(Debug, Clone, Default)]
+pub struct OpeningReport {
+    /// Legal swaps
+    pub moves: usize,
+    /// The swap scoring most before any refill
+    pub best: Option<MoveScore>,
+    /// Symbols of each type, indexed by `SymbolType::index`
+    pub counts: [i32; SymbolType::ALL.len()],
+    /// Count of the rarest type over the most common, from 0 (a type is missing) to 1 (all even)
+    pub balance: f64,
+}
+
+/// Work out what the model's current board offers
+pub fn analyze(model: &BoardModel) -> OpeningReport {
+    let moves = solver::evaluate_moves(model, usize::MAX);
+    let counts = hud::summarize(model.grid(), 0).counts;
+    let most = counts.iter().copied().max().unwrap_or(0);
+    let least = counts.iter().copied().min().unwrap_or(0);
+    OpeningReport {
+        moves: moves.len(),
+        best: moves.first().copied(),
+        counts,
+        balance: if most > 0 { f64::from(least) / f64::from(most) } else { 0.0 },
+    }
+}
+
+/// The least an opening must offer; the default accepts any board with a move
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FairnessPolicy {
+    pub min_moves: usize,
+    pub min_best_score: i32,
+    pub min_balance: f64,
+}
+
+impl FairnessPolicy {
+    /// Check if the policy asks for more than a single move, so deals need analyzing
+    pub fn is_strict(&self) -> bool {
+        self.min_moves > 1 || self.min_best_score > 0 || self.min_balance > 0.0
+    }
+
+    /// Check if a report meets every threshold
+    pub fn accepts(&self, report: &OpeningReport) -> bool {
+        report.moves >= self.min_moves
+            && report.best.map_or(0, |best| best.score) >= self.min_best_score
+            && report.balance >= self.min_balance
+    }
+}
+
+/// How dealing went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deal {
+    /// Boards dealt, counting the one kept
+    pub attempts: usize,
+    /// Whether the board kept passed; otherwise it is the last one dealt
+    pub fair: bool,
+}
+
+/// Deal boards of the given size plus preplaced symbols until one has a move and passes the policy,
+/// giving up after `max_attempts`
+pub fn deal(
+    model: &mut BoardModel,
+    size: (usize, usize),
+    preplaced: &[(usize, usize, SymbolType)],
+    policy: &FairnessPolicy,
+    max_attempts: usize,
+) -> Deal {
+    let max_attempts = max_attempts.max(1);
+    for attempt in 1..=max_attempts {
+        model.rebuild(size.0, size.1, preplaced);
+        let fair = model.find_hint().is_some() && (!policy.is_strict() || policy.accepts(&analyze(model)));
+        if fair {
+            return Deal { attempts: attempt, fair };
+        }
+    }
+    Deal {
+        attempts: max_attempts,
+        fair: false,
+    }
+}
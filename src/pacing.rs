@@ -0,0 +1,35 @@
+//! Adaptive rotation pacing - the rotation timer speeds up on a static board
+//!
+//! The rotation timer only runs while the board waits for the player, so
+//! cascades already hold it. With adaptive pacing it also runs faster the
+//! longer the board goes without a move or a match, up to a maximum rate,
+//! and drops back to normal speed as soon as something happens.
+
+/// Tracks how long the board has been static and how fast the rotation timer runs
+#[derive(Debug, Clone, Default)]
+pub struct RotationPacing {
+    /// Waiting time since the last move or match
+    static_time: f64,
+}
+
+impl RotationPacing {
+    /// Count `delta` seconds of the board waiting for the player
+    pub fn advance(&mut self, delta: f64) {
+        self.static_time += delta;
+    }
+
+    /// Note a move or a match, returning the timer to normal speed
+    pub fn on_activity(&mut self) {
+        self.static_time = 0.0;
+    }
+
+    /// Get the rotation timer's rate: 1 on an active board, rising linearly to
+    /// `max_rate` once the board has been static for `ramp` seconds
+    pub fn rate(&self, max_rate: f64, ramp: f64) -> f64 {
+        let max_rate = max_rate.max(1.0);
+        if ramp <= 0.0 {
+            return max_rate;
+        }
+        1.0 + (max_rate - 1.0) * (self.static_time / ramp).min(1.0)
+    }
+}
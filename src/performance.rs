@@ -0,0 +1,74 @@
+//! Performance presets - bundles of presentation settings for weaker devices
+//!
+//! A preset never changes the game itself, only how much work presenting it
+//! takes: whether symbol nodes are pooled, how often cosmetic signals may
+//! fire, how often snapshots are rebuilt and how elaborate animations are.
+
+/// Signals that only drive particles, sounds and similar juice, rate limited by a preset
+pub const JUICE_EVENTS: [&str; 3] = ["symbol_cleared", "fast_fall", "idle_tick"];
+
+/// How much presentation work the board does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PerformancePreset {
+    /// Everything on, for desktops and recent phones
+    #[default]
+    High,
+    /// Pooled nodes and capped juice signals
+    Medium,
+    /// Pooled nodes, sparse juice signals, fewer snapshots and quicker, simpler animations
+    Low,
+}
+
+impl PerformancePreset {
+    /// All presets, by index
+    pub const ALL: [PerformancePreset; 3] = [
+        PerformancePreset::High,
+        PerformancePreset::Medium,
+        PerformancePreset::Low,
+    ];
+
+    /// Get a preset from its index
+    pub fn from_index(index: i32) -> Option<Self> {
+        usize::try_from(index).ok().and_then(|index| Self::ALL.get(index).copied())
+    }
+
+    /// Get the preset's index
+    pub fn index(&self) -> i32 {
+        *self as i32
+    }
+
+    /// Whether cleared symbol nodes are kept for reuse instead of freed
+    pub fn pool_nodes(&self) -> bool {
+        *self != PerformancePreset::High
+    }
+
+    /// Most emissions per second of each juice event (0 for no cap)
+    pub fn juice_rate(&self) -> f64 {
+        match self {
+            PerformancePreset::High => 0.0,
+            PerformancePreset::Medium => 30.0,
+            PerformancePreset::Low => 10.0,
+        }
+    }
+
+    /// Most snapshots and HUD summaries per second
+    pub fn snapshot_rate(&self) -> f64 {
+        match self {
+            PerformancePreset::High | PerformancePreset::Medium => 10.0,
+            PerformancePreset::Low => 4.0,
+        }
+    }
+
+    /// Factor applied to every animation duration
+    pub fn animation_scale(&self) -> f64 {
+        match self {
+            PerformancePreset::High | PerformancePreset::Medium => 1.0,
+            PerformancePreset::Low => 0.6,
+        }
+    }
+
+    /// Whether extra flourishes play: fast-fall stretching and column-by-column transitions
+    pub fn flourishes(&self) -> bool {
+        *self != PerformancePreset::Low
+    }
+}
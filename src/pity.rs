@@ -0,0 +1,96 @@
+//! Pity - a nudge for players stuck without big matches
+//!
+//! Every turn (a swap, tap, link or hold) whose cascade makes no match of
+//! `BIG_MATCH` or more cells raises the meter. Once it reaches the threshold,
+//! the next refill places one symbol that sets up a swap making a big match,
+//! and the meter starts over. The nudge is worked out from the grid alone, so
+//! a seeded game and its replay are nudged the same way.
+
+use crate::chain::{Chain, ChainCause};
+use crate::rules::Ruleset;
+use crate::symbols::{Grid, Symbol, SymbolType};
+use godot::prelude::*;
+
+/// Shortest match that counts as a big one; it is long enough to leave a striped symbol
+pub const BIG_MATCH: usize = 4;
+
+/// How far from the nudged cell a swap may be to use it
+const SETUP_RADIUS: i32 = 2;
+
+/// Turns since the last big match
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PityMeter {
+    turns: u32,
+    threshold: u32,
+}
+
+impl PityMeter {
+    /// Set the turns without a big match before a nudge (0 turns nudges off)
+    pub fn set_threshold(&mut self, threshold: u32) {
+        self.threshold = threshold;
+    }
+
+    /// Get the turns since the last big match or nudge
+    pub fn turns(&self) -> u32 {
+        self.turns
+    }
+
+    /// Count a finished cascade; only the player's turns count, and a big match starts over
+    /// `chain` is None when the turn matched nothing
+    pub fn record(&mut self, cause: ChainCause, chain: Option<&Chain>) {
+        if !matches!(cause, ChainCause::Swap(..) | ChainCause::Tap(_) | ChainCause::Link | ChainCause::Hold(_)) {
+            return;
+        }
+        let big = chain.is_some_and(|chain| {
+            chain.steps.iter().flat_map(|step| &step.matches).any(|m| m.positions.len() >= BIG_MATCH)
+        });
+        self.turns = if big { 0 } else { self.turns + 1 };
+    }
+
+    /// Check if the next refill should nudge
+    pub fn is_due(&self) -> bool {
+        self.threshold > 0 && self.turns >= self.threshold
+    }
+
+    /// Start counting over
+    pub fn reset(&mut self) {
+        self.turns = 0;
+    }
+}
+
+/// Find a type for the empty cell at `pos` that matches nothing by itself but lets a swap nearby
+/// make a big match; types are tried in index order
+pub fn setup_type(grid: &Grid, rules: &dyn Ruleset, pos: Vector2i) -> Option<SymbolType> {
+    SymbolType::ALL.into_iter().find(|symbol_type| {
+        let mut grid = grid.clone();
+        grid.set(pos.x as usize, pos.y as usize, Some(Symbol::with_type(pos, *symbol_type)));
+        rules.find_matches(&grid).is_empty() && has_big_swap(&grid, rules, pos)
+    })
+}
+
+/// Check if swapping two neighbors near `pos` makes a big match
+fn has_big_swap(grid: &Grid, rules: &dyn Ruleset, pos: Vector2i) -> bool {
+    for y in pos.y - SETUP_RADIUS..=pos.y + SETUP_RADIUS {
+        for x in pos.x - SETUP_RADIUS..=pos.x + SETUP_RADIUS {
+            let from = Vector2i::new(x, y);
+            for to in [Vector2i::new(x + 1, y), Vector2i::new(x, y + 1)] {
+                if !movable(grid, from) || !movable(grid, to) {
+                    continue;
+                }
+                let mut swapped = grid.clone();
+                if swapped.swap(from, to).is_err() {
+                    continue;
+                }
+                if rules.find_matches(&swapped).iter().any(|m| m.len() >= BIG_MATCH) {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Check if a cell holds a symbol the player could swap
+fn movable(grid: &Grid, pos: Vector2i) -> bool {
+    grid.is_valid(pos.x, pos.y) && grid.matchable(pos.x as usize, pos.y as usize).is_some()
+}
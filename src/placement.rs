@@ -0,0 +1,86 @@
+//! Board placement - where the grid sits inside the board rect
+//!
+//! The rect is in the node's local space. The grid is inset by a margin,
+//! aligned to one of nine anchors and optionally scaled to fill the rect.
+
+use godot::prelude::*;
+
+/// Nine-way alignment of the grid within its rect, in reading order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    #[default]
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// All anchors, in the order of their indices
+    pub const ALL: [Anchor; 9] = [
+        Anchor::TopLeft,
+        Anchor::Top,
+        Anchor::TopRight,
+        Anchor::Left,
+        Anchor::Center,
+        Anchor::Right,
+        Anchor::BottomLeft,
+        Anchor::Bottom,
+        Anchor::BottomRight,
+    ];
+
+    /// Get an anchor from its index
+    pub fn from_index(index: i32) -> Option<Self> {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| Self::ALL.get(index).copied())
+    }
+
+    /// Get the share of free space left before the grid on each axis
+    fn factors(&self) -> (f32, f32) {
+        let index = *self as usize;
+        ((index % 3) as f32 * 0.5, (index / 3) as f32 * 0.5)
+    }
+}
+
+/// Cell size and top-left corner of a placed grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub cell_size: f32,
+    pub offset: Vector2,
+}
+
+/// Place a grid of `columns` x `rows` cells inside `rect`
+/// With `fit`, the cell size is chosen so the grid fills the inset rect
+pub fn place(
+    rect: Rect2,
+    margin: f32,
+    anchor: Anchor,
+    columns: usize,
+    rows: usize,
+    cell_size: f32,
+    fit: bool,
+) -> Placement {
+    let (columns, rows) = (columns.max(1) as f32, rows.max(1) as f32);
+    let area_width = rect.size.x - margin * 2.0;
+    let area_height = rect.size.y - margin * 2.0;
+
+    let cell_size = if fit {
+        (area_width / columns).min(area_height / rows).max(1.0)
+    } else {
+        cell_size
+    };
+
+    let (fx, fy) = anchor.factors();
+    let offset = Vector2::new(
+        rect.position.x + margin + (area_width - columns * cell_size) * fx,
+        rect.position.y + margin + (area_height - rows * cell_size) * fy,
+    );
+
+    Placement { cell_size, offset }
+}
@@ -0,0 +1,47 @@
+//! Profiles - named save slots, each with its own files
+//!
+//! A profile is a directory under the board's profiles directory holding
+//! everything the board saves for a player: seen teach moments and the garden
+//! (progress), and a profile file with their settings, statistics and unlocked
+//! mechanics. The board starts out on its plain save paths; switching to a
+//! profile points every save path into that profile's directory and loads from
+//! there, so nothing is shared between slots.
+
+/// File in a profile's directory holding the seen teach moments
+pub const TEACH_MOMENTS_FILE: &str = "teach_moments.cfg";
+
+/// File in a profile's directory holding the garden
+pub const GARDEN_FILE: &str = "garden.cfg";
+
+/// File in a profile's directory holding settings, statistics and unlocks
+pub const PROFILE_FILE: &str = "profile.cfg";
+
+/// Longest profile name allowed
+pub const MAX_NAME_LEN: usize = 32;
+
+/// Check that a name can be a profile's directory: letters, digits, spaces, '-' and '_' only
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("profile name is empty".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!("profile name is longer than {} characters", MAX_NAME_LEN));
+    }
+    if name != name.trim() {
+        return Err("profile name starts or ends with a space".to_string());
+    }
+    match name.chars().find(|c| !(c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))) {
+        Some(c) => Err(format!("profile name contains '{}'", c)),
+        None => Ok(()),
+    }
+}
+
+/// Get the directory of a profile under `root`
+pub fn profile_dir(root: &str, name: &str) -> String {
+    format!("{}/{}", root.trim_end_matches('/'), name)
+}
+
+/// Get the path of one of a profile's files
+pub fn file_path(root: &str, name: &str, file: &str) -> String {
+    format!("{}/{}", profile_dir(root, name), file)
+}
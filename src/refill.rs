@@ -0,0 +1,141 @@
+//! Refill policies - how refilled symbols pick their type
+//!
+//! `Bag` works like a Tetris randomizer: every symbol type is dealt once, in
+//! shuffled order, before the bag is refilled. A type therefore never goes
+//! more than `2 * SymbolType::ALL.len() - 2` refills of its column without
+//! appearing.
+//!
+//! Refills come from a `SpawnQueue` holding each column's upcoming symbols,
+//! so they can be shown ahead like the next pieces of a falling-block game.
+//! Each column has its own generator and bag, so looking ahead in one column
+//! never changes what another one spawns.
+//!
+//! A `SpawnGuarantee` puts a floor on how often a symbol type spawns, for
+//! levels whose goal is collecting it: once a column has queued the most
+//! symbols in a row allowed without the type, its next one is that type. The
+//! gap is counted per column as symbols are queued, so the guarantee follows
+//! the seed like everything else.
+
+use crate::symbols::{Symbol, SymbolType};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::VecDeque;
+
+/// Most upcoming symbols a column can be previewed for
+pub const MAX_PREVIEW: usize = 64;
+
+/// How refill picks symbol types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RefillPolicy {
+    /// Defer to the ruleset's spawn_symbol
+    #[default]
+    Random,
+    /// Deal every type once per shuffled bag
+    Bag,
+}
+
+impl RefillPolicy {
+    /// All policies, in the order of their indices
+    pub const ALL: [RefillPolicy; 2] = [RefillPolicy::Random, RefillPolicy::Bag];
+
+    /// Get a policy from its index
+    pub fn from_index(index: i32) -> Option<Self> {
+        usize::try_from(index).ok().and_then(|index| Self::ALL.get(index).copied())
+    }
+}
+
+/// A floor on how often refills spawn a symbol type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpawnGuarantee {
+    pub symbol_type: SymbolType,
+    /// Most symbols a column queues in a row without the type
+    pub max_gap: u32,
+}
+
+impl SpawnGuarantee {
+    /// Guarantee at least `rate` of each column's spawns are `symbol_type` (None for a rate of 0 or less)
+    pub fn from_rate(symbol_type: SymbolType, rate: f64) -> Option<Self> {
+        (rate > 0.0).then(|| Self {
+            symbol_type,
+            max_gap: ((1.0 / rate.min(1.0)).ceil() as u32).saturating_sub(1),
+        })
+    }
+}
+
+/// Shuffled bag of symbol types
+#[derive(Debug, Clone, Default)]
+pub struct SymbolBag {
+    remaining: Vec<SymbolType>,
+}
+
+impl SymbolBag {
+    /// Take the next type, refilling and shuffling the bag when it is empty
+    pub fn draw<R: Rng + ?Sized>(&mut self, rng: &mut R) -> SymbolType {
+        if self.remaining.is_empty() {
+            self.remaining = SymbolType::ALL.to_vec();
+            self.remaining.shuffle(rng);
+        }
+        self.remaining.pop().unwrap_or(SymbolType::Red)
+    }
+}
+
+/// One column's random source and the symbols it will spawn next
+#[derive(Debug, Clone)]
+pub struct ColumnQueue {
+    pub rng: StdRng,
+    pub bag: SymbolBag,
+    pub upcoming: VecDeque<Symbol>,
+    /// Symbols queued since the guaranteed type last was
+    pub gap: u32,
+}
+
+impl ColumnQueue {
+    /// Queue a symbol, or the guaranteed type instead if the column has gone too long without it
+    pub fn push(&mut self, symbol: Symbol, guarantee: Option<SpawnGuarantee>) {
+        let Some(guarantee) = guarantee else {
+            self.upcoming.push_back(symbol);
+            return;
+        };
+        if symbol.current_type() == guarantee.symbol_type {
+            self.gap = 0;
+            self.upcoming.push_back(symbol);
+        } else if self.gap >= guarantee.max_gap {
+            self.gap = 0;
+            self.upcoming.push_back(Symbol::with_type(symbol.grid_pos, guarantee.symbol_type));
+        } else {
+            self.gap += 1;
+            self.upcoming.push_back(symbol);
+        }
+    }
+}
+
+/// Upcoming refill symbols, per column
+#[derive(Debug, Clone, Default)]
+pub struct SpawnQueue {
+    columns: Vec<ColumnQueue>,
+}
+
+impl SpawnQueue {
+    /// Start empty queues for `width` columns, seeding their generators from `rng`
+    pub fn reset<R: Rng + ?Sized>(&mut self, width: usize, rng: &mut R) {
+        self.columns = (0..width)
+            .map(|_| ColumnQueue {
+                rng: StdRng::seed_from_u64(rng.next_u64()),
+                bag: SymbolBag::default(),
+                upcoming: VecDeque::new(),
+                gap: 0,
+            })
+            .collect();
+    }
+
+    /// Get the number of columns queued for
+    pub fn width(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Get a column's queue
+    pub fn column_mut(&mut self, column: usize) -> Option<&mut ColumnQueue> {
+        self.columns.get_mut(column)
+    }
+}
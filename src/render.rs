@@ -0,0 +1,95 @@
+//! Offline rasterization of the logical grid into images, and boards read back from images
+
+use crate::replay::Replay;
+use crate::symbols::{Grid, SymbolType};
+use godot::classes::Image;
+use godot::classes::image::Format;
+use godot::prelude::*;
+
+/// Background color behind the cells
+const BACKGROUND: Color = Color::from_rgb(0.12, 0.14, 0.18);
+
+/// Size in pixels of a cell in rendered replay frames
+const FRAME_CELL_SIZE: i32 = 32;
+
+/// Draw the grid's current symbol colors into a new image of the given size
+/// Cells are square and centered; returns None if the size is not positive
+pub fn rasterize_grid(grid: &Grid, size: Vector2i) -> Option<Gd<Image>> {
+    if size.x <= 0 || size.y <= 0 || grid.width == 0 || grid.height == 0 {
+        return None;
+    }
+
+    let mut image = Image::create_empty(size.x, size.y, false, Format::RGBA8)?;
+    image.fill(BACKGROUND);
+
+    let cell = (size.x / grid.width as i32).min(size.y / grid.height as i32).max(1);
+    let padding = cell / 10;
+    let origin = Vector2i::new(
+        (size.x - cell * grid.width as i32) / 2,
+        (size.y - cell * grid.height as i32) / 2,
+    );
+
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            let Some(symbol) = grid.get(x, y) else {
+                continue;
+            };
+            let rect = Rect2i::new(
+                Vector2i::new(origin.x + x as i32 * cell + padding, origin.y + y as i32 * cell + padding),
+                Vector2i::new(cell - padding * 2, cell - padding * 2),
+            );
+            image.fill_rect(rect, symbol.current_color());
+        }
+    }
+
+    Some(image)
+}
+
+/// Render a frame of each board state in the replay's `from_move..to_move` segment
+/// See `Replay::play` for the states shown
+pub fn replay_frames(replay: &Replay, from_move: usize, to_move: usize, max_cascade_depth: i32) -> Array<Gd<Image>> {
+    let mut frames = Array::new();
+    replay.play(from_move, to_move, max_cascade_depth, |grid| {
+        let size = Vector2i::new(grid.width as i32 * FRAME_CELL_SIZE, grid.height as i32 * FRAME_CELL_SIZE);
+        if let Some(image) = rasterize_grid(grid, size) {
+            frames.push(&image);
+        }
+    });
+    frames
+}
+
+/// Read a board of at most `max_width` x `max_height` cells from an image
+/// Each cell is the symbol type nearest the average color of its block of
+/// pixels; an image smaller than the board maps one pixel per cell.
+/// Returns the board size and a symbol per cell, or None for an empty image
+pub fn sample_image(image: &Image, max_width: usize, max_height: usize) -> Option<(usize, usize, Vec<SymbolType>)> {
+    let (image_width, image_height) = (image.get_width().max(0) as usize, image.get_height().max(0) as usize);
+    let width = image_width.min(max_width);
+    let height = image_height.min(max_height);
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let mut cells = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            // Pixel block covered by this cell
+            let (left, right) = (x * image_width / width, (x + 1) * image_width / width);
+            let (top, bottom) = (y * image_height / height, (y + 1) * image_height / height);
+
+            let mut sum = [0.0f32; 3];
+            for py in top..bottom {
+                for px in left..right {
+                    let pixel = image.get_pixel(px as i32, py as i32);
+                    sum[0] += pixel.r;
+                    sum[1] += pixel.g;
+                    sum[2] += pixel.b;
+                }
+            }
+            let count = ((right - left) * (bottom - top)).max(1) as f32;
+            cells.push(SymbolType::nearest(Color::from_rgb(sum[0] / count, sum[1] / count, sum[2] / count)));
+        }
+    }
+
+    Some((width, height, cells))
+}
@@ -0,0 +1,216 @@
+//! Replays - the moves of a game recorded from its dealt board and played back offline
+//!
+//! A replay keeps a copy of the model as it was dealt, random source
+//! included, and every move the player made since. Playing it back on that
+//! copy reaches the same boards without the scene tree, so a segment can be
+//! rendered to frames. Scripted board edits are recorded with the moves;
+//! refills biased by a frenzy and sandbox edits are not, so replays of games
+//! that used them drift from the original.
+
+use crate::edits::BoardEdit;
+use crate::engine::GameEngine;
+use crate::model::BoardModel;
+use crate::roulette::RouletteEffect;
+use crate::symbols::{Grid, Symbol};
+use godot::prelude::*;
+
+/// A move as it reached the model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayMove {
+    /// Swap two symbols, and their mirror images in mirror mode
+    Swap {
+        from: Vector2i,
+        to: Vector2i,
+        mirrored: bool,
+    },
+    /// Turn one symbol a face
+    Tap { pos: Vector2i, clockwise: bool },
+    /// A board rotation, with the roulette effect it fired
+    Rotate(RouletteEffect),
+    /// Clear a linked path
+    Link(Vec<Vector2i>),
+    /// Exchange a symbol with the hold slot
+    Hold(Vector2i),
+    /// Turn the whole grid a quarter turn
+    Spin { clockwise: bool },
+    /// A batch of scripted board edits
+    Edit(Vec<BoardEdit>),
+}
+
+impl ReplayMove {
+    /// Check if the move is one the player makes as a turn (swap, tap, link or hold)
+    pub fn is_turn(&self) -> bool {
+        matches!(
+            self,
+            ReplayMove::Swap { .. } | ReplayMove::Tap { .. } | ReplayMove::Link(_) | ReplayMove::Hold(_)
+        )
+    }
+
+    /// Describe the move for scripts: { kind, ... } where kind is swap (from, to, mirrored),
+    /// tap (pos, clockwise), rotate (effect, index: the row or column hit, -1 for none; a spawn_special also has
+    /// pos and special), link (cells), hold (pos), spin (clockwise) or edit (cells: every cell the edits change)
+    pub fn to_dictionary(&self) -> Dictionary {
+        let mut result = Dictionary::new();
+        match self {
+            ReplayMove::Swap { from, to, mirrored } => {
+                result.set("kind", "swap");
+                result.set("from", *from);
+                result.set("to", *to);
+                result.set("mirrored", *mirrored);
+            }
+            ReplayMove::Tap { pos, clockwise } => {
+                result.set("kind", "tap");
+                result.set("pos", *pos);
+                result.set("clockwise", *clockwise);
+            }
+            ReplayMove::Rotate(effect) => {
+                let (name, index) = match effect {
+                    RouletteEffect::RotateFaces => ("rotate_faces", -1),
+                    RouletteEffect::ShuffleRow(row) => ("shuffle_row", *row as i64),
+                    RouletteEffect::FreezeColumn(column) => ("freeze_column", *column as i64),
+                    RouletteEffect::SpawnSpecial(pos, kind) => {
+                        result.set("pos", *pos);
+                        result.set("special", kind.name());
+                        ("spawn_special", -1)
+                    }
+                };
+                result.set("kind", "rotate");
+                result.set("effect", name);
+                result.set("index", index);
+            }
+            ReplayMove::Link(cells) => {
+                result.set("kind", "link");
+                result.set("cells", cells.iter().copied().collect::<Array<Vector2i>>());
+            }
+            ReplayMove::Hold(pos) => {
+                result.set("kind", "hold");
+                result.set("pos", *pos);
+            }
+            ReplayMove::Spin { clockwise } => {
+                result.set("kind", "spin");
+                result.set("clockwise", *clockwise);
+            }
+            ReplayMove::Edit(edits) => {
+                result.set("kind", "edit");
+                result.set("cells", edits.iter().flat_map(|edit| edit.cells()).copied().collect::<Array<Vector2i>>());
+            }
+        }
+        result
+    }
+}
+
+/// The dealt board and the moves made on it
+#[derive(Debug, Clone)]
+pub struct Replay {
+    start: BoardModel,
+    moves: Vec<ReplayMove>,
+}
+
+impl Replay {
+    /// Start recording from the given model state
+    pub fn new(start: BoardModel) -> Self {
+        Self {
+            start,
+            moves: Vec::new(),
+        }
+    }
+
+    /// Record a move
+    pub fn record(&mut self, mv: ReplayMove) {
+        self.moves.push(mv);
+    }
+
+    /// Drop every move after the first `move_count`, as when the board rewinds
+    pub fn truncate(&mut self, move_count: usize) {
+        self.moves.truncate(move_count);
+    }
+
+    /// Get the number of recorded moves
+    pub fn move_count(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Get the recorded moves in order
+    pub fn moves(&self) -> &[ReplayMove] {
+        &self.moves
+    }
+
+    /// Estimate the memory the replay holds, in bytes: the dealt board and the move list
+    pub fn approximate_bytes(&self) -> usize {
+        let start = size_of::<BoardModel>() + self.start.grid().cells.capacity() * size_of::<Option<Symbol>>();
+        let links: usize = self
+            .moves
+            .iter()
+            .map(|mv| match mv {
+                ReplayMove::Link(cells) => cells.capacity() * size_of::<Vector2i>(),
+                ReplayMove::Edit(edits) => edits.capacity() * size_of::<BoardEdit>(),
+                _ => 0,
+            })
+            .sum();
+        start + self.moves.capacity() * size_of::<ReplayMove>() + links
+    }
+
+    /// Get an engine on a copy of the dealt board, ready to play the moves from the start
+    pub fn engine(&self, max_cascade_depth: i32) -> GameEngine {
+        GameEngine::new(self.start.clone(), max_cascade_depth)
+    }
+
+    /// Play the moves in `from_move..to_move`, after silently playing those before
+    /// `visit` sees the board before the first move and after each step that changed it:
+    /// the move itself, then each clear, fall and refill of the cascade it set off
+    pub fn play(&self, from_move: usize, to_move: usize, max_cascade_depth: i32, mut visit: impl FnMut(&Grid)) {
+        let mut engine = self.engine(max_cascade_depth);
+        let to_move = to_move.min(self.moves.len());
+
+        for (idx, mv) in self.moves.iter().enumerate().take(to_move) {
+            let shown = idx >= from_move;
+            if idx == from_move {
+                visit(engine.model().grid());
+            }
+            let frame = |grid: &Grid| {
+                if shown {
+                    visit(grid);
+                }
+            };
+            if let Err(err) = engine.play(mv, frame) {
+                godot_warn!("Replay move {} failed: {}", idx, err);
+                return;
+            }
+        }
+    }
+}
+
+/// A recorded game handed to scripts, to render or keep
+#[derive(GodotClass)]
+#[class(no_init, base=RefCounted)]
+pub struct GameReplay {
+    base: Base<RefCounted>,
+    replay: Replay,
+}
+
+#[godot_api]
+impl GameReplay {
+    /// Get the number of recorded moves
+    #[func]
+    fn get_move_count(&self) -> i32 {
+        self.replay.move_count() as i32
+    }
+
+    /// Get the recorded moves in order, as dictionaries (see `ReplayMove::to_dictionary`)
+    #[func]
+    fn get_moves(&self) -> Array<Dictionary> {
+        self.replay.moves().iter().map(ReplayMove::to_dictionary).collect()
+    }
+}
+
+impl GameReplay {
+    /// Wrap a copy of a replay for scripts
+    pub fn from_replay(replay: Replay) -> Gd<Self> {
+        Gd::from_init_fn(|base| Self { base, replay })
+    }
+
+    /// Get the wrapped replay
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+}
@@ -0,0 +1,81 @@
+//! Level-end results sequencing (objective ticks, star reveals, records)
+
+use std::collections::VecDeque;
+
+/// A single step of the results fanfare
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultEvent {
+    /// An objective is ticked off (index of the objective)
+    ObjectiveTick(i32),
+    /// A star is revealed (1-based star number)
+    StarReveal(i32),
+    /// The final score beats the previous best
+    NewRecord(i32),
+}
+
+impl ResultEvent {
+    /// Get the event kind name used in signal payloads
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ResultEvent::ObjectiveTick(_) => "objective",
+            ResultEvent::StarReveal(_) => "star",
+            ResultEvent::NewRecord(_) => "record",
+        }
+    }
+
+    /// Get the event value (objective index, star number or score)
+    pub fn value(&self) -> i32 {
+        match self {
+            ResultEvent::ObjectiveTick(v) | ResultEvent::StarReveal(v) | ResultEvent::NewRecord(v) => *v,
+        }
+    }
+}
+
+/// Count how many star thresholds a score reaches
+pub fn stars_for_score(score: i32, thresholds: &[i32]) -> i32 {
+    thresholds.iter().filter(|&&t| score >= t).count() as i32
+}
+
+/// An ordered queue of result events released at a fixed spacing
+#[derive(Debug, Clone, Default)]
+pub struct ResultsSequence {
+    events: VecDeque<ResultEvent>,
+    stars: i32,
+    timer: f64,
+}
+
+impl ResultsSequence {
+    /// Build the sequence: objectives first, then stars, then the record
+    pub fn new(objectives_completed: i32, stars: i32, record: Option<i32>) -> Self {
+        let mut events = VecDeque::new();
+        events.extend((0..objectives_completed).map(ResultEvent::ObjectiveTick));
+        events.extend((1..=stars).map(ResultEvent::StarReveal));
+        events.extend(record.map(ResultEvent::NewRecord));
+
+        Self {
+            events,
+            stars,
+            timer: 0.0,
+        }
+    }
+
+    /// Get the number of stars awarded
+    pub fn stars(&self) -> i32 {
+        self.stars
+    }
+
+    /// Check if all events have been released
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Advance the timer, releasing the next event once `spacing` has elapsed
+    pub fn advance(&mut self, delta: f64, spacing: f64) -> Option<ResultEvent> {
+        self.timer += delta;
+        if self.timer < spacing {
+            return None;
+        }
+        self.timer = 0.0;
+        self.events.pop_front()
+    }
+}
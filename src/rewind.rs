@@ -0,0 +1,76 @@
+//! Rewind - snapshots of the board before each move, for turning back time
+//!
+//! The board keeps a ring buffer of the model as it was before each of the
+//! player's recent moves, with the move count, objective progress and replay
+//! length at that point. Rewinding M moves restores the snapshot M back and drops it and
+//! every later one; the grids of the later snapshots are the frames the board
+//! plays backwards on the way there.
+
+use crate::model::BoardModel;
+use crate::objectives::ObjectiveSet;
+use crate::symbols::Grid;
+use std::collections::VecDeque;
+
+/// The board just before a move
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub model: BoardModel,
+    /// Moves the player had made
+    pub moves_made: i32,
+    /// Symbols of the objective color cleared
+    pub objective_cleared: i32,
+    /// Progress on the level's objectives
+    pub objectives: ObjectiveSet,
+    /// Moves the replay had recorded
+    pub replay_moves: usize,
+}
+
+/// The most recent snapshots, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct RewindHistory {
+    snapshots: VecDeque<Snapshot>,
+    capacity: usize,
+}
+
+impl RewindHistory {
+    /// Set how many moves can be rewound (0 keeps no snapshots), dropping the oldest extras
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.snapshots.len() > capacity {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Get how many moves can be rewound right now
+    pub fn available(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Remember the board before a move, dropping the oldest snapshot when full
+    pub fn record(&mut self, snapshot: Snapshot) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    /// Take the snapshot from `moves` moves ago, with the grids of the ones dropped after it,
+    /// newest first; None if that many moves are not recorded
+    pub fn rewind(&mut self, moves: usize) -> Option<(Snapshot, Vec<Grid>)> {
+        if moves == 0 || moves > self.snapshots.len() {
+            return None;
+        }
+        let later = self.snapshots.split_off(self.snapshots.len() - moves + 1);
+        let frames = later.into_iter().rev().map(|snapshot| snapshot.model.grid().clone()).collect();
+        let snapshot = self.snapshots.pop_back()?;
+        Some((snapshot, frames))
+    }
+
+    /// Forget every snapshot
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
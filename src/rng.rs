@@ -0,0 +1,32 @@
+//! Random sources - generators that seed the same way on every export target
+//!
+//! Unseeded boards take their seed from the operating system's entropy
+//! source, which single-threaded web exports don't always provide. With the
+//! `wasm` feature the seed comes from Godot's global random number generator
+//! instead, which the engine seeds itself at startup on every platform.
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Create a generator from a board seed (negative seeds from entropy)
+pub fn seeded(seed: i64) -> StdRng {
+    if seed < 0 {
+        from_entropy()
+    } else {
+        StdRng::seed_from_u64(seed as u64)
+    }
+}
+
+/// Create a generator seeded from the operating system
+#[cfg(not(feature = "wasm"))]
+pub fn from_entropy() -> StdRng {
+    StdRng::from_entropy()
+}
+
+/// Create a generator seeded from Godot's global random number generator
+#[cfg(feature = "wasm")]
+pub fn from_entropy() -> StdRng {
+    let high = godot::global::randi() as u64;
+    let low = godot::global::randi() as u64;
+    StdRng::seed_from_u64((high << 32) | (low & 0xffff_ffff))
+}
@@ -0,0 +1,77 @@
+//! Rotation statistics - how much the revolving mechanic shapes play
+//!
+//! Counts the rotations the player has lived through and the matches each
+//! kind of move made directly (the first step of its chain, not the cascade
+//! after it), and measures how long the player takes to make a match of
+//! their own after each rotation.
+
+use crate::chain::{Chain, ChainCause};
+
+/// Rotation and match counters for the current game
+#[derive(Debug, Clone, Default)]
+pub struct RotationStats {
+    rotations: u32,
+    rotation_matches: u32,
+    swap_matches: u32,
+    other_matches: u32,
+    /// Game time of the last rotation the player has not matched after yet
+    pending_rotation: Option<f64>,
+    delay_total: f64,
+    delay_count: u32,
+}
+
+impl RotationStats {
+    /// Count a rotation that finished at game time `now`
+    pub fn record_rotation(&mut self, now: f64) {
+        self.rotations += 1;
+        self.pending_rotation = Some(now);
+    }
+
+    /// Count the matches a chain's cause made directly, timing the player's first match after a rotation
+    pub fn record_chain(&mut self, chain: &Chain, now: f64) {
+        let direct = chain.steps.first().map_or(0, |step| step.matches.len()) as u32;
+        if direct == 0 {
+            return;
+        }
+
+        match chain.cause {
+            ChainCause::Rotation => {
+                self.rotation_matches += direct;
+                return;
+            }
+            ChainCause::Swap(..) => self.swap_matches += direct,
+            ChainCause::Link | ChainCause::Tap(_) | ChainCause::Hold(_) | ChainCause::Spin => {
+                self.other_matches += direct
+            }
+        }
+        if let Some(rotated_at) = self.pending_rotation.take() {
+            self.delay_total += now - rotated_at;
+            self.delay_count += 1;
+        }
+    }
+
+    /// Get the number of rotations so far
+    pub fn rotations(&self) -> u32 {
+        self.rotations
+    }
+
+    /// Get the matches made directly by rotations
+    pub fn rotation_matches(&self) -> u32 {
+        self.rotation_matches
+    }
+
+    /// Get the matches made directly by swaps
+    pub fn swap_matches(&self) -> u32 {
+        self.swap_matches
+    }
+
+    /// Get the matches made directly by taps, links and the hold slot
+    pub fn other_matches(&self) -> u32 {
+        self.other_matches
+    }
+
+    /// Get the average game time between a rotation and the player's next match, if there was one
+    pub fn average_time_to_match(&self) -> Option<f64> {
+        (self.delay_count > 0).then(|| self.delay_total / self.delay_count as f64)
+    }
+}
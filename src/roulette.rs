@@ -0,0 +1,155 @@
+//! Rotation roulette - a level modifier that replaces each rotation with a random effect
+//!
+//! The kind of effect is picked one cycle ahead so it can be announced; the row,
+//! column or cell it hits is picked when it fires. Picks come from their own
+//! generator, seeded from the board seed, so a seeded board always spins the
+//! same effects.
+
+use crate::modifiers::{Modifier, ModifierContext, ModifierEvent};
+use crate::rng;
+use crate::symbols::{Grid, SymbolKind};
+use godot::prelude::*;
+use rand::Rng;
+use rand::rngs::StdRng;
+
+/// The kinds of effect a rotation can turn into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouletteKind {
+    /// Every symbol turns to its next face, like a plain rotation
+    RotateFaces,
+    /// The symbols of one row are shuffled
+    ShuffleRow,
+    /// One column can't be moved until the next rotation
+    FreezeColumn,
+    /// One plain symbol becomes striped or a color bomb
+    SpawnSpecial,
+}
+
+impl RouletteKind {
+    /// All kinds, in pick order
+    pub const ALL: [RouletteKind; 4] = [
+        RouletteKind::RotateFaces,
+        RouletteKind::ShuffleRow,
+        RouletteKind::FreezeColumn,
+        RouletteKind::SpawnSpecial,
+    ];
+
+    /// Name used in signals
+    pub fn name(&self) -> &'static str {
+        match self {
+            RouletteKind::RotateFaces => "rotate_faces",
+            RouletteKind::ShuffleRow => "shuffle_row",
+            RouletteKind::FreezeColumn => "freeze_column",
+            RouletteKind::SpawnSpecial => "spawn_special",
+        }
+    }
+}
+
+/// Specials a spawn can turn a symbol into
+const SPAWNED: [SymbolKind; 3] = [SymbolKind::StripedRow, SymbolKind::StripedColumn, SymbolKind::ColorBomb];
+
+/// An effect that fired, with the row, column or cell it hit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouletteEffect {
+    RotateFaces,
+    ShuffleRow(usize),
+    FreezeColumn(usize),
+    SpawnSpecial(Vector2i, SymbolKind),
+}
+
+/// The upcoming effect and the one currently in force
+#[derive(Debug, Clone)]
+pub struct Roulette {
+    rng: StdRng,
+    upcoming: RouletteKind,
+    frozen_column: Option<usize>,
+}
+
+impl Roulette {
+    /// Create a roulette from a board seed (-1 seeds from entropy)
+    pub fn new(seed: i64) -> Self {
+        let mut rng = rng::seeded(seed);
+        let upcoming = Self::pick(&mut rng);
+        Self {
+            rng,
+            upcoming,
+            frozen_column: None,
+        }
+    }
+
+    /// Get the kind of effect the next rotation will have
+    pub fn upcoming(&self) -> RouletteKind {
+        self.upcoming
+    }
+
+    /// Fire the upcoming effect on a grid and pick the next one
+    /// A spawn with no plain symbol to turn special falls back to a plain rotation
+    pub fn spin(&mut self, grid: &Grid) -> RouletteEffect {
+        let effect = match self.upcoming {
+            RouletteKind::RotateFaces => RouletteEffect::RotateFaces,
+            RouletteKind::ShuffleRow => RouletteEffect::ShuffleRow(self.rng.gen_range(0..grid.height.max(1))),
+            RouletteKind::FreezeColumn => RouletteEffect::FreezeColumn(self.rng.gen_range(0..grid.width.max(1))),
+            RouletteKind::SpawnSpecial => self.pick_spawn(grid),
+        };
+        self.frozen_column = match effect {
+            RouletteEffect::FreezeColumn(column) => Some(column),
+            _ => None,
+        };
+        self.upcoming = Self::pick(&mut self.rng);
+        effect
+    }
+
+    /// Check if a cell is in the frozen column
+    pub fn is_frozen(&self, pos: Vector2i) -> bool {
+        self.frozen_column.is_some_and(|column| pos.x == column as i32)
+    }
+
+    /// Pick a plain symbol and the special it becomes
+    fn pick_spawn(&mut self, grid: &Grid) -> RouletteEffect {
+        let plain: Vec<Vector2i> = (0..grid.height)
+            .flat_map(|y| (0..grid.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| {
+                grid.matchable(x, y)
+                    .is_some_and(|symbol| symbol.kind == SymbolKind::Plain && !symbol.seeker && !symbol.wild)
+            })
+            .map(|(x, y)| Vector2i::new(x as i32, y as i32))
+            .collect();
+        if plain.is_empty() {
+            return RouletteEffect::RotateFaces;
+        }
+        let pos = plain[self.rng.gen_range(0..plain.len())];
+        let kind = SPAWNED[self.rng.gen_range(0..SPAWNED.len())];
+        RouletteEffect::SpawnSpecial(pos, kind)
+    }
+
+    /// Pick a kind with equal odds
+    fn pick(rng: &mut StdRng) -> RouletteKind {
+        RouletteKind::ALL[rng.gen_range(0..RouletteKind::ALL.len())]
+    }
+}
+
+impl Modifier for Roulette {
+    fn name(&self) -> &'static str {
+        "roulette"
+    }
+
+    fn on_attach(&mut self, ctx: &mut ModifierContext) {
+        ctx.emit(ModifierEvent::RotationAnnounced(self.upcoming()));
+    }
+
+    fn pick_rotation(&mut self, ctx: &mut ModifierContext) -> Option<RouletteEffect> {
+        Some(self.spin(ctx.model.grid()))
+    }
+
+    fn on_rotation(&mut self, ctx: &mut ModifierContext, _effect: RouletteEffect) {
+        ctx.emit(ModifierEvent::RotationAnnounced(self.upcoming()));
+    }
+
+    fn locks(&self, pos: Vector2i) -> bool {
+        self.is_frozen(pos)
+    }
+
+    fn upcoming_rotation(&self) -> Option<RouletteKind> {
+        Some(self.upcoming())
+    }
+}
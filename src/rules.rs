@@ -0,0 +1,222 @@
+//! Rulesets - the pluggable rules of a game variant
+//!
+//! Matching, scoring, spawning and rotation all go through a `Ruleset`, so a
+//! variant is a new rule struct rather than flags spread through the board.
+
+use crate::combo::ComboMatrix;
+use crate::combo_timer::ComboMode;
+use crate::matching::{Match, MatchFinder};
+use crate::symbols::{Grid, Symbol, SymbolType};
+use godot::prelude::*;
+use rand::RngCore;
+use std::fmt;
+use std::sync::Arc;
+
+/// The rules of a game variant
+pub trait Ruleset: fmt::Debug + Send + Sync {
+    /// Get the name of this variant
+    fn name(&self) -> &'static str;
+
+    /// Find all matches on the grid
+    fn find_matches(&self, grid: &Grid) -> Vec<Match>;
+
+    /// Score a match at the given combo multiplier
+    fn score_match(&self, m: &Match, combo: i32) -> i32;
+
+    /// Create the symbol spawned into an empty cell during refill
+    fn spawn_symbol(&self, pos: Vector2i, rng: &mut dyn RngCore) -> Symbol;
+
+    /// Check if symbols rotate on the rotation timer
+    fn rotates(&self) -> bool;
+
+    /// Rotate every symbol on the grid
+    fn rotate(&self, grid: &mut Grid);
+
+    /// Check if the player moves by swapping neighbors
+    fn swaps(&self) -> bool {
+        true
+    }
+
+    /// Check if the player moves by tapping a symbol to rotate it one face
+    fn taps(&self) -> bool {
+        false
+    }
+
+    /// Check if swapping two positions would create a match
+    fn would_create_match(&self, grid: &Grid, pos1: Vector2i, pos2: Vector2i) -> bool {
+        let mut temp_grid = grid.clone();
+        temp_grid.swap(pos1, pos2).is_ok() && !self.find_matches(&temp_grid).is_empty()
+    }
+
+    /// Get what pairs of specials do when swapped into each other
+    fn special_combos(&self) -> ComboMatrix {
+        ComboMatrix::standard()
+    }
+
+    /// Get how the combo multiplier is kept up
+    fn combo_mode(&self) -> ComboMode {
+        ComboMode::Cascade
+    }
+
+    /// Find the first swap that would create a match, scanning row by row
+    fn find_possible_move(&self, grid: &Grid) -> Option<(Vector2i, Vector2i)> {
+        for y in 0..grid.height as i32 {
+            for x in 0..grid.width as i32 {
+                let pos = Vector2i::new(x, y);
+                for neighbor in [Vector2i::new(x + 1, y), Vector2i::new(x, y + 1)] {
+                    if grid.is_valid(neighbor.x, neighbor.y) && self.would_create_match(grid, pos, neighbor) {
+                        return Some((pos, neighbor));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Find the first cell whose symbol makes a match after one to three taps, scanning row by row
+    fn find_possible_tap(&self, grid: &Grid) -> Option<Vector2i> {
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                let mut temp_grid = grid.clone();
+                for _ in 0..3 {
+                    let Some(symbol) = temp_grid.get_mut(x, y) else {
+                        break;
+                    };
+                    symbol.rotate();
+                    if !self.find_matches(&temp_grid).is_empty() {
+                        return Some(Vector2i::new(x as i32, y as i32));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Get a built-in ruleset by index: 0 classic revolving, 1 tap to rotate, 2 combo rush
+pub fn builtin(index: i32) -> Option<Arc<dyn Ruleset>> {
+    match index {
+        0 => Some(Arc::new(ClassicRevolvingRules)),
+        1 => Some(Arc::new(TapRotateRules)),
+        2 => Some(Arc::new(ComboRushRules)),
+        _ => None,
+    }
+}
+
+/// The original rules: straight lines of 3+, combo-multiplied scores and
+/// all symbols revolving to their next face together
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClassicRevolvingRules;
+
+impl Ruleset for ClassicRevolvingRules {
+    fn name(&self) -> &'static str {
+        "classic_revolving"
+    }
+
+    fn find_matches(&self, grid: &Grid) -> Vec<Match> {
+        MatchFinder::find_all(grid)
+    }
+
+    fn score_match(&self, m: &Match, combo: i32) -> i32 {
+        m.score() * combo
+    }
+
+    fn spawn_symbol(&self, pos: Vector2i, rng: &mut dyn RngCore) -> Symbol {
+        Symbol::with_type(pos, SymbolType::random(rng))
+    }
+
+    fn rotates(&self) -> bool {
+        true
+    }
+
+    fn rotate(&self, grid: &mut Grid) {
+        grid.rotate_all();
+    }
+
+    fn would_create_match(&self, grid: &Grid, pos1: Vector2i, pos2: Vector2i) -> bool {
+        MatchFinder::would_create_match(grid, pos1, pos2)
+    }
+}
+
+/// No swapping and no rotation timer: the player taps a symbol to turn it to
+/// its next face, and matches form purely from those rotations
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TapRotateRules;
+
+impl Ruleset for TapRotateRules {
+    fn name(&self) -> &'static str {
+        "tap_rotate"
+    }
+
+    fn find_matches(&self, grid: &Grid) -> Vec<Match> {
+        MatchFinder::find_all(grid)
+    }
+
+    fn score_match(&self, m: &Match, combo: i32) -> i32 {
+        m.score() * combo
+    }
+
+    fn spawn_symbol(&self, pos: Vector2i, rng: &mut dyn RngCore) -> Symbol {
+        Symbol::with_type(pos, SymbolType::random(rng))
+    }
+
+    fn rotates(&self) -> bool {
+        false
+    }
+
+    fn rotate(&self, grid: &mut Grid) {
+        grid.rotate_all();
+    }
+
+    fn swaps(&self) -> bool {
+        false
+    }
+
+    fn taps(&self) -> bool {
+        true
+    }
+}
+
+/// The classic rules with a timed combo: the multiplier carries over to the
+/// next move as long as it matches within a few seconds of the last
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ComboRushRules;
+
+impl ComboRushRules {
+    /// Seconds between matches that keep the combo going
+    const WINDOW: f64 = 3.0;
+}
+
+impl Ruleset for ComboRushRules {
+    fn name(&self) -> &'static str {
+        "combo_rush"
+    }
+
+    fn find_matches(&self, grid: &Grid) -> Vec<Match> {
+        MatchFinder::find_all(grid)
+    }
+
+    fn score_match(&self, m: &Match, combo: i32) -> i32 {
+        m.score() * combo
+    }
+
+    fn spawn_symbol(&self, pos: Vector2i, rng: &mut dyn RngCore) -> Symbol {
+        Symbol::with_type(pos, SymbolType::random(rng))
+    }
+
+    fn rotates(&self) -> bool {
+        true
+    }
+
+    fn rotate(&self, grid: &mut Grid) {
+        grid.rotate_all();
+    }
+
+    fn would_create_match(&self, grid: &Grid, pos1: Vector2i, pos2: Vector2i) -> bool {
+        MatchFinder::would_create_match(grid, pos1, pos2)
+    }
+
+    fn combo_mode(&self) -> ComboMode {
+        ComboMode::Timed { window: Self::WINDOW }
+    }
+}
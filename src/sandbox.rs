@@ -0,0 +1,78 @@
+//! Sandbox mode - free placement with undo and no scoring
+//!
+//! Pieces are described by palette dictionaries: `{ "symbol": "red" }` or
+//! `{ "symbol": 2 }` (a symbol type index) places a symbol, `{ "special":
+//! "striped_row" }` turns the symbol in the cell into a special (any name
+//! `apply_board_edit` takes), and `{ "obstacle": "stone", "strength": 2 }` puts
+//! down a stone, ice or chocolate ("none" clears it). Keys combine, so
+//! `{ "symbol": "red", "special": "color_bomb" }` places a color bomb.
+
+use crate::edits::{self, BoardEdit, Special};
+use crate::symbols::{Grid, SymbolType};
+use godot::prelude::*;
+
+/// Undo snapshots kept before the oldest is dropped
+const MAX_UNDO: usize = 100;
+
+/// Parse a palette dictionary into the board edits that place it at a cell
+pub fn parse_spec(pos: Vector2i, spec: &Dictionary) -> Result<Vec<BoardEdit>, String> {
+    let mut placed = Vec::new();
+    if let Some(symbol) = spec.get("symbol") {
+        placed.push(BoardEdit::Set {
+            pos,
+            symbol_type: parse_symbol(&symbol)?,
+        });
+    }
+    if let Some(special) = spec.get("special") {
+        let name = special.try_to::<GString>().map_err(|_| "special must be a name")?.to_string();
+        let special = Special::from_name(&name)
+            .ok_or(format!("unknown special: {} (available: {})", name, Special::NAMES.join(", ")))?;
+        placed.push(BoardEdit::Special { pos, special });
+    }
+    if let Some(obstacle) = spec.get("obstacle") {
+        let name = obstacle.try_to::<GString>().map_err(|_| "obstacle must be a name")?.to_string();
+        placed.push(BoardEdit::Obstacle {
+            pos,
+            overlay: edits::parse_obstacle(&name, edits::parse_strength(spec)?)?,
+        });
+    }
+    if placed.is_empty() {
+        return Err("spec has no symbol, special or obstacle".to_string());
+    }
+    Ok(placed)
+}
+
+/// Parse a symbol type given by name or index
+fn parse_symbol(symbol: &Variant) -> Result<SymbolType, String> {
+    if let Ok(index) = symbol.try_to::<i32>() {
+        return edits::parse_symbol_type(index);
+    }
+    let name = symbol.try_to::<GString>().map_err(|_| "symbol must be a name or index")?.to_string();
+    SymbolType::parse(&name).ok_or(format!("unknown symbol type: {}", name))
+}
+
+/// Grid snapshots to undo sandbox changes with
+#[derive(Debug, Clone, Default)]
+pub struct SandboxHistory {
+    snapshots: Vec<Grid>,
+}
+
+impl SandboxHistory {
+    /// Remember the grid before a change
+    pub fn record(&mut self, grid: &Grid) {
+        if self.snapshots.len() == MAX_UNDO {
+            self.snapshots.remove(0);
+        }
+        self.snapshots.push(grid.clone());
+    }
+
+    /// Take the grid from before the most recent change
+    pub fn undo(&mut self) -> Option<Grid> {
+        self.snapshots.pop()
+    }
+
+    /// Forget all recorded changes
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
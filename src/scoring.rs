@@ -0,0 +1,32 @@
+//! Score breakdown - where the points came from
+
+use std::collections::BTreeMap;
+
+/// Source of points scored by matches
+pub const SOURCE_MATCH: &str = "match";
+
+/// Source of points scored by the level-end sweep
+pub const SOURCE_SWEEP: &str = "sweep";
+
+/// Points scored per source, for results screens and balancing
+#[derive(Debug, Clone, Default)]
+pub struct ScoreBreakdown {
+    totals: BTreeMap<String, i32>,
+}
+
+impl ScoreBreakdown {
+    /// Add points to a source
+    pub fn add(&mut self, source: &str, amount: i32) {
+        *self.totals.entry(source.to_string()).or_insert(0) += amount;
+    }
+
+    /// Iterate over sources and their totals, sorted by source
+    pub fn totals(&self) -> impl Iterator<Item = (&str, i32)> {
+        self.totals.iter().map(|(source, total)| (source.as_str(), *total))
+    }
+
+    /// Forget all points
+    pub fn clear(&mut self) {
+        self.totals.clear();
+    }
+}
@@ -0,0 +1,54 @@
+//! Seekers - specials that fly to the cell most valuable for the objectives
+//!
+//! An L, T or cross shaped match leaves a seeker behind in place of the swapped
+//! cell, or its first cell in a cascade. When a seeker is cleared it flies to and clears one more cell, chosen
+//! by `pick_target`: blockers first, then symbols of the objective type, with
+//! cell score multipliers adding to a cell's value.
+
+use crate::multipliers::CellMultipliers;
+use crate::symbols::{Grid, SymbolType};
+use godot::prelude::*;
+
+/// Value of clearing a blocker
+const BLOCKER_VALUE: i32 = 3;
+
+/// Value of clearing a symbol of the objective type
+const OBJECTIVE_VALUE: i32 = 2;
+
+/// Get how much clearing a cell helps the objectives
+fn value(grid: &Grid, pos: Vector2i, objective: Option<SymbolType>, multipliers: &CellMultipliers) -> i32 {
+    let Some(symbol) = grid.get(pos.x as usize, pos.y as usize) else {
+        return 0;
+    };
+    let base = if symbol.blocker {
+        BLOCKER_VALUE
+    } else if Some(symbol.current_type()) == objective {
+        OBJECTIVE_VALUE
+    } else {
+        0
+    };
+    base + multipliers.factor(pos) - 1
+}
+
+/// Pick the cell a seeker launched from `from` clears, skipping cells already being cleared
+/// Ties go to the cell nearest the seeker, then the topmost, then the leftmost
+pub fn pick_target(
+    grid: &Grid,
+    from: Vector2i,
+    excluded: &[Vector2i],
+    objective: Option<SymbolType>,
+    multipliers: &CellMultipliers,
+) -> Option<Vector2i> {
+    let distance = |pos: Vector2i| (pos.x - from.x).abs() + (pos.y - from.y).abs();
+    (0..grid.height)
+        .flat_map(|y| (0..grid.width).map(move |x| Vector2i::new(x as i32, y as i32)))
+        .filter(|pos| grid.get(pos.x as usize, pos.y as usize).is_some() && !excluded.contains(pos))
+        .max_by_key(|pos| {
+            (
+                value(grid, *pos, objective, multipliers),
+                -distance(*pos),
+                -pos.y,
+                -pos.x,
+            )
+        })
+}
@@ -0,0 +1,105 @@
+//! Board snapshots - compact text rows of the grid for overlays and dashboards
+//!
+//! Each row is one string with one character per cell: the symbol's letter
+//! code for `type_rows`, its rotation state (`0`-`3`) for `face_rows` and
+//! what it is besides a plain symbol for `special_rows`, with `#` for a
+//! stone, `%` for chocolate, `_` for a hole and `.` for an empty cell in all
+//! three. The layers under the symbols get rows of their own: ice layers and
+//! multipliers as digits, jelly as `j`, and `.` where a cell has none.
+
+use crate::jelly::JellyLayer;
+use crate::multipliers::CellMultipliers;
+use crate::symbols::{CellOverlay, Grid, Symbol, SymbolKind};
+use godot::prelude::*;
+
+/// Character marking an empty cell
+pub const EMPTY_CHAR: char = '.';
+
+/// Character marking a stone
+pub const STONE_CHAR: char = '#';
+
+/// Character marking chocolate
+pub const CHOCOLATE_CHAR: char = '%';
+
+/// Character marking a hole in the board
+pub const HOLE_CHAR: char = '_';
+
+/// Character marking a plain symbol in the special rows
+pub const PLAIN_CHAR: char = 'o';
+
+/// Character marking jelly
+pub const JELLY_CHAR: char = 'j';
+
+/// Current symbol types, one string per row
+pub fn type_rows(grid: &Grid) -> Vec<String> {
+    rows(grid, |symbol| symbol.current_type().letter())
+}
+
+/// Rotation states, one string per row
+pub fn face_rows(grid: &Grid) -> Vec<String> {
+    rows(grid, |symbol| char::from(b'0' + symbol.rotation_state))
+}
+
+/// Specials, one string per row: `S` seeker, `H` row striped, `V` column striped,
+/// `B` color bomb, `W` wild, `I` ingredient, `X` blocker and `o` a plain symbol
+pub fn special_rows(grid: &Grid) -> Vec<String> {
+    rows(grid, |symbol| match symbol.kind {
+        SymbolKind::StripedRow => 'H',
+        SymbolKind::StripedColumn => 'V',
+        SymbolKind::ColorBomb => 'B',
+        SymbolKind::Ingredient => 'I',
+        SymbolKind::Plain if symbol.seeker => 'S',
+        SymbolKind::Plain if symbol.wild => 'W',
+        SymbolKind::Plain if symbol.blocker => 'X',
+        SymbolKind::Plain => PLAIN_CHAR,
+    })
+}
+
+/// Ice layers left on each cell (capped at 9), one string per row
+pub fn ice_rows(grid: &Grid) -> Vec<String> {
+    cell_rows(grid, |x, y| match grid.overlay(x, y) {
+        Some(CellOverlay::Ice(layers)) => digit(layers),
+        _ => EMPTY_CHAR,
+    })
+}
+
+/// Jelly, one string per row
+pub fn jelly_rows(grid: &Grid, jelly: &JellyLayer) -> Vec<String> {
+    cell_rows(grid, |x, y| if jelly.contains(cell_pos(x, y)) { JELLY_CHAR } else { EMPTY_CHAR })
+}
+
+/// Score multipliers above 1 (capped at 9), one string per row
+pub fn multiplier_rows(grid: &Grid, multipliers: &CellMultipliers) -> Vec<String> {
+    cell_rows(grid, |x, y| match multipliers.factor(cell_pos(x, y)) {
+        factor if factor > 1 => digit(factor.unsigned_abs()),
+        _ => EMPTY_CHAR,
+    })
+}
+
+/// Map every cell to a character, one string per row
+fn rows(grid: &Grid, cell: impl Fn(&Symbol) -> char) -> Vec<String> {
+    cell_rows(grid, |x, y| match grid.get(x, y) {
+        Some(symbol) => cell(symbol),
+        None if grid.is_stone(x, y) => STONE_CHAR,
+        None if grid.overlay(x, y) == Some(CellOverlay::Chocolate) => CHOCOLATE_CHAR,
+        None if !grid.is_enabled(x, y) => HOLE_CHAR,
+        None => EMPTY_CHAR,
+    })
+}
+
+/// Map every cell position to a character, one string per row
+fn cell_rows(grid: &Grid, cell: impl Fn(usize, usize) -> char) -> Vec<String> {
+    (0..grid.height)
+        .map(|y| (0..grid.width).map(|x| cell(x, y)).collect())
+        .collect()
+}
+
+/// Grid position of a cell
+fn cell_pos(x: usize, y: usize) -> Vector2i {
+    Vector2i::new(x as i32, y as i32)
+}
+
+/// A count as a single digit, capped at 9
+fn digit(count: u32) -> char {
+    char::from_digit(count.min(9), 10).unwrap_or('9')
+}
@@ -0,0 +1,159 @@
+//! Soak runs - the board plays itself for a long stretch, watching for leaked nodes
+//!
+//! A soak run makes bot moves level after level. Each level ends with a board
+//! reset; on the frame after, once freed nodes are gone, the node and object
+//! counts are sampled. The first sample is the baseline, and any later sample
+//! above it is a leak in the clear/refill cycle.
+
+/// Node and object counts sampled between levels; pooled symbol nodes are not counted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NodeSample {
+    /// Nodes in the scene tree
+    pub engine_nodes: i64,
+    /// Objects alive in the engine
+    pub engine_objects: i64,
+    /// Children of the board's symbol container
+    pub view_nodes: i64,
+}
+
+impl NodeSample {
+    /// Check whether any count grew past a baseline
+    pub fn exceeds(&self, baseline: &NodeSample) -> bool {
+        self.engine_nodes > baseline.engine_nodes
+            || self.engine_objects > baseline.engine_objects
+            || self.view_nodes > baseline.view_nodes
+    }
+}
+
+/// What the board should do for the run this frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoakStep {
+    /// Make a bot move if the board is ready
+    Move,
+    /// End the level by resetting the board
+    EndLevel,
+    /// Sample the node counts for `record_sample`
+    Sample,
+}
+
+/// A level whose sample exceeded the baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Leak {
+    pub level: u32,
+    pub sample: NodeSample,
+}
+
+/// Progress of a soak run
+#[derive(Debug, Clone, Default)]
+pub struct SoakRun {
+    active: bool,
+    headless: bool,
+    remaining: f64,
+    level_seconds: f64,
+    level_elapsed: f64,
+    awaiting_sample: bool,
+    baseline: Option<NodeSample>,
+    levels: u32,
+    moves: u32,
+    leaks: Vec<Leak>,
+}
+
+impl SoakRun {
+    /// Start a run of `duration` game seconds split into levels of `level_seconds`;
+    /// the board must reset before the next frame so the baseline is sampled on a fresh board
+    pub fn start(&mut self, duration: f64, level_seconds: f64, headless: bool) {
+        *self = Self {
+            active: true,
+            headless,
+            remaining: duration,
+            level_seconds,
+            awaiting_sample: true,
+            ..Self::default()
+        };
+    }
+
+    /// Stop the run, returning true if it was running
+    pub fn stop(&mut self) -> bool {
+        let was_active = self.active;
+        self.active = false;
+        was_active
+    }
+
+    /// Check if a run is in progress
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Check if the run (or the last one) plays without drawing or animating
+    pub fn is_headless(&self) -> bool {
+        self.headless
+    }
+
+    /// Advance by `delta`, returning what the board should do this frame
+    pub fn advance(&mut self, delta: f64) -> Option<SoakStep> {
+        if !self.active {
+            return None;
+        }
+        if self.awaiting_sample {
+            return Some(SoakStep::Sample);
+        }
+
+        self.remaining -= delta;
+        self.level_elapsed += delta;
+        if self.remaining <= 0.0 || self.level_elapsed >= self.level_seconds {
+            self.level_elapsed = 0.0;
+            self.awaiting_sample = true;
+            return Some(SoakStep::EndLevel);
+        }
+        Some(SoakStep::Move)
+    }
+
+    /// Count a bot move
+    pub fn record_move(&mut self) {
+        self.moves += 1;
+    }
+
+    /// Take the counts sampled after a level ended, returning the leak if they exceed the baseline;
+    /// the run finishes once its time is up
+    pub fn record_sample(&mut self, sample: NodeSample) -> Option<Leak> {
+        self.awaiting_sample = false;
+        if self.remaining <= 0.0 {
+            self.active = false;
+        }
+
+        let Some(baseline) = self.baseline else {
+            self.baseline = Some(sample);
+            return None;
+        };
+        self.levels += 1;
+        if !sample.exceeds(&baseline) {
+            return None;
+        }
+        let leak = Leak {
+            level: self.levels,
+            sample,
+        };
+        self.leaks.push(leak);
+        Some(leak)
+    }
+
+    /// Get the counts every level is compared with
+    pub fn baseline(&self) -> Option<NodeSample> {
+        self.baseline
+    }
+
+    /// Get the number of levels played to the end
+    pub fn levels(&self) -> u32 {
+        self.levels
+    }
+
+    /// Get the number of bot moves made
+    pub fn moves(&self) -> u32 {
+        self.moves
+    }
+
+    /// Get the levels that ended above the baseline
+    pub fn leaks(&self) -> &[Leak] {
+        &self.leaks
+    }
+}
@@ -0,0 +1,116 @@
+//! Move evaluation - plays swaps out on a copy of the model to score and predict them
+//!
+//! Cascades are simulated without refills, so evaluation never draws from
+//! the random source and is the same for every seed.
+
+use crate::chain::{Chain, StepSpecials};
+use crate::error::SwapRejection;
+use crate::model::{self, BoardModel, ModelEvent};
+use godot::prelude::*;
+
+/// Most resolutions simulated per move
+const MAX_SIMULATED_DEPTH: i32 = 20;
+
+/// A legal swap and the points it scores before any refill
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveScore {
+    pub from: Vector2i,
+    pub to: Vector2i,
+    pub score: i32,
+}
+
+/// The predicted result of a legal swap, before any refill
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// Points scored by the whole cascade
+    pub score: i32,
+    /// What each resolution matched and cleared
+    pub chain: Option<Chain>,
+    /// Cells left holding a new seeker
+    pub seekers: Vec<Vector2i>,
+    /// Every special the cascade created and set off: seekers, striped symbols, color bombs and combos
+    pub specials: StepSpecials,
+}
+
+/// Play a swap out on a copy of the model and return the points it scores
+pub fn evaluate_move(model: &BoardModel, from: Vector2i, to: Vector2i) -> i32 {
+    let mut sim = scoring_copy(model);
+    if sim.swap(from, to).is_err() {
+        return 0;
+    }
+    cascade(&mut sim);
+    sim.score()
+}
+
+/// Play a swap out on a copy of the model, or say why it isn't a legal move
+pub fn simulate_move(model: &BoardModel, from: Vector2i, to: Vector2i) -> Result<Outcome, SwapRejection> {
+    model.check_swap(from, to)?;
+
+    let mut sim = scoring_copy(model);
+    if sim.swap(from, to).is_err() {
+        return Err(SwapRejection::NoMatch);
+    }
+    cascade(&mut sim);
+    sim.end_cascade();
+
+    let events = sim.drain_events();
+    let mut outcome = Outcome {
+        score: sim.score(),
+        chain: None,
+        seekers: Vec::new(),
+        specials: model::special_events(&events),
+    };
+    for event in events {
+        match event {
+            ModelEvent::ChainResolved(chain) => outcome.chain = Some(chain),
+            ModelEvent::SeekerCreated(pos) => outcome.seekers.push(pos),
+            _ => {}
+        }
+    }
+    Ok(outcome)
+}
+
+/// Copy a model to simulate on, scoring from zero with no pending events
+fn scoring_copy(model: &BoardModel) -> BoardModel {
+    let mut sim = model.clone();
+    sim.set_scoring(true);
+    sim.reset_scoring();
+    sim.drain_events();
+    sim
+}
+
+/// Resolve matches and let symbols fall until the board settles, without refilling
+fn cascade(sim: &mut BoardModel) {
+    while sim.cascade_depth() < MAX_SIMULATED_DEPTH && !sim.resolve_matches().is_empty() {
+        sim.apply_gravity();
+    }
+}
+
+/// Evaluate up to `budget` legal swaps, best first
+pub fn evaluate_moves(model: &BoardModel, budget: usize) -> Vec<MoveScore> {
+    let grid = model.grid();
+    let mut moves = Vec::new();
+
+    'scan: for y in 0..grid.height as i32 {
+        for x in 0..grid.width as i32 {
+            let from = Vector2i::new(x, y);
+            for to in [Vector2i::new(x + 1, y), Vector2i::new(x, y + 1)] {
+                if moves.len() >= budget {
+                    break 'scan;
+                }
+                if grid.is_valid(to.x, to.y) && model.can_swap(from, to) {
+                    let score = evaluate_move(model, from, to);
+                    moves.push(MoveScore { from, to, score });
+                }
+            }
+        }
+    }
+
+    moves.sort_by(|a, b| b.score.cmp(&a.score));
+    moves
+}
+
+/// Rank a score among evaluated moves, 1 being the best; ties share a rank
+pub fn rank(moves: &[MoveScore], score: i32) -> usize {
+    1 + moves.iter().filter(|m| m.score > score).count()
+}
@@ -0,0 +1,102 @@
+//! Game state machine with validated transitions
+
+use std::fmt;
+
+/// Game states
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameState {
+    #[default]
+    Ready,
+    Selected,
+    Swapping,
+    Matching,
+    Falling,
+    Rotating,
+    Sweeping,
+    Results,
+    Finished,
+    Transitioning,
+}
+
+impl GameState {
+    /// Get the lowercase name of this state
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameState::Ready => "ready",
+            GameState::Selected => "selected",
+            GameState::Swapping => "swapping",
+            GameState::Matching => "matching",
+            GameState::Falling => "falling",
+            GameState::Rotating => "rotating",
+            GameState::Sweeping => "sweeping",
+            GameState::Results => "results",
+            GameState::Finished => "finished",
+            GameState::Transitioning => "transitioning",
+        }
+    }
+
+    /// Check if the player can interact with the board in this state
+    pub fn accepts_input(&self) -> bool {
+        matches!(self, GameState::Ready | GameState::Selected)
+    }
+
+    /// Check if the transition table allows moving from this state to `next`
+    pub fn can_transition_to(&self, next: GameState) -> bool {
+        use GameState::*;
+
+        matches!(
+            (self, next),
+            (Ready, Selected | Rotating | Matching | Sweeping | Results | Transitioning)
+                | (Selected, Ready | Swapping | Matching)
+                | (Swapping, Matching | Ready)
+                | (Matching, Falling)
+                | (Falling, Matching | Ready)
+                | (Rotating, Ready)
+                | (Sweeping, Results)
+                | (Results, Finished)
+                | (Transitioning, Ready)
+        )
+    }
+}
+
+/// A transition rejected by the state machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IllegalTransition {
+    pub from: GameState,
+    pub to: GameState,
+}
+
+impl fmt::Display for IllegalTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "illegal state transition {} -> {}", self.from.name(), self.to.name())
+    }
+}
+
+/// Holds the current state and only changes it along the transition table
+#[derive(Debug, Clone, Default)]
+pub struct StateMachine {
+    state: GameState,
+}
+
+impl StateMachine {
+    /// Get the current state
+    pub fn current(&self) -> GameState {
+        self.state
+    }
+
+    /// Move to `next`, returning the previous state
+    /// Staying in the same state is always allowed
+    pub fn transition(&mut self, next: GameState) -> Result<GameState, IllegalTransition> {
+        let previous = self.state;
+        if previous != next && !previous.can_transition_to(next) {
+            return Err(IllegalTransition { from: previous, to: next });
+        }
+        self.state = next;
+        Ok(previous)
+    }
+
+    /// Unconditionally return to `Ready`, returning the previous state
+    pub fn reset(&mut self) -> GameState {
+        std::mem::replace(&mut self.state, GameState::Ready)
+    }
+}
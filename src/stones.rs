@@ -0,0 +1,31 @@
+//! Stones - obstacles filling cells in place of symbols
+//!
+//! A stone sits in the grid's overlay layer as `CellOverlay::Stone` with its
+//! hit points. It never matches, moves or falls: symbols above it come to rest
+//! on it, and refills skip its cell. Each resolution that clears cells next to
+//! a stone takes one hit point off it; at none the stone breaks, leaving an
+//! empty cell for symbols to fall into.
+
+use crate::symbols::{CellOverlay, Grid};
+use godot::prelude::*;
+
+/// Take one hit point off every stone next to the cleared cells, breaking those left with none
+/// Returns each stone hit with the hit points it has left
+pub fn hit_adjacent(grid: &mut Grid, cleared: &[Vector2i]) -> Vec<(Vector2i, u32)> {
+    let mut hit = Vec::new();
+    for pos in cleared {
+        for offset in [Vector2i::new(1, 0), Vector2i::new(-1, 0), Vector2i::new(0, 1), Vector2i::new(0, -1)] {
+            let neighbor = *pos + offset;
+            if !grid.is_valid(neighbor.x, neighbor.y) || hit.iter().any(|(stone, _)| *stone == neighbor) {
+                continue;
+            }
+            let Some(CellOverlay::Stone(hp)) = grid.overlay(neighbor.x as usize, neighbor.y as usize) else {
+                continue;
+            };
+            let left = hp.saturating_sub(1);
+            let _ = grid.set_overlay(neighbor, (left > 0).then_some(CellOverlay::Stone(left)));
+            hit.push((neighbor, left));
+        }
+    }
+    hit
+}
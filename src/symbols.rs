@@ -1,7 +1,33 @@
 //! Symbol types and management for the match-3 game
 
+use crate::error::BoardError;
 use godot::prelude::*;
 use rand::Rng;
+use std::collections::BTreeMap;
+
+/// Color of a symbol that expired into a blocker
+pub const BLOCKER_COLOR: Color = Color::from_rgba(0.3, 0.3, 0.3, 1.0);
+
+/// Color of a color bomb, which has no type of its own
+pub const COLOR_BOMB_COLOR: Color = Color::from_rgba(0.95, 0.95, 0.95, 1.0);
+
+/// Color of a wild symbol, which matches any type
+pub const WILD_COLOR: Color = Color::from_rgba(0.85, 0.6, 0.95, 1.0);
+
+/// Color of an ingredient, which never matches
+pub const INGREDIENT_COLOR: Color = Color::from_rgba(0.75, 0.55, 0.3, 1.0);
+
+/// Color of a stone with one hit point left; sturdier stones are drawn darker
+pub const STONE_COLOR: Color = Color::from_rgba(0.6, 0.55, 0.5, 1.0);
+
+/// Color of chocolate
+pub const CHOCOLATE_COLOR: Color = Color::from_rgba(0.4, 0.25, 0.15, 1.0);
+
+/// Color of ice over a symbol; the symbol shows through
+pub const ICE_COLOR: Color = Color::from_rgba(0.75, 0.9, 1.0, 0.55);
+
+/// Color of ice down to its last layer
+pub const CRACKED_ICE_COLOR: Color = Color::from_rgba(0.85, 0.95, 1.0, 0.3);
 
 /// The different symbol types available in the game
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -27,8 +53,7 @@ impl SymbolType {
     ];
 
     /// Get a random symbol type
-    pub fn random() -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
         Self::ALL[rng.gen_range(0..Self::ALL.len())]
     }
 
@@ -44,6 +69,18 @@ impl SymbolType {
         }
     }
 
+    /// Get the symbol type whose color is closest to a color
+    pub fn nearest(color: Color) -> Self {
+        let distance = |symbol_type: &SymbolType| {
+            let c = symbol_type.color();
+            (c.r - color.r).powi(2) + (c.g - color.g).powi(2) + (c.b - color.b).powi(2)
+        };
+        Self::ALL
+            .into_iter()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .unwrap_or_default()
+    }
+
     /// Get the index of this symbol type
     pub fn index(&self) -> usize {
         match self {
@@ -60,8 +97,117 @@ impl SymbolType {
     pub fn from_index(index: usize) -> Self {
         Self::ALL[index % Self::ALL.len()]
     }
+
+    /// Get the single-letter code of this symbol type
+    pub fn letter(&self) -> char {
+        match self {
+            SymbolType::Red => 'R',
+            SymbolType::Blue => 'B',
+            SymbolType::Green => 'G',
+            SymbolType::Yellow => 'Y',
+            SymbolType::Purple => 'P',
+            SymbolType::Orange => 'O',
+        }
+    }
+
+    /// Get the symbol type for a single-letter code (case-insensitive)
+    pub fn from_letter(letter: char) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.letter() == letter.to_ascii_uppercase())
+    }
+
+    /// Parse a symbol type from its name or letter code (case-insensitive)
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().to_ascii_lowercase();
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(letter), None) => Self::from_letter(letter),
+            _ => Self::ALL.into_iter().find(|t| t.name() == text),
+        }
+    }
+
+    /// Get the lowercase name of this symbol type
+    pub fn name(&self) -> &'static str {
+        match self {
+            SymbolType::Red => "red",
+            SymbolType::Blue => "blue",
+            SymbolType::Green => "green",
+            SymbolType::Yellow => "yellow",
+            SymbolType::Purple => "purple",
+            SymbolType::Orange => "orange",
+        }
+    }
 }
 
+/// Length of a straight match that leaves a striped symbol behind
+pub const STRIPE_MATCH_LEN: usize = 4;
+
+/// Shortest straight match that leaves a color bomb behind
+pub const COLOR_BOMB_MATCH_LEN: usize = 5;
+
+/// What a symbol clears besides itself
+/// A straight match of `STRIPE_MATCH_LEN` leaves a striped symbol in the cell the player swapped
+/// (or its first cell in a cascade), striped across the match: a row match clears a column later.
+/// One of `COLOR_BOMB_MATCH_LEN` or more leaves a color bomb the same way
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SymbolKind {
+    #[default]
+    Plain,
+    /// Clears its whole row when cleared
+    StripedRow,
+    /// Clears its whole column when cleared
+    StripedColumn,
+    /// Never matches; swapped with a symbol, it clears every symbol of that symbol's type
+    ColorBomb,
+    /// Never matches and can't be cleared; it falls with the board and leaves it through the bottom row
+    Ingredient,
+}
+
+impl SymbolKind {
+    /// Get the striped kind a straight match leaves behind
+    pub fn striped_for(horizontal_match: bool) -> Self {
+        if horizontal_match {
+            SymbolKind::StripedColumn
+        } else {
+            SymbolKind::StripedRow
+        }
+    }
+
+    /// Check if this kind is striped
+    pub fn is_striped(&self) -> bool {
+        matches!(self, SymbolKind::StripedRow | SymbolKind::StripedColumn)
+    }
+
+    /// Get the lowercase name of this kind
+    pub fn name(&self) -> &'static str {
+        match self {
+            SymbolKind::Plain => "plain",
+            SymbolKind::StripedRow => "striped_row",
+            SymbolKind::StripedColumn => "striped_column",
+            SymbolKind::ColorBomb => "color_bomb",
+            SymbolKind::Ingredient => "ingredient",
+        }
+    }
+
+    /// Get the occupied cells a symbol of this kind clears from `at` when cleared, itself included
+    /// A color bomb only goes off when swapped (see `Grid::cells_of_type`)
+    pub fn cleared_cells(&self, grid: &Grid, at: Vector2i) -> Vec<Vector2i> {
+        let hit = |pos: Vector2i| match self {
+            SymbolKind::Plain | SymbolKind::ColorBomb | SymbolKind::Ingredient => pos == at,
+            SymbolKind::StripedRow => pos.y == at.y,
+            SymbolKind::StripedColumn => pos.x == at.x,
+        };
+        (0..grid.height)
+            .flat_map(|y| (0..grid.width).map(move |x| Vector2i::new(x as i32, y as i32)))
+            .filter(|pos| hit(*pos) && grid.get(pos.x as usize, pos.y as usize).is_some())
+            .collect()
+    }
+}
+
+/// Key/value tags host games attach to a symbol for their own mechanics (quest items, character pieces)
+pub type SymbolTags = BTreeMap<String, String>;
+
 /// A symbol on the game board with rotation capability
 #[derive(Debug, Clone)]
 pub struct Symbol {
@@ -75,12 +221,23 @@ pub struct Symbol {
     pub selected: bool,
     /// Whether this symbol is marked for clearing
     pub marked_for_clear: bool,
+    /// Rotations this symbol has sat through without being matched
+    pub age: u32,
+    /// Whether this symbol expired into a blocker: it can't move, turn or match
+    pub blocker: bool,
+    /// Whether this symbol is a seeker special (see the `seeker` module)
+    pub seeker: bool,
+    /// Whether this symbol is wild: it matches symbols of any type
+    pub wild: bool,
+    /// What this symbol clears besides itself
+    pub kind: SymbolKind,
+    /// Tags set by level data or scripts; they move with the symbol until it is cleared
+    pub tags: SymbolTags,
 }
 
 impl Symbol {
     /// Create a new symbol with random faces
-    pub fn new(grid_pos: Vector2i) -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn new<R: Rng + ?Sized>(grid_pos: Vector2i, rng: &mut R) -> Self {
         let faces = [
             SymbolType::from_index(rng.gen_range(0..6)),
             SymbolType::from_index(rng.gen_range(0..6)),
@@ -94,6 +251,12 @@ impl Symbol {
             grid_pos,
             selected: false,
             marked_for_clear: false,
+            age: 0,
+            blocker: false,
+            seeker: false,
+            wild: false,
+            kind: SymbolKind::Plain,
+            tags: SymbolTags::new(),
         }
     }
 
@@ -117,6 +280,12 @@ impl Symbol {
             grid_pos,
             selected: false,
             marked_for_clear: false,
+            age: 0,
+            blocker: false,
+            seeker: false,
+            wild: false,
+            kind: SymbolKind::Plain,
+            tags: SymbolTags::new(),
         }
     }
 
@@ -125,19 +294,73 @@ impl Symbol {
         self.faces[self.rotation_state as usize]
     }
 
-    /// Get the current color
+    /// Get the current color; blockers are gray, color bombs white, ingredients brown, wilds violet, seekers
+    /// lighter than their type and striped symbols darker
     pub fn current_color(&self) -> Color {
-        self.current_type().color()
+        if self.blocker {
+            BLOCKER_COLOR
+        } else if self.kind == SymbolKind::ColorBomb {
+            COLOR_BOMB_COLOR
+        } else if self.kind == SymbolKind::Ingredient {
+            INGREDIENT_COLOR
+        } else if self.wild {
+            WILD_COLOR
+        } else if self.seeker {
+            self.current_type().color().lightened(0.45)
+        } else if self.kind.is_striped() {
+            self.current_type().color().darkened(0.35)
+        } else {
+            self.current_type().color()
+        }
     }
 
-    /// Rotate the symbol clockwise
+    /// Rotate the symbol clockwise; blockers and ingredients don't turn
     pub fn rotate(&mut self) {
+        if self.blocker || self.kind == SymbolKind::Ingredient {
+            return;
+        }
         self.rotation_state = (self.rotation_state + 1) % 4;
     }
 
-    /// Check if this symbol matches another (same current type)
+    /// Rotate the symbol counterclockwise, back to its previous face
+    pub fn rotate_back(&mut self) {
+        if self.blocker || self.kind == SymbolKind::Ingredient {
+            return;
+        }
+        self.rotation_state = (self.rotation_state + 3) % 4;
+    }
+
+    /// Check if this symbol matches another (same current type or either one wild, neither a blocker)
     pub fn matches(&self, other: &Symbol) -> bool {
-        self.current_type() == other.current_type()
+        !self.blocker && !other.blocker && (self.wild || other.wild || self.current_type() == other.current_type())
+    }
+}
+
+/// Something on a cell apart from its symbol, kept in the grid's overlay layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellOverlay {
+    /// A stone with hit points filling the cell in place of a symbol (see the `stones` module)
+    Stone(u32),
+    /// Ice with layers holding the cell's symbol in place (see the `ice` module)
+    Ice(u32),
+    /// Chocolate filling the cell in place of a symbol and spreading after moves (see the `chocolate` module)
+    Chocolate,
+}
+
+impl CellOverlay {
+    /// Get the color the overlay is drawn in
+    pub fn color(&self) -> Color {
+        match self {
+            CellOverlay::Stone(hp) => STONE_COLOR.darkened((f64::from(hp.saturating_sub(1)) * 0.2).min(0.6)),
+            CellOverlay::Ice(1) => CRACKED_ICE_COLOR,
+            CellOverlay::Ice(_) => ICE_COLOR,
+            CellOverlay::Chocolate => CHOCOLATE_COLOR,
+        }
+    }
+
+    /// Check if the overlay fills its cell in place of a symbol
+    pub fn fills_cell(&self) -> bool {
+        matches!(self, CellOverlay::Stone(_) | CellOverlay::Chocolate)
     }
 }
 
@@ -147,6 +370,10 @@ pub struct Grid {
     pub width: usize,
     pub height: usize,
     pub cells: Vec<Option<Symbol>>,
+    /// Overlays indexed like the cells
+    pub overlays: Vec<Option<CellOverlay>>,
+    /// Whether each cell is playable, indexed like the cells; a disabled cell is a hole that never holds a symbol
+    pub enabled: Vec<bool>,
 }
 
 impl Grid {
@@ -156,6 +383,8 @@ impl Grid {
             width,
             height,
             cells: vec![None; width * height],
+            overlays: vec![None; width * height],
+            enabled: vec![true; width * height],
         }
     }
 
@@ -169,6 +398,15 @@ impl Grid {
         x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
     }
 
+    /// Get the index for a position, or an error if it is outside the grid
+    pub fn checked_index(&self, pos: Vector2i) -> Result<usize, BoardError> {
+        if self.is_valid(pos.x, pos.y) {
+            Ok(self.index(pos.x as usize, pos.y as usize))
+        } else {
+            Err(BoardError::OutOfBounds(pos))
+        }
+    }
+
     /// Get a cell
     pub fn get(&self, x: usize, y: usize) -> Option<&Symbol> {
         if x < self.width && y < self.height {
@@ -178,6 +416,75 @@ impl Grid {
         }
     }
 
+    /// Get a cell if its symbol can take part in a match; blockers, color bombs and ingredients can't
+    pub fn matchable(&self, x: usize, y: usize) -> Option<&Symbol> {
+        self.get(x, y)
+            .filter(|symbol| !symbol.blocker && !matches!(symbol.kind, SymbolKind::ColorBomb | SymbolKind::Ingredient))
+    }
+
+    /// Get the cells holding a matchable symbol of a type, row by row
+    pub fn cells_of_type(&self, symbol_type: SymbolType) -> Vec<Vector2i> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.matchable(x, y).is_some_and(|symbol| symbol.current_type() == symbol_type))
+            .map(|(x, y)| Vector2i::new(x as i32, y as i32))
+            .collect()
+    }
+
+    /// Get the overlay on a cell
+    pub fn overlay(&self, x: usize, y: usize) -> Option<CellOverlay> {
+        if x < self.width && y < self.height {
+            self.overlays[self.index(x, y)]
+        } else {
+            None
+        }
+    }
+
+    /// Check if a stone fills a cell
+    pub fn is_stone(&self, x: usize, y: usize) -> bool {
+        matches!(self.overlay(x, y), Some(CellOverlay::Stone(_)))
+    }
+
+    /// Check if a stone or chocolate fills a cell, leaving no room for a symbol
+    pub fn is_filled(&self, x: usize, y: usize) -> bool {
+        self.overlay(x, y).is_some_and(|overlay| overlay.fills_cell())
+    }
+
+    /// Check if ice holds a cell's symbol in place
+    pub fn is_frozen(&self, x: usize, y: usize) -> bool {
+        matches!(self.overlay(x, y), Some(CellOverlay::Ice(_)))
+    }
+
+    /// Check if a cell is playable rather than a hole (false if out of bounds)
+    pub fn is_enabled(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.enabled[self.index(x, y)]
+    }
+
+    /// Make a cell playable, or a hole; a hole loses its symbol and overlay
+    pub fn set_enabled(&mut self, pos: Vector2i, enabled: bool) -> Result<(), BoardError> {
+        let idx = self.checked_index(pos)?;
+        if !enabled {
+            self.cells[idx] = None;
+            self.overlays[idx] = None;
+        }
+        self.enabled[idx] = enabled;
+        Ok(())
+    }
+
+    /// Put an overlay on a cell, or remove it with None; a stone or chocolate takes the cell's symbol
+    /// off the board
+    pub fn set_overlay(&mut self, pos: Vector2i, overlay: Option<CellOverlay>) -> Result<(), BoardError> {
+        let idx = self.checked_index(pos)?;
+        if !self.enabled[idx] {
+            return Err(BoardError::CellDisabled(pos));
+        }
+        if overlay.is_some_and(|overlay| overlay.fills_cell()) {
+            self.cells[idx] = None;
+        }
+        self.overlays[idx] = overlay;
+        Ok(())
+    }
+
     /// Get a mutable cell
     pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut Symbol> {
         if x < self.width && y < self.height {
@@ -188,15 +495,25 @@ impl Grid {
         }
     }
 
-    /// Set a cell
+    /// Set a cell, reporting out-of-bounds positions and holes
+    pub fn try_set(&mut self, pos: Vector2i, symbol: Option<Symbol>) -> Result<(), BoardError> {
+        let idx = self.checked_index(pos)?;
+        if !self.enabled[idx] {
+            return Err(BoardError::CellDisabled(pos));
+        }
+        self.cells[idx] = symbol;
+        Ok(())
+    }
+
+    /// Set a cell (ignored if out of bounds or a hole)
     pub fn set(&mut self, x: usize, y: usize, symbol: Option<Symbol>) {
-        if x < self.width && y < self.height {
+        if self.is_enabled(x, y) {
             let idx = self.index(x, y);
             self.cells[idx] = symbol;
         }
     }
 
-    /// Take a symbol from a cell (removes it)
+    /// Take a symbol from a cell (removes it; None if out of bounds)
     pub fn take(&mut self, x: usize, y: usize) -> Option<Symbol> {
         if x < self.width && y < self.height {
             let idx = self.index(x, y);
@@ -206,11 +523,37 @@ impl Grid {
         }
     }
 
-    /// Fill the grid with random symbols (avoiding initial matches)
-    pub fn fill_random(&mut self) {
+    /// Swap the symbols at two positions, leaving the grid untouched on error; frozen symbols can't move
+    pub fn swap(&mut self, pos1: Vector2i, pos2: Vector2i) -> Result<(), BoardError> {
+        let idx1 = self.checked_index(pos1)?;
+        let idx2 = self.checked_index(pos2)?;
+
+        for (idx, pos) in [(idx1, pos1), (idx2, pos2)] {
+            if self.cells[idx].is_none() {
+                return Err(BoardError::EmptyCell(pos));
+            }
+            if matches!(self.overlays[idx], Some(CellOverlay::Ice(_))) {
+                return Err(BoardError::CellFrozen(pos));
+            }
+        }
+
+        self.cells.swap(idx1, idx2);
+        for (idx, pos) in [(idx1, pos1), (idx2, pos2)] {
+            if let Some(symbol) = self.cells[idx].as_mut() {
+                symbol.grid_pos = pos;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fill the playable cells with random symbols (avoiding initial matches)
+    pub fn fill_random<R: Rng + ?Sized>(&mut self, rng: &mut R) {
         for y in 0..self.height {
             for x in 0..self.width {
-                let mut symbol_type = SymbolType::random();
+                if !self.is_enabled(x, y) {
+                    continue;
+                }
+                let mut symbol_type = SymbolType::random(rng);
 
                 // Avoid creating matches on fill
                 let mut attempts = 0;
@@ -226,7 +569,7 @@ impl Grid {
                     if !would_match_h && !would_match_v {
                         break;
                     }
-                    symbol_type = SymbolType::random();
+                    symbol_type = SymbolType::random(rng);
                     attempts += 1;
                 }
 
@@ -244,4 +587,62 @@ impl Grid {
             }
         }
     }
+
+    /// Get a copy with rows and columns exchanged: the symbol at (x, y) moves to (y, x)
+    pub fn transposed(&self) -> Grid {
+        self.remapped(self.height, self.width, |pos| Vector2i::new(pos.y, pos.x))
+    }
+
+    /// Get a copy mirrored left to right
+    pub fn flipped_horizontally(&self) -> Grid {
+        let last = self.width as i32 - 1;
+        self.remapped(self.width, self.height, |pos| Vector2i::new(last - pos.x, pos.y))
+    }
+
+    /// Get a copy mirrored top to bottom
+    pub fn flipped_vertically(&self) -> Grid {
+        let last = self.height as i32 - 1;
+        self.remapped(self.width, self.height, |pos| Vector2i::new(pos.x, last - pos.y))
+    }
+
+    /// Get a copy turned a quarter turn; a non-square grid comes back with width and height exchanged
+    pub fn rotated(&self, clockwise: bool) -> Grid {
+        if clockwise {
+            self.transposed().flipped_horizontally()
+        } else {
+            self.transposed().flipped_vertically()
+        }
+    }
+
+    /// Get where a cell ends up when the grid is turned a quarter turn
+    pub fn rotated_position(&self, pos: Vector2i, clockwise: bool) -> Vector2i {
+        if clockwise {
+            Vector2i::new(self.height as i32 - 1 - pos.y, pos.x)
+        } else {
+            Vector2i::new(pos.y, self.width as i32 - 1 - pos.x)
+        }
+    }
+
+    /// Build a `width` x `height` grid holding each symbol, overlay and hole at the cell `map` sends it to
+    fn remapped(&self, width: usize, height: usize, map: impl Fn(Vector2i) -> Vector2i) -> Grid {
+        let mut grid = Grid::new(width, height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let pos = map(Vector2i::new(x as i32, y as i32));
+                if !self.is_enabled(x, y) {
+                    let _ = grid.set_enabled(pos, false);
+                    continue;
+                }
+                if let Some(overlay) = self.overlay(x, y) {
+                    let _ = grid.set_overlay(pos, Some(overlay));
+                }
+                let Some(mut symbol) = self.get(x, y).cloned() else {
+                    continue;
+                };
+                symbol.grid_pos = pos;
+                grid.set(pos.x as usize, pos.y as usize, Some(symbol));
+            }
+        }
+        grid
+    }
 }
@@ -0,0 +1,45 @@
+//! Contextual teaching - which mechanics the player has already been taught
+
+use std::collections::BTreeSet;
+
+/// The board is about to rotate for the first time
+pub const TOPIC_ROTATION: &str = "rotation";
+
+/// A level with blockers was loaded
+pub const TOPIC_BLOCKERS: &str = "blockers";
+
+/// A match left a special symbol (seeker, striped symbol or color bomb) behind for the first time
+pub const TOPIC_SPECIALS: &str = "specials";
+
+/// Seconds before a rotation at which the rotation topic fires
+pub const ROTATION_LEAD: f64 = 2.0;
+
+/// Topics already shown to the current profile
+#[derive(Debug, Clone, Default)]
+pub struct TeachingLog {
+    seen: BTreeSet<String>,
+}
+
+impl TeachingLog {
+    /// Create a log from previously seen topics
+    pub fn from_topics(topics: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            seen: topics.into_iter().collect(),
+        }
+    }
+
+    /// Mark a topic as seen, returning true if this is the first time
+    pub fn first_time(&mut self, topic: &str) -> bool {
+        self.seen.insert(topic.to_string())
+    }
+
+    /// Check if a topic was already seen
+    pub fn has_seen(&self, topic: &str) -> bool {
+        self.seen.contains(topic)
+    }
+
+    /// Iterate over seen topics in sorted order
+    pub fn topics(&self) -> impl Iterator<Item = &str> {
+        self.seen.iter().map(String::as_str)
+    }
+}
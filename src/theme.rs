@@ -0,0 +1,50 @@
+//! Per-symbol-type presentation cues (sounds, particle colors)
+
+use crate::symbols::SymbolType;
+use godot::prelude::*;
+
+/// Presentation cues for a single symbol type
+#[derive(Debug, Clone)]
+pub struct SymbolCue {
+    /// Name of the sound cue played when a symbol of this type clears
+    pub clear_sound: GString,
+    /// Color of the particles spawned when a symbol of this type clears
+    pub particle_color: Color,
+}
+
+/// Registry mapping every symbol type to its cues
+#[derive(Debug, Clone)]
+pub struct SymbolTheme {
+    cues: Vec<SymbolCue>,
+}
+
+impl Default for SymbolTheme {
+    fn default() -> Self {
+        let cues = SymbolType::ALL
+            .iter()
+            .map(|symbol_type| SymbolCue {
+                clear_sound: format!("clear_{}", symbol_type.name()).into(),
+                particle_color: symbol_type.color(),
+            })
+            .collect();
+
+        Self { cues }
+    }
+}
+
+impl SymbolTheme {
+    /// Get the cues for a symbol type
+    pub fn cue(&self, symbol_type: SymbolType) -> &SymbolCue {
+        &self.cues[symbol_type.index()]
+    }
+
+    /// Set the clear sound for a symbol type
+    pub fn set_clear_sound(&mut self, symbol_type: SymbolType, sound: GString) {
+        self.cues[symbol_type.index()].clear_sound = sound;
+    }
+
+    /// Set the particle color for a symbol type
+    pub fn set_particle_color(&mut self, symbol_type: SymbolType, color: Color) {
+        self.cues[symbol_type.index()].particle_color = color;
+    }
+}
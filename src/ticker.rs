@@ -0,0 +1,54 @@
+//! Score ticker - a display score that counts up toward the real one
+//!
+//! HUDs show the ticker's value instead of the score so points roll up
+//! smoothly. The shown value closes a fixed fraction of the gap every second,
+//! so big gains count quickly at first and settle gently. Drops in the score,
+//! such as a reset, are shown at once.
+
+/// Gap below which the ticker lands on the score
+const SNAP_GAP: f64 = 0.5;
+
+/// A score shown as counting toward its real value
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScoreTicker {
+    shown: f64,
+    target: i32,
+}
+
+impl ScoreTicker {
+    /// Get the value to display
+    pub fn value(&self) -> i32 {
+        self.shown.round() as i32
+    }
+
+    /// Set the score to count toward; a lower score is shown at once
+    /// Returns the new display value if it jumped
+    pub fn set_target(&mut self, score: i32) -> Option<i32> {
+        self.target = score;
+        if f64::from(score) >= self.shown {
+            return None;
+        }
+        self.shown = f64::from(score);
+        Some(score)
+    }
+
+    /// Ease toward the score by `delta` seconds at `rate` (0 to land at once)
+    /// Returns the new display value if it changed
+    pub fn advance(&mut self, delta: f64, rate: f64) -> Option<i32> {
+        let target = f64::from(self.target);
+        if self.shown == target {
+            return None;
+        }
+        let before = self.value();
+        if rate <= 0.0 || target - self.shown < SNAP_GAP {
+            self.shown = target;
+        } else {
+            self.shown += (target - self.shown) * (1.0 - (-rate * delta).exp());
+            if target - self.shown < SNAP_GAP {
+                self.shown = target;
+            }
+        }
+        let after = self.value();
+        (after != before).then_some(after)
+    }
+}
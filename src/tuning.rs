@@ -0,0 +1,35 @@
+//! Metadata for parameters that can be tuned live from a generic panel
+
+/// A tunable parameter and its allowed range
+#[derive(Debug, Clone, Copy)]
+pub struct Tunable {
+    pub name: &'static str,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+impl Tunable {
+    /// Clamp a value into this tunable's range
+    pub fn clamp(&self, value: f64) -> f64 {
+        value.clamp(self.min, self.max)
+    }
+}
+
+/// All live-tunable board parameters
+pub const TUNABLES: &[Tunable] = &[
+    Tunable { name: "rotation_interval", min: 0.5, max: 60.0, step: 0.1 },
+    Tunable { name: "clear_duration", min: 0.0, max: 2.0, step: 0.01 },
+    Tunable { name: "fall_duration_per_cell", min: 0.0, max: 1.0, step: 0.01 },
+    Tunable { name: "spawn_duration", min: 0.0, max: 2.0, step: 0.01 },
+    Tunable { name: "rotation_duration", min: 0.0, max: 2.0, step: 0.01 },
+    Tunable { name: "results_event_spacing", min: 0.0, max: 5.0, step: 0.05 },
+    Tunable { name: "idle_threshold", min: 1.0, max: 120.0, step: 1.0 },
+    Tunable { name: "idle_tick_interval", min: 0.5, max: 60.0, step: 0.5 },
+    Tunable { name: "idle_shuffle_seconds", min: 1.0, max: 120.0, step: 1.0 },
+];
+
+/// Find a tunable by name
+pub fn find(name: &str) -> Option<&'static Tunable> {
+    TUNABLES.iter().find(|t| t.name == name)
+}
@@ -0,0 +1,84 @@
+//! Turn summary - what one player move did, from the input until the board settles
+//!
+//! The board opens a turn when the player swaps, taps, links or holds, and
+//! tallies the model's events into it while the cascade plays out. Once the
+//! cascade ends it sends the tally as one `turn_resolved` signal, so HUDs and
+//! analytics don't have to piece a turn together from the match, special and
+//! score signals.
+
+use crate::model::ModelEvent;
+
+/// Counts taken when a turn opens, to work out what changed by its end
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TurnCounters {
+    pub score: i32,
+    pub moves: i32,
+    pub objective_cleared: i32,
+}
+
+/// What a turn has done so far
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TurnSummary {
+    /// The kind of move that opened the turn ("swap", "tap", "link" or "hold")
+    pub cause: &'static str,
+    /// Matches scored, over every resolution
+    pub matches: i32,
+    /// Resolutions that scored matches
+    pub resolutions: i32,
+    /// Seekers, striped symbols and color bombs left behind
+    pub specials_created: i32,
+    /// Seekers launched, stripes fired, color bombs set off and special combos
+    pub specials_activated: i32,
+    /// Chocolate cells eaten
+    pub chocolate_eaten: i32,
+    /// Ingredients brought out through the bottom row
+    pub ingredients_collected: i32,
+    start: TurnCounters,
+}
+
+impl TurnSummary {
+    /// Open a turn for a move of kind `cause`, taken at the counts before the move
+    pub fn begin(cause: &'static str, start: TurnCounters) -> Self {
+        Self {
+            cause,
+            start,
+            ..Self::default()
+        }
+    }
+
+    /// Tally a model event
+    pub fn record(&mut self, event: &ModelEvent) {
+        match event {
+            ModelEvent::MatchFound { count } => {
+                self.matches += count;
+                self.resolutions += 1;
+            }
+            ModelEvent::SeekerCreated(_) | ModelEvent::StripedCreated { .. } | ModelEvent::ColorBombCreated(_) => {
+                self.specials_created += 1;
+            }
+            ModelEvent::SeekerLaunched { .. }
+            | ModelEvent::StripeFired { .. }
+            | ModelEvent::ColorBombFired { .. }
+            | ModelEvent::ComboTriggered { .. } => {
+                self.specials_activated += 1;
+            }
+            ModelEvent::ChocolateEaten(_) => self.chocolate_eaten += 1,
+            ModelEvent::IngredientCollected { .. } => self.ingredients_collected += 1,
+            _ => {}
+        }
+    }
+
+    /// Get the cascades after the move's own resolution
+    pub fn cascades(&self) -> i32 {
+        (self.resolutions - 1).max(0)
+    }
+
+    /// Get how much each counter changed between the start of the turn and `now`
+    pub fn deltas(&self, now: TurnCounters) -> TurnCounters {
+        TurnCounters {
+            score: now.score - self.start.score,
+            moves: now.moves - self.start.moves,
+            objective_cleared: now.objective_cleared - self.start.objective_cleared,
+        }
+    }
+}
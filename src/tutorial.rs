@@ -0,0 +1,136 @@
+//! Scripted tutorials - authored steps that constrain the board
+//!
+//! Steps are declared as dictionaries (e.g. in a level's `tutorial` list):
+//!
+//! ```text
+//! { "text": "Swap these two", "swap": [Vector2i(3, 4), Vector2i(3, 5)],
+//!   "spotlight": [Vector2i(3, 4), Vector2i(3, 5)], "freeze_rotation": true }
+//! ```
+//!
+//! A step with a `swap` only allows that swap and advances once it is made;
+//! a step without one leaves play free and advances when the script says so.
+
+use crate::model::BoardModel;
+use godot::prelude::*;
+
+/// One authored tutorial step
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TutorialStep {
+    /// Message for the UI to show
+    pub text: String,
+    /// The only swap allowed during this step
+    pub allowed_swap: Option<(Vector2i, Vector2i)>,
+    /// Cells to highlight; all others are dimmed
+    pub spotlight: Vec<Vector2i>,
+    /// Whether the rotation timer is stopped
+    pub freeze_rotation: bool,
+}
+
+impl TutorialStep {
+    /// Parse a step from its dictionary form
+    pub fn from_dictionary(dict: &Dictionary) -> Result<Self, String> {
+        let mut step = TutorialStep::default();
+
+        if let Some(text) = dict.get("text") {
+            step.text = text.try_to::<GString>().map_err(|_| "text must be a string")?.to_string();
+        }
+
+        if let Some(swap) = dict.get("swap") {
+            let cells = parse_cells(&swap).map_err(|err| format!("swap: {}", err))?;
+            let [from, to] = cells[..] else {
+                return Err("swap must list exactly two cells".to_string());
+            };
+            if !BoardModel::is_adjacent(from, to) {
+                return Err(format!("swap cells are not adjacent: {:?} {:?}", from, to));
+            }
+            step.allowed_swap = Some((from, to));
+        }
+
+        if let Some(spotlight) = dict.get("spotlight") {
+            step.spotlight = parse_cells(&spotlight).map_err(|err| format!("spotlight: {}", err))?;
+        }
+
+        if let Some(freeze) = dict.get("freeze_rotation") {
+            step.freeze_rotation = freeze.try_to::<bool>().map_err(|_| "freeze_rotation must be a bool")?;
+        }
+
+        Ok(step)
+    }
+
+    /// Check if this step's swap is the given one, in either direction
+    pub fn is_allowed_swap(&self, pos1: Vector2i, pos2: Vector2i) -> bool {
+        match self.allowed_swap {
+            Some((from, to)) => (from, to) == (pos1, pos2) || (from, to) == (pos2, pos1),
+            None => true,
+        }
+    }
+}
+
+/// Read an array of Vector2i cells
+fn parse_cells(value: &Variant) -> Result<Vec<Vector2i>, String> {
+    let array = value.try_to::<VariantArray>().map_err(|_| "expected an array of Vector2i")?;
+    array
+        .iter_shared()
+        .map(|cell| cell.try_to::<Vector2i>().map_err(|_| format!("not a Vector2i: {}", cell)))
+        .collect()
+}
+
+/// Progress through a list of tutorial steps
+#[derive(Debug, Clone, Default)]
+pub struct Tutorial {
+    steps: Vec<TutorialStep>,
+    current: usize,
+}
+
+impl Tutorial {
+    /// Start a tutorial at its first step
+    pub fn new(steps: Vec<TutorialStep>) -> Self {
+        Self { steps, current: 0 }
+    }
+
+    /// Parse and start a tutorial from step dictionaries
+    pub fn from_dictionaries(steps: &Array<Dictionary>) -> Result<Self, String> {
+        let steps = steps
+            .iter_shared()
+            .enumerate()
+            .map(|(i, dict)| TutorialStep::from_dictionary(&dict).map_err(|err| format!("step {}: {}", i, err)))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self::new(steps))
+    }
+
+    /// Get the current step, if the tutorial is still running
+    pub fn current(&self) -> Option<&TutorialStep> {
+        self.steps.get(self.current)
+    }
+
+    /// Get the index of the current step
+    pub fn step_index(&self) -> usize {
+        self.current
+    }
+
+    /// Move to the next step, returning it if there is one
+    pub fn advance(&mut self) -> Option<&TutorialStep> {
+        if self.current < self.steps.len() {
+            self.current += 1;
+        }
+        self.current()
+    }
+
+    /// Check if a cell may be selected
+    pub fn allows_selection(&self, pos: Vector2i) -> bool {
+        match self.current().and_then(|step| step.allowed_swap) {
+            Some((from, to)) => pos == from || pos == to,
+            None => true,
+        }
+    }
+
+    /// Check if a swap may be made
+    pub fn allows_swap(&self, pos1: Vector2i, pos2: Vector2i) -> bool {
+        self.current().is_none_or(|step| step.is_allowed_swap(pos1, pos2))
+    }
+
+    /// Check if the rotation timer is frozen
+    pub fn freezes_rotation(&self) -> bool {
+        self.current().is_some_and(|step| step.freeze_rotation)
+    }
+}
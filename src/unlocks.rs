@@ -0,0 +1,37 @@
+//! Unlocks - mechanics a campaign hands out to the player over time
+//!
+//! Each flag gates one mechanic on top of the core swap-and-match play. The
+//! host game sets flags as the player progresses; they are saved with the
+//! player's profile, so a mechanic stays unlocked across sessions. A locked
+//! mechanic's funcs refuse to run, as if the feature were missing.
+
+/// Spinning the board a quarter turn clockwise, on square boards only
+pub const UNLOCK_SPIN: i32 = 1;
+
+/// Spinning the board counterclockwise as well
+pub const UNLOCK_SPIN_COUNTERCLOCKWISE: i32 = 2;
+
+/// Stashing a symbol in the hold slot, where the board has one
+pub const UNLOCK_HOLD: i32 = 4;
+
+/// The rewind booster
+pub const UNLOCK_REWIND: i32 = 8;
+
+/// Turning a single symbol by hand, in rulesets where a tap rotates it
+pub const UNLOCK_MANUAL_ROTATION: i32 = 16;
+
+/// Turning a single symbol counterclockwise as well
+pub const UNLOCK_ROTATE_COUNTERCLOCKWISE: i32 = 32;
+
+/// Every mechanic
+pub const UNLOCK_ALL: i32 = UNLOCK_SPIN
+    | UNLOCK_SPIN_COUNTERCLOCKWISE
+    | UNLOCK_HOLD
+    | UNLOCK_REWIND
+    | UNLOCK_MANUAL_ROTATION
+    | UNLOCK_ROTATE_COUNTERCLOCKWISE;
+
+/// Check if every mechanic in `flags` is unlocked in `unlocks`
+pub fn allows(unlocks: i32, flags: i32) -> bool {
+    unlocks & flags == flags
+}
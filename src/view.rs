@@ -0,0 +1,1065 @@
+//! Board view - the nodes and tweens that present a `BoardModel`
+//!
+//! All symbol nodes live under a dedicated container node owned by the view,
+//! so the view never needs to borrow the `GameBoard` that drives it.
+
+use crate::animation::{AnimationKind, AnimationOwners};
+use crate::emphasis::{self, EmphasisMap, EmphasisSource};
+use crate::model::{Fall, Spawn};
+use crate::symbols::{CellOverlay, Grid};
+use godot::classes::{ColorRect, Node2D, Tween};
+use godot::prelude::*;
+
+/// Brightness of a fully dimmed symbol (emphasis -1)
+const DIMMED_LEVEL: f32 = 0.35;
+
+/// Brightness added to a fully emphasized symbol (emphasis 1)
+const LIFT: f32 = 0.35;
+
+/// Width of the outline around an emphasized symbol
+const OUTLINE_WIDTH: f32 = 4.0;
+
+/// Color of the outline around a fully emphasized symbol; lesser emphasis fades it
+const OUTLINE: Color = Color::from_rgba(1.0, 1.0, 0.85, 0.9);
+
+/// Color of the fog over hidden cells; symbols show through faintly
+const FOG: Color = Color::from_rgba(0.55, 0.6, 0.65, 0.85);
+
+/// Scale a fast-falling symbol starts at, stretched along its fall
+const FALL_STRETCH: Vector2 = Vector2::new(0.8, 1.35);
+
+/// Nodes a view holds, by what they are doing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NodeCounts {
+    /// Symbol nodes on the grid
+    pub symbols: usize,
+    /// Cell tiles under the symbols
+    pub tiles: usize,
+    /// Symbol nodes off the grid, waiting for their clear animation
+    pub clearing: usize,
+    /// Hidden symbol nodes waiting for reuse
+    pub pooled: usize,
+    /// Symbol nodes with an animation still running
+    pub animating: usize,
+    /// Every child of the container, pooled nodes and seekers included
+    pub children: usize,
+}
+
+/// ColorRect-based presentation of the grid
+pub struct BoardView {
+    /// Container node holding all symbol nodes
+    root: Gd<Node2D>,
+
+    /// Visual representations of symbols, indexed like the grid
+    nodes: Vec<Option<Gd<ColorRect>>>,
+
+    /// Cell tiles drawn under the symbols, indexed like the grid
+    tiles: Vec<Option<Gd<ColorRect>>>,
+
+    /// Overlays such as stones drawn over their cells, indexed like the grid
+    overlays: Vec<Option<Gd<ColorRect>>>,
+
+    /// Fog covering hidden cells, above everything else, indexed like the grid
+    fog: Vec<Option<Gd<ColorRect>>>,
+
+    /// Outlines drawn around emphasized symbols, between the tiles and the symbols, indexed like the grid
+    outlines: Vec<Option<Gd<ColorRect>>>,
+
+    width: usize,
+    height: usize,
+    cell_size: f32,
+    cell_padding: f32,
+
+    /// Top-left corner of the board within the container
+    offset: Vector2,
+
+    /// Animation tweens that may still be running
+    tweens: Vec<Gd<Tween>>,
+
+    /// The animation driving each symbol node, so a new one can stop the old one cleanly
+    owners: AnimationOwners,
+
+    /// Nodes off the grid that are waiting for their clear or flight animation
+    clearing: Vec<Gd<ColorRect>>,
+
+    /// How strongly each cell is drawn out or dimmed, by source
+    emphasis: EmphasisMap,
+
+    /// Whether removed symbol nodes are hidden and kept for reuse instead of freed
+    pooling: bool,
+
+    /// Hidden symbol nodes ready for reuse
+    pool: Vec<Gd<ColorRect>>,
+}
+
+impl BoardView {
+    /// Create a view with a fresh, detached container node
+    pub fn new() -> Self {
+        let mut root = Node2D::new_alloc();
+        root.set_name("Symbols");
+
+        Self {
+            root,
+            nodes: Vec::new(),
+            tiles: Vec::new(),
+            overlays: Vec::new(),
+            fog: Vec::new(),
+            outlines: Vec::new(),
+            width: 0,
+            height: 0,
+            cell_size: 64.0,
+            cell_padding: 4.0,
+            offset: Vector2::ZERO,
+            tweens: Vec::new(),
+            owners: AnimationOwners::default(),
+            clearing: Vec::new(),
+            emphasis: EmphasisMap::default(),
+            pooling: false,
+            pool: Vec::new(),
+        }
+    }
+
+    /// Get the container node; the owner must add it to the scene tree
+    pub fn root(&self) -> &Gd<Node2D> {
+        &self.root
+    }
+
+    /// Set the grid dimensions and layout metrics used by later calls
+    pub fn configure(&mut self, width: usize, height: usize, cell_size: f32, cell_padding: f32, offset: Vector2) {
+        self.width = width;
+        self.height = height;
+        self.cell_size = cell_size;
+        self.cell_padding = cell_padding;
+        self.offset = offset;
+        self.clear_tiles();
+        self.clear_fog();
+        self.clear_outlines();
+    }
+
+    /// Move and resize the existing nodes for a new cell size and offset
+    pub fn relayout(&mut self, cell_size: f32, offset: Vector2) {
+        self.cell_size = cell_size;
+        self.offset = offset;
+
+        let size = self.symbol_size();
+        let width = self.width.max(1);
+        for idx in 0..self.nodes.len() {
+            let pos = Vector2i::new((idx % width) as i32, (idx / width) as i32);
+            let position = self.cell_position(pos);
+            if let Some(node) = self.nodes[idx].as_mut() {
+                node.set_size(Vector2::new(size, size));
+                node.set_position(position);
+            }
+        }
+
+        for idx in 0..self.overlays.len() {
+            let pos = Vector2i::new((idx % width) as i32, (idx / width) as i32);
+            let position = self.cell_position(pos);
+            if let Some(overlay) = self.overlays[idx].as_mut() {
+                overlay.set_size(Vector2::new(size, size));
+                overlay.set_position(position);
+            }
+        }
+
+        for idx in 0..self.tiles.len() {
+            let pos = Vector2i::new((idx % width) as i32, (idx / width) as i32);
+            let position = self.tile_position(pos);
+            if let Some(tile) = self.tiles[idx].as_mut() {
+                tile.set_size(Vector2::new(cell_size, cell_size));
+                tile.set_position(position);
+            }
+        }
+
+        for idx in 0..self.fog.len() {
+            let pos = Vector2i::new((idx % width) as i32, (idx / width) as i32);
+            let position = self.tile_position(pos);
+            if let Some(fog) = self.fog[idx].as_mut() {
+                fog.set_size(Vector2::new(cell_size, cell_size));
+                fog.set_position(position);
+            }
+        }
+
+        let outline_size = size + OUTLINE_WIDTH * 2.0;
+        for idx in 0..self.outlines.len() {
+            let pos = Vector2i::new((idx % width) as i32, (idx / width) as i32);
+            let position = self.cell_position(pos) - Vector2::new(OUTLINE_WIDTH, OUTLINE_WIDTH);
+            if let Some(outline) = self.outlines[idx].as_mut() {
+                outline.set_size(Vector2::new(outline_size, outline_size));
+                outline.set_position(position);
+            }
+        }
+    }
+
+    /// Get the top-left corner of the board
+    pub fn offset(&self) -> Vector2 {
+        self.offset
+    }
+
+    /// Get the side length of a symbol node
+    pub fn symbol_size(&self) -> f32 {
+        self.cell_size - self.cell_padding * 2.0
+    }
+
+    /// Convert grid coordinates to a position within the board
+    pub fn cell_position(&self, pos: Vector2i) -> Vector2 {
+        Vector2::new(
+            self.offset.x + pos.x as f32 * self.cell_size + self.cell_padding,
+            self.offset.y + pos.y as f32 * self.cell_size + self.cell_padding,
+        )
+    }
+
+    /// Get the top-left corner of a whole cell, ignoring padding
+    fn tile_position(&self, pos: Vector2i) -> Vector2 {
+        self.offset + Vector2::new(pos.x as f32, pos.y as f32) * self.cell_size
+    }
+
+    /// Get the center of a cell within the board
+    pub fn cell_center(&self, pos: Vector2i) -> Vector2 {
+        Vector2::new(
+            self.offset.x + (pos.x as f32 + 0.5) * self.cell_size,
+            self.offset.y + (pos.y as f32 + 0.5) * self.cell_size,
+        )
+    }
+
+    /// Convert a position within the board to grid coordinates
+    pub fn cell_at(&self, point: Vector2) -> Option<Vector2i> {
+        let local_x = point.x - self.offset.x;
+        let local_y = point.y - self.offset.y;
+
+        if local_x < 0.0 || local_y < 0.0 {
+            return None;
+        }
+
+        let grid_x = (local_x / self.cell_size) as usize;
+        let grid_y = (local_y / self.cell_size) as usize;
+
+        if grid_x < self.width && grid_y < self.height {
+            Some(Vector2i::new(grid_x as i32, grid_y as i32))
+        } else {
+            None
+        }
+    }
+
+    /// Create an animation tween, tracked so it can be killed
+    fn create_tween(&mut self) -> Option<Gd<Tween>> {
+        self.tweens.retain(|tween| tween.is_instance_valid() && tween.is_valid());
+
+        let tween = self.root.create_tween()?;
+        self.tweens.push(tween.clone());
+        Some(tween)
+    }
+
+    /// Create an animation tween on a symbol node, taking the node over from any animation still running on it;
+    /// `rest` is where the node is left if this animation is cut short in turn
+    fn animate_node(&mut self, node: &Gd<ColorRect>, kind: AnimationKind, rest: Option<Vector2>) -> Option<Gd<Tween>> {
+        self.tweens.retain(|tween| tween.is_instance_valid() && tween.is_valid());
+
+        let tween = node.clone().create_tween()?;
+        self.tweens.push(tween.clone());
+        self.owners.claim(node, kind, tween.clone(), rest);
+        Some(tween)
+    }
+
+    /// Call `on_complete` once `duration` has passed, on a tween of its own so it fires
+    /// however the node animations in between end
+    fn call_after(&mut self, duration: f64, on_complete: &Callable) {
+        if let Some(mut tween) = self.create_tween() {
+            tween.tween_interval(duration);
+            tween.tween_callback(on_complete);
+        }
+    }
+
+    /// Stop every running animation so no completion callback fires, leaving symbol nodes at rest
+    pub fn kill_tweens(&mut self) {
+        self.owners.stop_all();
+        for mut tween in self.tweens.drain(..) {
+            if tween.is_instance_valid() {
+                tween.kill();
+            }
+        }
+
+        // Their clear animations will never free them now
+        let clearing: Vec<Gd<ColorRect>> = self.clearing.drain(..).collect();
+        for node in clearing {
+            if node.is_instance_valid() {
+                self.release_node(node);
+            }
+        }
+    }
+
+    /// Keep removed symbol nodes for reuse instead of freeing them; disabling frees the pool
+    pub fn set_pooling(&mut self, pooling: bool) {
+        self.pooling = pooling;
+        if !pooling {
+            for mut node in self.pool.drain(..) {
+                if node.is_instance_valid() {
+                    node.queue_free();
+                }
+            }
+        }
+    }
+
+    /// Count the nodes the view holds
+    pub fn node_counts(&self) -> NodeCounts {
+        NodeCounts {
+            symbols: self.nodes.iter().flatten().count(),
+            tiles: self.tiles.iter().flatten().count(),
+            clearing: self.clearing.iter().filter(|node| node.is_instance_valid()).count(),
+            pooled: self.pool.len(),
+            animating: self.owners.running(),
+            children: self.root.get_child_count() as usize,
+        }
+    }
+
+    /// Free a symbol node, or hide it in the pool when pooling
+    fn release_node(&mut self, mut node: Gd<ColorRect>) {
+        if self.pooling {
+            node.hide();
+            self.pool.push(node);
+        } else {
+            node.queue_free();
+        }
+    }
+
+    /// Move nodes whose clear animation has hidden them into the pool
+    fn reclaim_cleared(&mut self) {
+        self.clearing.retain(|node| node.is_instance_valid());
+        let (done, running): (Vec<_>, Vec<_>) = self.clearing.drain(..).partition(|node| !node.is_visible());
+        self.clearing = running;
+        self.pool.extend(done);
+    }
+
+    /// Check if any animation is still running
+    pub fn is_animating(&self) -> bool {
+        self.tweens
+            .iter()
+            .any(|tween| tween.is_instance_valid() && tween.is_valid() && tween.is_running())
+    }
+
+    /// Pause or resume every running animation
+    pub fn set_paused(&mut self, paused: bool) {
+        self.tweens.retain(|tween| tween.is_instance_valid() && tween.is_valid());
+
+        for tween in &mut self.tweens {
+            if paused {
+                tween.pause();
+            } else {
+                tween.play();
+            }
+        }
+    }
+
+    /// Get the node index for a position
+    fn index(&self, pos: Vector2i) -> Option<usize> {
+        if pos.x < 0 || pos.y < 0 || pos.x as usize >= self.width || pos.y as usize >= self.height {
+            return None;
+        }
+        Some(pos.y as usize * self.width + pos.x as usize)
+    }
+
+    /// Create a symbol node at its cell
+    fn create_node(&mut self, pos: Vector2i, color: Color) -> Gd<ColorRect> {
+        if self.pooling && self.pool.is_empty() {
+            self.reclaim_cleared();
+        }
+        let pooled = self.pool.pop().filter(|node| node.is_instance_valid());
+        let size = self.symbol_size();
+
+        let mut rect = match pooled {
+            Some(mut rect) => {
+                rect.set_scale(Vector2::ONE);
+                rect.set_rotation(0.0);
+                rect.set_modulate(Color::WHITE);
+                rect.set_self_modulate(Color::WHITE);
+                rect.show();
+                rect
+            }
+            None => {
+                let rect = ColorRect::new_alloc();
+                self.root.add_child(&rect);
+                rect
+            }
+        };
+        rect.set_size(Vector2::new(size, size));
+        rect.set_color(color);
+        rect.set_position(self.cell_position(pos));
+        rect
+    }
+
+    /// Show a tile of the given color under a cell, or remove it with None
+    pub fn set_tile(&mut self, pos: Vector2i, color: Option<Color>) {
+        let Some(idx) = self.index(pos) else {
+            return;
+        };
+        let Some(color) = color else {
+            if let Some(mut tile) = self.tiles[idx].take() {
+                tile.queue_free();
+            }
+            return;
+        };
+        if let Some(tile) = self.tiles[idx].as_mut() {
+            tile.set_color(color);
+            return;
+        }
+
+        let mut tile = ColorRect::new_alloc();
+        tile.set_size(Vector2::new(self.cell_size, self.cell_size));
+        tile.set_position(self.tile_position(pos));
+        tile.set_color(color);
+        tile.set_z_index(-2);
+        self.root.add_child(&tile);
+        self.tiles[idx] = Some(tile);
+    }
+
+    /// Remove every tile, sizing the tile layer for the current grid
+    pub fn clear_tiles(&mut self) {
+        for mut tile in self.tiles.drain(..).flatten() {
+            tile.queue_free();
+        }
+        self.tiles = vec![None; self.width * self.height];
+    }
+
+    /// Draw an overlay over a cell, or remove it with None
+    pub fn set_overlay(&mut self, pos: Vector2i, overlay: Option<CellOverlay>) {
+        let Some(idx) = self.index(pos) else {
+            return;
+        };
+        let Some(overlay) = overlay else {
+            if let Some(mut node) = self.overlays[idx].take() {
+                node.queue_free();
+            }
+            return;
+        };
+        if let Some(node) = self.overlays[idx].as_mut() {
+            node.set_color(overlay.color());
+            return;
+        }
+
+        let size = self.symbol_size();
+        let mut node = ColorRect::new_alloc();
+        node.set_size(Vector2::new(size, size));
+        node.set_position(self.cell_position(pos));
+        node.set_color(overlay.color());
+        node.set_z_index(1);
+        self.root.add_child(&node);
+        self.overlays[idx] = Some(node);
+    }
+
+    /// Free every overlay node and draw the grid's overlays
+    fn show_overlays(&mut self, grid: &Grid) {
+        for mut node in self.overlays.drain(..).flatten() {
+            node.queue_free();
+        }
+        self.overlays = vec![None; self.width * self.height];
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                self.set_overlay(Vector2i::new(x as i32, y as i32), grid.overlay(x, y));
+            }
+        }
+    }
+
+    /// Cover a cell in fog, or clear it
+    /// Ignored before the first `configure`, when there is no fog layer to draw on
+    pub fn set_fogged(&mut self, pos: Vector2i, fogged: bool) {
+        let Some(idx) = self.index(pos).filter(|idx| *idx < self.fog.len()) else {
+            return;
+        };
+        if !fogged {
+            if let Some(mut fog) = self.fog[idx].take() {
+                fog.queue_free();
+            }
+            return;
+        }
+        if self.fog[idx].is_some() {
+            return;
+        }
+
+        let mut fog = ColorRect::new_alloc();
+        fog.set_size(Vector2::new(self.cell_size, self.cell_size));
+        fog.set_position(self.tile_position(pos));
+        fog.set_color(FOG);
+        fog.set_z_index(2);
+        self.root.add_child(&fog);
+        self.fog[idx] = Some(fog);
+    }
+
+    /// Clear the fog from every cell, sizing the fog layer for the current grid
+    pub fn clear_fog(&mut self) {
+        for mut fog in self.fog.drain(..).flatten() {
+            fog.queue_free();
+        }
+        self.fog = vec![None; self.width * self.height];
+    }
+
+    /// Tint the node at a position on top of its color, e.g. to show its age
+    pub fn set_cell_tint(&mut self, pos: Vector2i, tint: Color) {
+        if let Some(node) = self.index(pos).and_then(|idx| self.nodes[idx].as_mut()) {
+            node.set_self_modulate(tint);
+        }
+    }
+
+    /// Dim every cell except the given ones (none dims nothing)
+    pub fn set_spotlight(&mut self, cells: &[Vector2i]) {
+        let rest = if cells.is_empty() { 0.0 } else { emphasis::DIMMED };
+        self.emphasis.fill(EmphasisSource::Tutorial, rest);
+        for pos in cells {
+            self.emphasis.set(EmphasisSource::Tutorial, *pos, 0.0);
+        }
+        self.apply_emphasis();
+    }
+
+    /// Dim the given locked cells (none dims nothing)
+    pub fn set_locked(&mut self, cells: &[Vector2i]) {
+        self.set_emphasis(EmphasisSource::Lock, cells, emphasis::DIMMED);
+    }
+
+    /// Replace a source's emphasis with `emphasis` on the given cells and none elsewhere
+    pub fn set_emphasis(&mut self, source: EmphasisSource, cells: &[Vector2i], emphasis: f32) {
+        self.emphasis.clear(source);
+        for pos in cells {
+            self.emphasis.set(source, *pos, emphasis);
+        }
+        self.apply_emphasis();
+    }
+
+    /// Take a source's emphasis off every cell
+    pub fn clear_emphasis(&mut self, source: EmphasisSource) {
+        if !self.emphasis.is_clear(source) {
+            self.emphasis.clear(source);
+            self.apply_emphasis();
+        }
+    }
+
+    /// Get the emphasis per cell, by source
+    pub fn emphasis(&self) -> &EmphasisMap {
+        &self.emphasis
+    }
+
+    /// Draw each cell's blended emphasis on the symbol currently in it: brightened and outlined, or dimmed
+    fn apply_emphasis(&mut self) {
+        let width = self.width.max(1);
+        if self.outlines.len() != self.nodes.len() {
+            self.clear_outlines();
+        }
+        for idx in 0..self.nodes.len() {
+            let pos = Vector2i::new((idx % width) as i32, (idx / width) as i32);
+            let emphasis = self.emphasis.blended(pos);
+            let Some(node) = self.nodes[idx].as_mut() else {
+                self.set_outline(idx, 0.0);
+                continue;
+            };
+            let level = if emphasis < 0.0 { 1.0 + (1.0 - DIMMED_LEVEL) * emphasis } else { 1.0 + LIFT * emphasis };
+            node.set_modulate(Color::from_rgba(level, level, level, 1.0));
+            self.set_outline(idx, emphasis);
+        }
+    }
+
+    /// Show the outline around a cell's symbol at a strength, creating it if missing, or hide it at 0 or below
+    fn set_outline(&mut self, idx: usize, emphasis: f32) {
+        if emphasis <= 0.0 {
+            if let Some(outline) = self.outlines.get_mut(idx).and_then(Option::as_mut) {
+                outline.hide();
+            }
+            return;
+        }
+        if idx >= self.outlines.len() {
+            return;
+        }
+
+        if self.outlines[idx].is_none() {
+            let pos = Vector2i::new((idx % self.width.max(1)) as i32, (idx / self.width.max(1)) as i32);
+            let size = self.symbol_size() + OUTLINE_WIDTH * 2.0;
+            let mut outline = ColorRect::new_alloc();
+            outline.set_size(Vector2::new(size, size));
+            outline.set_position(self.cell_position(pos) - Vector2::new(OUTLINE_WIDTH, OUTLINE_WIDTH));
+            outline.set_z_index(-1);
+            self.root.add_child(&outline);
+            self.outlines[idx] = Some(outline);
+        }
+        if let Some(outline) = self.outlines[idx].as_mut() {
+            outline.set_color(Color::from_rgba(OUTLINE.r, OUTLINE.g, OUTLINE.b, OUTLINE.a * emphasis));
+            outline.show();
+        }
+    }
+
+    /// Remove every outline, sizing the outline layer for the current grid
+    fn clear_outlines(&mut self) {
+        for mut outline in self.outlines.drain(..).flatten() {
+            outline.queue_free();
+        }
+        self.outlines = vec![None; self.width * self.height];
+    }
+
+    /// Free all nodes and create one per symbol and overlay in the grid
+    pub fn rebuild(&mut self, grid: &Grid) {
+        let nodes: Vec<Gd<ColorRect>> = self.nodes.drain(..).flatten().collect();
+        for node in nodes {
+            self.release_node(node);
+        }
+
+        self.nodes = vec![None; self.width * self.height];
+
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                if let Some(symbol) = grid.get(x, y) {
+                    let pos = Vector2i::new(x as i32, y as i32);
+                    let node = self.create_node(pos, symbol.current_color());
+                    if let Some(idx) = self.index(pos) {
+                        self.nodes[idx] = Some(node);
+                    }
+                }
+            }
+        }
+        self.show_overlays(grid);
+        self.apply_emphasis();
+    }
+
+    /// Recolor the node at a position, creating it if missing
+    pub fn set_cell_color(&mut self, pos: Vector2i, color: Color) {
+        let Some(idx) = self.index(pos) else {
+            return;
+        };
+
+        match self.nodes[idx].as_mut() {
+            Some(node) => node.set_color(color),
+            None => {
+                let node = self.create_node(pos, color);
+                self.nodes[idx] = Some(node);
+                self.apply_emphasis();
+            }
+        }
+    }
+
+    /// List cells whose node is missing, freed, orphaned or showing the wrong color
+    pub fn find_mismatches(&self, grid: &Grid) -> Vec<Vector2i> {
+        let mut mismatches = Vec::new();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let pos = Vector2i::new(x as i32, y as i32);
+                let Some(idx) = self.index(pos) else {
+                    continue;
+                };
+                let in_sync = match (grid.get(x, y), self.nodes.get(idx).and_then(Option::as_ref)) {
+                    (Some(symbol), Some(node)) => {
+                        node.is_instance_valid() && node.get_color() == symbol.current_color()
+                    }
+                    (None, None) => true,
+                    _ => false,
+                };
+                if !in_sync {
+                    mismatches.push(pos);
+                }
+            }
+        }
+
+        mismatches
+    }
+
+    /// List cells whose node was left away from its resting place (moved, scaled, turned or hidden)
+    /// by an animation that never finished; only meaningful while nothing is animating
+    pub fn find_misplaced(&self) -> Vec<Vector2i> {
+        let mut misplaced = Vec::new();
+        for (idx, node) in self.nodes.iter().enumerate() {
+            let Some(node) = node.as_ref().filter(|node| node.is_instance_valid()) else {
+                continue;
+            };
+            let pos = Vector2i::new((idx % self.width) as i32, (idx / self.width) as i32);
+            let at_rest = node.get_position().distance_to(self.cell_position(pos)) < 0.5
+                && node.get_scale() == Vector2::ONE
+                && node.get_rotation() == 0.0
+                && node.is_visible();
+            if !at_rest {
+                misplaced.push(pos);
+            }
+        }
+        misplaced
+    }
+
+    /// Recreate the nodes at the given cells from the grid
+    pub fn repair(&mut self, grid: &Grid, positions: &[Vector2i]) {
+        for pos in positions {
+            let Some(idx) = self.index(*pos) else {
+                continue;
+            };
+            if let Some(node) = self.nodes[idx].take().filter(|node| node.is_instance_valid()) {
+                self.release_node(node);
+            }
+            if let Some(symbol) = grid.get(pos.x as usize, pos.y as usize) {
+                let node = self.create_node(*pos, symbol.current_color());
+                self.nodes[idx] = Some(node);
+            }
+        }
+        self.apply_emphasis();
+    }
+
+    /// Show or clear the selection emphasis on a cell
+    pub fn set_highlighted(&mut self, pos: Vector2i, highlighted: bool) {
+        let emphasis = if highlighted { emphasis::SELECTED } else { 0.0 };
+        self.emphasis.set(EmphasisSource::Selection, pos, emphasis);
+        self.apply_emphasis();
+    }
+
+    /// Swap the nodes of two cells
+    pub fn swap(&mut self, pos1: Vector2i, pos2: Vector2i) {
+        let (Some(idx1), Some(idx2)) = (self.index(pos1), self.index(pos2)) else {
+            return;
+        };
+        self.nodes.swap(idx1, idx2);
+
+        self.set_highlighted(pos1, false);
+        self.set_highlighted(pos2, false);
+        self.apply_emphasis();
+    }
+
+    /// Shrink the nodes at the given positions away, then call `on_complete`
+    pub fn animate_clear(&mut self, positions: &[Vector2i], duration: f64, on_complete: &Callable) {
+        let nodes_to_clear: Vec<Gd<ColorRect>> = positions
+            .iter()
+            .filter_map(|pos| self.index(*pos))
+            .filter_map(|idx| self.nodes[idx].take())
+            .collect();
+
+        // Shrink each node to nothing, then free it (or hide it for the pool)
+        let zero_scale = Variant::from(Vector2::ZERO);
+        let release = if self.pooling { "hide" } else { "queue_free" };
+        for node in &nodes_to_clear {
+            if let Some(mut tween) = self.animate_node(node, AnimationKind::Clear, None) {
+                tween.tween_property(node, "scale", &zero_scale, duration);
+                tween.tween_callback(&node.callable(release));
+            }
+        }
+        self.call_after(duration, on_complete);
+
+        self.clearing.retain(|node| node.is_instance_valid());
+        self.clearing.extend(nodes_to_clear);
+    }
+
+    /// Flash `color` over the given cells, fading out over `duration`
+    pub fn flash_cells(&mut self, cells: &[Vector2i], color: Color, duration: f64) {
+        let Some(mut tween) = self.create_tween() else {
+            return;
+        };
+        tween.set_parallel();
+
+        let size = self.symbol_size();
+        let transparent = Variant::from(Color::from_rgba(color.r, color.g, color.b, 0.0));
+        let mut flashes = Vec::with_capacity(cells.len());
+        for pos in cells {
+            let mut flash = ColorRect::new_alloc();
+            flash.set_size(Vector2::new(size, size));
+            flash.set_color(color);
+            flash.set_position(self.cell_center(*pos) - Vector2::new(size, size) / 2.0);
+            self.root.add_child(&flash);
+            tween.tween_property(&flash, "color", &transparent, duration);
+            flashes.push(flash);
+        }
+
+        // Free the flashes once they have faded
+        tween.chain();
+        for flash in &flashes {
+            tween.tween_callback(&flash.callable("queue_free"));
+        }
+
+        self.clearing.retain(|node| node.is_instance_valid());
+        self.clearing.extend(flashes);
+    }
+
+    /// Fly a seeker from one cell to another over `duration`
+    pub fn animate_seeker(&mut self, from: Vector2i, to: Vector2i, duration: f64) {
+        let mut seeker = ColorRect::new_alloc();
+        let size = self.symbol_size() * 0.4;
+        seeker.set_size(Vector2::new(size, size));
+        seeker.set_color(Color::WHITE);
+        seeker.set_position(self.cell_center(from) - Vector2::new(size, size) / 2.0);
+        self.root.add_child(&seeker);
+
+        if let Some(mut tween) = self.create_tween() {
+            let target = Variant::from(self.cell_center(to) - Vector2::new(size, size) / 2.0);
+            tween.tween_property(&seeker, "position", &target, duration);
+            tween.tween_callback(&seeker.callable("queue_free"));
+        }
+
+        self.clearing.retain(|node| node.is_instance_valid());
+        self.clearing.push(seeker);
+    }
+
+    /// Slide the nodes at the given positions down out of their cells while fading them away
+    /// Runs alongside whatever comes next; the cells are free for falls straight away
+    pub fn animate_exits(&mut self, positions: &[Vector2i], duration: f64) {
+        let exiting: Vec<Gd<ColorRect>> = positions
+            .iter()
+            .filter_map(|pos| self.index(*pos))
+            .filter_map(|idx| self.nodes[idx].take())
+            .collect();
+
+        let transparent = Variant::from(Color::from_rgba(1.0, 1.0, 1.0, 0.0));
+        let release = if self.pooling { "hide" } else { "queue_free" };
+        for node in &exiting {
+            let Some(mut tween) = self.animate_node(node, AnimationKind::Exit, None) else {
+                continue;
+            };
+            let below = Variant::from(node.get_position() + Vector2::new(0.0, self.cell_size));
+            tween.set_parallel();
+            tween.tween_property(node, "position", &below, duration);
+            tween.tween_property(node, "modulate", &transparent, duration);
+            tween.chain();
+            tween.tween_callback(&node.callable(release));
+        }
+
+        self.clearing.retain(|node| node.is_instance_valid());
+        self.clearing.extend(exiting);
+    }
+
+    /// Move nodes down along the given falls, bending where their paths do, then call `on_complete`
+    /// Falls of more than `stretch_over` cells (0 for none) stretch along the fall and ease back at the bottom
+    pub fn animate_falls(&mut self, falls: &[Fall], duration_per_cell: f64, stretch_over: i32, on_complete: &Callable) {
+        // Take every falling node first so destination slots can't be overwritten
+        let mut animations: Vec<(Gd<ColorRect>, Fall)> = Vec::new();
+        for fall in falls {
+            if let Some(node) = self.index(fall.from).and_then(|idx| self.nodes[idx].take()) {
+                animations.push((node, fall.clone()));
+            }
+        }
+
+        let mut longest = 0.0;
+        for (node, fall) in &animations {
+            let rest = self.cell_position(fall.to);
+            let Some(mut tween) = self.animate_node(node, AnimationKind::Fall, Some(rest)) else {
+                continue;
+            };
+            tween.set_parallel();
+
+            // Each leg of the path starts when the one before it ends
+            let (mut start, mut delay) = (fall.from, 0.0);
+            for waypoint in fall.bends.iter().chain([&fall.to]) {
+                let target = Variant::from(self.cell_position(*waypoint));
+                let duration = (waypoint.y - start.y) as f64 * duration_per_cell;
+                if let Some(mut tweener) = tween.tween_property(node, "position", &target, duration) {
+                    tweener.set_delay(delay);
+                }
+                start = *waypoint;
+                delay += duration;
+            }
+
+            if stretch_over > 0 && fall.to.y - fall.from.y > stretch_over {
+                let mut node = node.clone();
+                let size = self.symbol_size();
+                node.set_pivot_offset(Vector2::new(size / 2.0, size / 2.0));
+                node.set_scale(FALL_STRETCH);
+                tween.tween_property(&node, "scale", &Variant::from(Vector2::ONE), delay);
+            }
+            longest = f64::max(longest, delay);
+        }
+        self.call_after(longest, on_complete);
+
+        for (node, fall) in animations {
+            if let Some(idx) = self.index(fall.to) {
+                self.nodes[idx] = Some(node);
+            }
+        }
+        self.apply_emphasis();
+    }
+
+    /// Drop new nodes in from above the board, then call `on_complete`
+    pub fn animate_spawns(&mut self, spawns: &[Spawn], duration: f64, on_complete: &Callable) {
+        let mut new_nodes: Vec<(Gd<ColorRect>, Spawn)> = Vec::new();
+        let mut longest = 0.0;
+
+        for spawn in spawns {
+            let mut node = self.create_node(spawn.pos, spawn.symbol_type.color());
+            let target = self.cell_position(spawn.pos);
+            if let Some(mut tween) = self.animate_node(&node, AnimationKind::Spawn, Some(target)) {
+                // Start just above the board
+                node.set_position(Vector2::new(target.x, self.offset.y - self.cell_size));
+                let spawn_duration = duration + spawn.pos.y as f64 * 0.05;
+                tween.tween_property(&node, "position", &Variant::from(target), spawn_duration);
+                longest = f64::max(longest, spawn_duration);
+            }
+
+            new_nodes.push((node, *spawn));
+        }
+        self.call_after(longest, on_complete);
+
+        for (node, spawn) in new_nodes {
+            if let Some(idx) = self.index(spawn.pos) {
+                self.nodes[idx] = Some(node);
+            }
+        }
+        self.apply_emphasis();
+    }
+
+    /// Drop every symbol in from above the board, one column after another, then call `on_complete`
+    pub fn animate_intro(&mut self, duration: f64, column_delay: f64, on_complete: &Callable) {
+        let width = self.width.max(1);
+        let drop = Vector2::new(0.0, self.cell_size * (self.height as f32 + 1.0));
+        let mut longest = 0.0;
+        for idx in 0..self.nodes.len() {
+            let pos = Vector2i::new((idx % width) as i32, (idx / width) as i32);
+            let target = self.cell_position(pos);
+            let Some(mut node) = self.nodes[idx].clone() else {
+                continue;
+            };
+            let Some(mut tween) = self.animate_node(&node, AnimationKind::Intro, Some(target)) else {
+                continue;
+            };
+            node.set_position(target - drop);
+            let delay = pos.x as f64 * column_delay;
+            if let Some(mut tweener) = tween.tween_property(&node, "position", &Variant::from(target), duration) {
+                tweener.set_delay(delay);
+            }
+            longest = f64::max(longest, delay + duration);
+        }
+        self.call_after(longest, on_complete);
+    }
+
+    /// Send every symbol flying off away from the board's center, one column after another,
+    /// then call `on_complete`; the nodes stay off the board until rebuilt or brought back by an intro
+    pub fn animate_outro(&mut self, duration: f64, column_delay: f64, on_complete: &Callable) {
+        let width = self.width.max(1);
+        let half_cell = Vector2::new(self.cell_size / 2.0, self.cell_size / 2.0);
+        let center = self.offset + Vector2::new(self.width as f32, self.height as f32) * self.cell_size / 2.0;
+        let distance = self.cell_size * (self.width.max(self.height) as f32 + 2.0);
+        let mut longest = 0.0;
+        for idx in 0..self.nodes.len() {
+            let pos = Vector2i::new((idx % width) as i32, (idx / width) as i32);
+            let start = self.cell_position(pos);
+            let away = start + half_cell - center;
+            let direction = if away.length() < 1.0 { Vector2::new(0.0, 1.0) } else { away / away.length() };
+            let Some(node) = self.nodes[idx].clone() else {
+                continue;
+            };
+            let Some(mut tween) = self.animate_node(&node, AnimationKind::Outro, Some(start)) else {
+                continue;
+            };
+            let target = Variant::from(start + direction * distance);
+            let delay = pos.x as f64 * column_delay;
+            if let Some(mut tweener) = tween.tween_property(&node, "position", &target, duration) {
+                tweener.set_delay(delay);
+            }
+            longest = f64::max(longest, delay + duration);
+        }
+        self.call_after(longest, on_complete);
+    }
+
+    /// Spin every node a quarter turn, then call `on_complete`
+    pub fn animate_rotation(&mut self, duration: f64, on_complete: &Callable) {
+        let size = self.symbol_size();
+        let width = self.width.max(1);
+        let final_rotation = Variant::from(std::f64::consts::FRAC_PI_2);
+        for idx in 0..self.nodes.len() {
+            let pos = Vector2i::new((idx % width) as i32, (idx / width) as i32);
+            let Some(mut node) = self.nodes[idx].clone() else {
+                continue;
+            };
+            // Taking the node over first lands a fall still running, so it turns in its own cell
+            let rest = self.cell_position(pos);
+            let Some(mut tween) = self.animate_node(&node, AnimationKind::Rotation, Some(rest)) else {
+                continue;
+            };
+            node.set_pivot_offset(Vector2::new(size / 2.0, size / 2.0));
+            tween.tween_property(&node, "rotation", &final_rotation, duration);
+        }
+        self.call_after(duration, on_complete);
+    }
+
+    /// Move every node to the cell a quarter turn of `grid` takes it to, turning it on the way,
+    /// then call `on_complete`; the grid must be square
+    pub fn animate_spin(&mut self, grid: &Grid, clockwise: bool, duration: f64, on_complete: &Callable) {
+        let nodes = std::mem::take(&mut self.nodes);
+        self.nodes = vec![None; nodes.len()];
+        for (idx, node) in nodes.into_iter().enumerate() {
+            let pos = Vector2i::new((idx % self.width) as i32, (idx / self.width) as i32);
+            if let Some(to) = self.index(grid.rotated_position(pos, clockwise)) {
+                self.nodes[to] = node;
+            }
+        }
+        // Overlays are square and fixed to their cells, so they just move with the turn
+        let overlays = std::mem::take(&mut self.overlays);
+        self.overlays = vec![None; overlays.len()];
+        for (idx, overlay) in overlays.into_iter().enumerate() {
+            let pos = Vector2i::new((idx % self.width) as i32, (idx / self.width) as i32);
+            if let Some(to) = self.index(grid.rotated_position(pos, clockwise)) {
+                self.overlays[to] = overlay;
+            }
+        }
+        self.apply_emphasis();
+
+        let size = self.symbol_size();
+        let quarter = std::f64::consts::FRAC_PI_2;
+        let turn = Variant::from(if clockwise { quarter } else { -quarter });
+        for idx in 0..self.nodes.len() {
+            let Some(mut node) = self.nodes[idx].clone() else {
+                continue;
+            };
+            let pos = Vector2i::new((idx % self.width) as i32, (idx / self.width) as i32);
+            let target = self.cell_position(pos);
+            let Some(mut tween) = self.animate_node(&node, AnimationKind::Spin, Some(target)) else {
+                continue;
+            };
+            node.set_pivot_offset(Vector2::new(size / 2.0, size / 2.0));
+            tween.set_parallel();
+            tween.tween_property(&node, "position", &target.to_variant(), duration);
+            tween.tween_property(&node, "rotation", &turn, duration);
+
+            // Symbols have no facing, so they snap back upright once in place
+            tween.chain();
+            tween.tween_property(&node, "rotation", &Variant::from(0.0), 0.0);
+        }
+
+        if self.overlays.iter().any(Option::is_some) {
+            if let Some(mut tween) = self.create_tween() {
+                tween.set_parallel();
+                for (idx, overlay) in self.overlays.iter().enumerate() {
+                    let Some(overlay) = overlay else {
+                        continue;
+                    };
+                    let pos = Vector2i::new((idx % self.width) as i32, (idx / self.width) as i32);
+                    tween.tween_property(overlay, "position", &self.cell_position(pos).to_variant(), duration);
+                }
+            }
+        }
+        self.call_after(duration, on_complete);
+    }
+
+    /// Recolor every node through each grid in turn, `frame_duration` apiece, then call `on_complete`;
+    /// nodes over cells empty in a frame fade out. Nodes are not moved or created, so rebuild afterwards
+    pub fn animate_frames(&mut self, frames: &[Grid], frame_duration: f64, on_complete: &Callable) {
+        if !frames.is_empty() {
+            for idx in 0..self.nodes.len() {
+                let Some(node) = self.nodes[idx].clone() else {
+                    continue;
+                };
+                let pos = Vector2i::new((idx % self.width) as i32, (idx / self.width) as i32);
+                let rest = self.cell_position(pos);
+                let Some(mut tween) = self.animate_node(&node, AnimationKind::Frames, Some(rest)) else {
+                    continue;
+                };
+                for frame in frames {
+                    let symbol = frame.cells.get(idx).and_then(|cell| cell.as_ref());
+                    let color = symbol.map_or(Color::TRANSPARENT_BLACK, |symbol| symbol.current_color());
+                    tween.tween_property(&node, "color", &color.to_variant(), frame_duration);
+                }
+            }
+        }
+        self.call_after(frames.len() as f64 * frame_duration, on_complete);
+    }
+
+    /// Apply the rotated colors and reset the spin angle
+    pub fn finish_rotation(&mut self, grid: &Grid) {
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                let Some(symbol) = grid.get(x, y) else {
+                    continue;
+                };
+                let Some(idx) = self.index(Vector2i::new(x as i32, y as i32)) else {
+                    continue;
+                };
+                if let Some(node) = self.nodes[idx].as_mut() {
+                    node.set_color(symbol.current_color());
+                    node.set_rotation(0.0);
+                }
+            }
+        }
+    }
+}
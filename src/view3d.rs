@@ -0,0 +1,274 @@
+//! 3D board view - the same board drawn as tiles in a 3D scene
+//!
+//! `GameBoard3D` hosts a hidden `GameBoard` that runs all of the game logic
+//! and mirrors its grid as one box tile per cell, lying on the node's XZ
+//! plane. Rotations flip every tile a quarter turn. Clicks are picked against
+//! the board plane through the current camera and sent to the board as
+//! selections and swaps.
+
+use crate::board::GameBoard;
+use crate::symbols::Grid;
+use godot::classes::{
+    BoxMesh, Camera3D, INode3D, InputEvent, InputEventMouseButton, MeshInstance3D, Node3D, StandardMaterial3D,
+};
+use godot::global::MouseButton;
+use godot::prelude::*;
+
+/// Height a selected tile is raised by, as a fraction of the tile height
+const SELECTED_LIFT: f32 = 0.5;
+
+/// A match-3 board drawn in 3D
+#[derive(GodotClass)]
+#[class(base=Node3D)]
+pub struct GameBoard3D {
+    base: Base<Node3D>,
+
+    /// Distance between neighboring tile centers
+    #[export(range = (0.1, 10.0, 0.05, or_greater))]
+    cell_spacing: f32,
+
+    /// Tile size as a fraction of the spacing
+    #[export(range = (0.1, 1.0, 0.05))]
+    tile_size: f32,
+
+    /// Tile thickness
+    #[export(range = (0.01, 2.0, 0.01))]
+    tile_height: f32,
+
+    /// The hidden board running the game
+    board: Option<Gd<GameBoard>>,
+
+    /// One tile per cell, row by row
+    tiles: Vec<Gd<MeshInstance3D>>,
+
+    /// Color each tile shows; None for an empty cell
+    shown: Vec<Option<Color>>,
+
+    /// Grid size the tiles were built for
+    size: (usize, usize),
+
+    /// Cell picked first for a swap
+    selected: Option<Vector2i>,
+
+    /// How long the board's rotation spin takes, read while the board is not busy
+    rotation_duration: f64,
+}
+
+#[godot_api]
+impl INode3D for GameBoard3D {
+    fn init(base: Base<Node3D>) -> Self {
+        Self {
+            base,
+            cell_spacing: 1.1,
+            tile_size: 0.9,
+            tile_height: 0.25,
+            board: None,
+            tiles: Vec::new(),
+            shown: Vec::new(),
+            size: (0, 0),
+            selected: None,
+            rotation_duration: 0.3,
+        }
+    }
+
+    fn ready(&mut self) {
+        // Host a board placed under this node, or a new one; it never draws or reads input itself
+        let existing = self.base().get_children().iter_shared().find_map(|child| child.try_cast::<GameBoard>().ok());
+        let mut board = match existing {
+            Some(board) => board,
+            None => {
+                let mut board = GameBoard::new_alloc();
+                board.set_name("Board");
+                self.base_mut().add_child(&board);
+                board
+            }
+        };
+        board.set_visible(false);
+        board.set_process_input(false);
+
+        let on_rotation = self.base().callable("on_rotation_triggered");
+        board.connect("rotation_triggered", &on_rotation);
+        self.board = Some(board);
+        self.sync_tiles();
+    }
+
+    fn process(&mut self, _delta: f64) {
+        self.sync_tiles();
+    }
+
+    fn unhandled_input(&mut self, event: Gd<InputEvent>) {
+        let Ok(click) = event.try_cast::<InputEventMouseButton>() else {
+            return;
+        };
+        if !click.is_pressed() || click.get_button_index() != MouseButton::LEFT {
+            return;
+        }
+        let Some(camera) = self.base().get_viewport().and_then(|viewport| viewport.get_camera_3d()) else {
+            return;
+        };
+        if let Some(pos) = self.pick_cell(&camera, click.get_position()) {
+            self.click_cell(pos);
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+        }
+    }
+}
+
+#[godot_api]
+impl GameBoard3D {
+    /// Get the hosted board, for its signals and game functions
+    #[func]
+    fn get_board(&self) -> Option<Gd<GameBoard>> {
+        self.board.clone()
+    }
+
+    /// Get the cell under a screen position as seen through `camera`, or (-1, -1) off the board
+    #[func]
+    fn cell_at_screen(&self, camera: Gd<Camera3D>, screen_pos: Vector2) -> Vector2i {
+        self.pick_cell(&camera, screen_pos).unwrap_or(Vector2i::new(-1, -1))
+    }
+
+    /// Flip every tile a quarter turn along with the board's rotation
+    #[func]
+    fn on_rotation_triggered(&mut self, _tick: i64) {
+        // The board is mid-command here, so use the duration read on the last sync
+        let duration = self.rotation_duration;
+        let Some(mut tween) = self.base_mut().create_tween() else {
+            return;
+        };
+        tween.set_parallel();
+        for tile in &self.tiles {
+            tween.tween_property(tile, "rotation:x", &Variant::from(std::f64::consts::FRAC_PI_2), duration);
+        }
+
+        // The board has rotated its grid by now; the new colors show as the tiles snap back flat
+        tween.chain();
+        for tile in &self.tiles {
+            tween.tween_property(tile, "rotation:x", &Variant::from(0.0), 0.0);
+        }
+    }
+
+    /// Rebuild the tiles if the grid size changed and recolor the ones whose cell changed
+    fn sync_tiles(&mut self) {
+        let Some(board) = self.board.clone() else {
+            return;
+        };
+        let board = board.bind();
+        self.rotation_duration = board.rotation_time();
+        let grid = board.grid();
+
+        if self.size != (grid.width, grid.height) {
+            self.rebuild_tiles(grid);
+        }
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                let idx = y * grid.width + x;
+                let color = grid.get(x, y).map(|symbol| symbol.current_color());
+                if self.shown[idx] != color {
+                    self.shown[idx] = color;
+                    self.paint_tile(idx, color);
+                }
+            }
+        }
+    }
+
+    /// Free the old tiles and create one per cell of the grid
+    fn rebuild_tiles(&mut self, grid: &Grid) {
+        for mut tile in self.tiles.drain(..) {
+            tile.queue_free();
+        }
+        self.size = (grid.width, grid.height);
+        self.shown = vec![None; grid.width * grid.height];
+        self.selected = None;
+
+        let mut mesh = BoxMesh::new_gd();
+        let side = self.cell_spacing * self.tile_size;
+        mesh.set_size(Vector3::new(side, self.tile_height, side));
+
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                let mut tile = MeshInstance3D::new_alloc();
+                tile.set_mesh(&mesh);
+                tile.set_material_override(&StandardMaterial3D::new_gd());
+                tile.set_position(self.tile_position(Vector2i::new(x as i32, y as i32)));
+                tile.set_visible(false);
+                self.base_mut().add_child(&tile);
+                self.tiles.push(tile);
+            }
+        }
+    }
+
+    /// Show a tile in a color, or hide it for an empty cell
+    fn paint_tile(&mut self, idx: usize, color: Option<Color>) {
+        let tile = &mut self.tiles[idx];
+        tile.set_visible(color.is_some());
+        let Some(color) = color else {
+            return;
+        };
+        let material = tile.get_material_override().and_then(|material| material.try_cast::<StandardMaterial3D>().ok());
+        if let Some(mut material) = material {
+            material.set_albedo(color);
+        }
+    }
+
+    /// Get a tile's resting position, with the grid centered on the node
+    fn tile_position(&self, pos: Vector2i) -> Vector3 {
+        let (width, height) = (self.size.0 as f32, self.size.1 as f32);
+        Vector3::new(
+            (pos.x as f32 - (width - 1.0) / 2.0) * self.cell_spacing,
+            0.0,
+            (pos.y as f32 - (height - 1.0) / 2.0) * self.cell_spacing,
+        )
+    }
+
+    /// Find the cell under a screen position by casting the camera ray onto the board plane
+    fn pick_cell(&self, camera: &Gd<Camera3D>, screen_pos: Vector2) -> Option<Vector2i> {
+        let to_local = self.base().get_global_transform().affine_inverse();
+        let origin = to_local * camera.project_ray_origin(screen_pos);
+        let direction = to_local.basis * camera.project_ray_normal(screen_pos);
+        if direction.y.abs() < f32::EPSILON {
+            return None;
+        }
+        let distance = -origin.y / direction.y;
+        if distance < 0.0 {
+            return None;
+        }
+
+        let hit = origin + direction * distance;
+        let (width, height) = (self.size.0 as f32, self.size.1 as f32);
+        let x = (hit.x / self.cell_spacing + width / 2.0).floor();
+        let y = (hit.z / self.cell_spacing + height / 2.0).floor();
+        (x >= 0.0 && y >= 0.0 && x < width && y < height).then(|| Vector2i::new(x as i32, y as i32))
+    }
+
+    /// Select a cell, or swap the selected cell with a neighbor
+    fn click_cell(&mut self, pos: Vector2i) {
+        let Some(mut board) = self.board.clone() else {
+            return;
+        };
+        match self.selected.take() {
+            Some(from) if (from.x - pos.x).abs() + (from.y - pos.y).abs() == 1 => {
+                self.lift_tile(from, false);
+                board.call("request_swap", &[from.to_variant(), pos.to_variant()]);
+            }
+            Some(from) => self.lift_tile(from, false),
+            None => {
+                self.selected = Some(pos);
+                self.lift_tile(pos, true);
+            }
+        }
+    }
+
+    /// Raise or lower a tile to show it is selected
+    fn lift_tile(&mut self, pos: Vector2i, lifted: bool) {
+        let mut position = self.tile_position(pos);
+        if lifted {
+            position.y += self.tile_height * SELECTED_LIFT;
+        }
+        let idx = pos.y as usize * self.size.0 + pos.x as usize;
+        if let Some(tile) = self.tiles.get_mut(idx) {
+            tile.set_position(position);
+        }
+    }
+}